@@ -1,9 +1,6 @@
 extern crate snoop;
 
-use snoop::read::Reader;
-use std::fs;
-use std::fs::File;
-use std::io::BufReader;
+use snoop::multi::MultiReader;
 use std::time::Instant;
 
 /// cargo run --example bulk -- snoop_files/
@@ -11,24 +8,17 @@ use std::time::Instant;
 /// read a bulk of files in snoop_files and get a copy of this data
 fn main() {
     let start = Instant::now();
-    let paths = fs::read_dir(std::env::args().nth(1).unwrap()).expect("no path given");
+    let mut reader =
+        MultiReader::from_dir(std::env::args().nth(1).expect("no path given")).unwrap();
     let mut cnt = 0u128;
-    for path in paths {
-        let p = path.unwrap().path();
-        println!("read file: {}", p.display());
-
-        let fp = match File::open(p) {
-            Ok(f) => f,
-            Err(e) => {
-                println!("File Error: {}", e);
-                return;
-            }
-        };
-
-        for i in Reader::new(BufReader::new(fp)).unwrap() {
-            cnt += 1;
-            let _packet = i.unwrap();
+    let mut file = None;
+    while let Some(i) = reader.next() {
+        let (idx, _packet) = i.unwrap();
+        if file != Some(idx) {
+            println!("read file: {}", reader.path(idx).unwrap().display());
+            file = Some(idx);
         }
+        cnt += 1;
     }
 
     println!("read packets: {} in {:?}", cnt, start.elapsed());
@@ -26,7 +26,6 @@ fn main() {
     let stream = stream.read_stream(time).unwrap();
     println!(
         "read stream packet: \n{:#?}\ndata: {:x?}\n",
-        &stream.header,
-        &stream.data[..]
+        &stream.header, stream.data
     );
 }
@@ -1,6 +1,5 @@
 extern crate snoop;
 
-use snoop::format::DataLinkType;
 use snoop::read::Reader;
 use snoop::write::Writer;
 use std::fs::File;
@@ -29,8 +28,9 @@ fn main() {
             return;
         }
     };
-    let mut writer = Writer::new(BufWriter::new(out), DataLinkType::Ethernet).unwrap();
-    for i in Reader::new(BufReader::new(fp)).unwrap() {
+    let reader = Reader::new(BufReader::new(fp)).unwrap();
+    let mut writer = Writer::new(BufWriter::new(out), reader.header().link_type).unwrap();
+    for i in reader {
         let packet = i.unwrap();
         writer.write_packet(&packet).unwrap();
     }
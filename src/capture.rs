@@ -0,0 +1,209 @@
+//! generic capture sources and sinks independent of the capture file format.
+//!
+//! tools written against [`CaptureSource`] and [`CaptureSink`] work with snoop
+//! and with every other implemented format.
+use crate::format::{DataLinkType, PacketHeader, SnoopPacket, SnoopPacketRef, MAX_CAPTURE_LEN};
+use crate::parse::Parser;
+use crate::Error;
+use std::time::Duration;
+
+/// captured packet independent of the capture format.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Packet {
+    /// time since the unix epoch when the packet was captured
+    pub timestamp: Duration,
+    /// length of the packet on the wire
+    pub original_length: u32,
+    /// cumulative number of dropped packets before this packet, 0 if the format has no counter
+    pub drops: u32,
+    /// captured packet data
+    pub data: Vec<u8>,
+}
+
+impl TryFrom<SnoopPacket> for Packet {
+    type Error = Error;
+
+    /// convert a snoop packet, fails with [`Error::InvalidMicroseconds`] on an invalid timestamp.
+    fn try_from(packet: SnoopPacket) -> Result<Self, Error> {
+        Ok(Packet {
+            timestamp: packet.header.timestamp()?,
+            original_length: packet.header.original_length,
+            drops: packet.header.cumulative_drops,
+            data: packet.data,
+        })
+    }
+}
+
+impl TryFrom<&SnoopPacketRef<'_>> for Packet {
+    type Error = Error;
+
+    /// convert a snoop packet, fails with [`Error::InvalidMicroseconds`] on an invalid timestamp.
+    fn try_from(packet: &SnoopPacketRef) -> Result<Self, Error> {
+        Ok(Packet {
+            timestamp: packet.header.timestamp()?,
+            original_length: packet.header.original_length,
+            drops: packet.header.cumulative_drops,
+            data: packet.data.to_vec(),
+        })
+    }
+}
+
+impl TryFrom<Packet> for SnoopPacket {
+    type Error = Error;
+
+    /// convert to a snoop packet, data longer than the snoop capture len is truncated.
+    fn try_from(mut packet: Packet) -> Result<Self, Error> {
+        let header = packet_header(&packet)?;
+        packet.data.truncate(header.included_length as usize);
+        Ok(SnoopPacket {
+            header,
+            data: packet.data,
+        })
+    }
+}
+
+/// a source of captured packets like a file reader.
+pub trait CaptureSource {
+    /// link type of the captured packets
+    fn link_type(&self) -> DataLinkType;
+
+    /// read the next packet, `None` at a valid end of the capture.
+    fn next_packet(&mut self) -> Option<Result<Packet, Error>>;
+
+    /// iterate over the remaining packets.
+    fn packets(&mut self) -> Packets<'_, Self>
+    where
+        Self: Sized,
+    {
+        Packets { source: self }
+    }
+}
+
+/// a sink for captured packets like a file writer.
+pub trait CaptureSink {
+    /// link type of the written packets
+    fn link_type(&self) -> DataLinkType;
+
+    /// write a packet.
+    /// # Errors
+    /// will return [`Error`] if the packet can not be written.
+    fn write_capture(&mut self, packet: &Packet) -> Result<(), Error>;
+
+    /// flush buffered packets.
+    /// # Errors
+    /// will return [`Error::Io`] if something unexpected happen.
+    fn flush_capture(&mut self) -> Result<(), Error>;
+}
+
+/// iterator over the packets of a [`CaptureSource`].
+#[derive(Debug)]
+pub struct Packets<'a, S: ?Sized> {
+    source: &'a mut S,
+}
+
+impl<S> Iterator for Packets<'_, S>
+where
+    S: CaptureSource + ?Sized,
+{
+    type Item = Result<Packet, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.source.next_packet()
+    }
+}
+
+impl<S> CaptureSource for Box<S>
+where
+    S: CaptureSource + ?Sized,
+{
+    fn link_type(&self) -> DataLinkType {
+        (**self).link_type()
+    }
+
+    fn next_packet(&mut self) -> Option<Result<Packet, Error>> {
+        (**self).next_packet()
+    }
+}
+
+impl<S> CaptureSink for Box<S>
+where
+    S: CaptureSink + ?Sized,
+{
+    fn link_type(&self) -> DataLinkType {
+        (**self).link_type()
+    }
+
+    fn write_capture(&mut self, packet: &Packet) -> Result<(), Error> {
+        (**self).write_capture(packet)
+    }
+
+    fn flush_capture(&mut self) -> Result<(), Error> {
+        (**self).flush_capture()
+    }
+}
+
+/// copy all packets from a source to a sink and return the number of copied packets.
+/// # Errors
+/// will return [`Error`] if a packet can not be read or written.
+pub fn copy<S, K>(source: &mut S, sink: &mut K) -> Result<u64, Error>
+where
+    S: CaptureSource + ?Sized,
+    K: CaptureSink + ?Sized,
+{
+    let mut cnt = 0;
+    while let Some(packet) = source.next_packet() {
+        sink.write_capture(&packet?)?;
+        cnt += 1;
+    }
+    sink.flush_capture()?;
+    Ok(cnt)
+}
+
+/// build the snoop packet header of a packet, data longer than the snoop capture len is truncated.
+pub(crate) fn packet_header(packet: &Packet) -> Result<PacketHeader, Error> {
+    #[allow(clippy::cast_possible_truncation)]
+    let included_length = packet.data.len().min(MAX_CAPTURE_LEN as usize) as u32;
+    Ok(PacketHeader {
+        original_length: packet.original_length.max(included_length),
+        included_length,
+        packet_record_length: Parser::record_len(included_length),
+        cumulative_drops: packet.drops,
+        timestamp_seconds: u32::try_from(packet.timestamp.as_secs())
+            .map_err(|_| Error::TimeEpoch)?,
+        timestamp_microseconds: packet.timestamp.subsec_micros(),
+    })
+}
+
+#[cfg(feature = "read")]
+impl<R> CaptureSource for crate::read::Reader<R>
+where
+    R: std::io::Read,
+{
+    fn link_type(&self) -> DataLinkType {
+        self.header().link_type
+    }
+
+    fn next_packet(&mut self) -> Option<Result<Packet, Error>> {
+        self.iter_ref()
+            .map(|r| r.and_then(|packet| Packet::try_from(&packet)))
+    }
+}
+
+#[cfg(feature = "write")]
+impl<W> CaptureSink for crate::write::Writer<W>
+where
+    W: std::io::Write,
+{
+    fn link_type(&self) -> DataLinkType {
+        self.header().link_type
+    }
+
+    fn write_capture(&mut self, packet: &Packet) -> Result<(), Error> {
+        let header = packet_header(packet)?;
+        self.write_record(&header, &packet.data[..header.included_length as usize])
+    }
+
+    fn flush_capture(&mut self) -> Result<(), Error> {
+        self.flush()
+    }
+}
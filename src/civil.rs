@@ -0,0 +1,108 @@
+//! civil calendar calculations on unix timestamps in utc.
+//!
+//! based on the days from civil algorithms of Howard Hinnant,
+//! see <http://howardhinnant.github.io/date_algorithms.html>.
+
+#[cfg(feature = "write")]
+use std::fmt::Write;
+
+const SECONDS_PER_DAY: u64 = 86_400;
+
+/// broken down utc date and time of a unix timestamp.
+#[derive(Debug, Default, PartialEq, Clone, Copy)]
+pub(crate) struct DateTime {
+    pub(crate) year: i64,
+    pub(crate) month: u32,
+    pub(crate) day: u32,
+    pub(crate) hour: u32,
+    pub(crate) minute: u32,
+    pub(crate) second: u32,
+}
+
+impl DateTime {
+    /// convert seconds since the unix epoch into a utc date and time.
+    #[allow(clippy::cast_possible_truncation, clippy::cast_possible_wrap)]
+    pub(crate) fn from_unix(secs: u64) -> Self {
+        let days = (secs / SECONDS_PER_DAY) as i64;
+        let rem = (secs % SECONDS_PER_DAY) as u32;
+        let (year, month, day) = civil_from_days(days);
+        Self {
+            year,
+            month,
+            day,
+            hour: rem / 3600,
+            minute: rem / 60 % 60,
+            second: rem % 60,
+        }
+    }
+
+    /// day of the year, starting with 1 at the first of january.
+    #[cfg(feature = "write")]
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    pub(crate) fn year_day(&self) -> u32 {
+        (days_from_civil(self.year, self.month, self.day) - days_from_civil(self.year, 1, 1)) as u32
+            + 1
+    }
+}
+
+/// days since the unix epoch of the given civil date.
+#[cfg(feature = "write")]
+#[allow(clippy::cast_possible_wrap)]
+pub(crate) fn days_from_civil(year: i64, month: u32, day: u32) -> i64 {
+    let y = if month <= 2 { year - 1 } else { year };
+    let era = y.div_euclid(400);
+    let yoe = y - era * 400;
+    let mp = (i64::from(month) + 9) % 12;
+    let doy = (153 * mp + 2) / 5 + i64::from(day) - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146_097 + doe - 719_468
+}
+
+/// civil date as (year, month, day) of the given days since the unix epoch.
+#[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+pub(crate) fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z - era * 146_097;
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    (year, month, day)
+}
+
+/// expand a `strftime` style template with the utc time of `secs`.
+///
+/// supported conversions are `%Y`, `%y`, `%m`, `%d`, `%H`, `%M`, `%S`, `%j`,
+/// `%s` (seconds since the epoch) and `%%`. the non standard `%n` expands to
+/// the given sequence number. unknown conversions are copied as they are.
+#[cfg(feature = "write")]
+pub(crate) fn strftime(template: &str, secs: u64, seq: u64) -> String {
+    let dt = DateTime::from_unix(secs);
+    let mut out = String::with_capacity(template.len() + 16);
+    let mut chars = template.chars();
+    while let Some(c) = chars.next() {
+        if c != '%' {
+            out.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('Y') => write!(out, "{:04}", dt.year),
+            Some('y') => write!(out, "{:02}", dt.year.rem_euclid(100)),
+            Some('m') => write!(out, "{:02}", dt.month),
+            Some('d') => write!(out, "{:02}", dt.day),
+            Some('H') => write!(out, "{:02}", dt.hour),
+            Some('M') => write!(out, "{:02}", dt.minute),
+            Some('S') => write!(out, "{:02}", dt.second),
+            Some('j') => write!(out, "{:03}", dt.year_day()),
+            Some('s') => write!(out, "{secs}"),
+            Some('n') => write!(out, "{seq}"),
+            Some('%') | None => out.write_char('%'),
+            Some(o) => write!(out, "%{o}"),
+        }
+        .expect("write to string");
+    }
+    out
+}
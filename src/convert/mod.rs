@@ -0,0 +1,82 @@
+//! convert between snoop and other capture file formats like pcap and pcapng.
+//!
+//! link types are mapped between [`DataLinkType`] and the `LINKTYPE_*` values
+//! used by libpcap. snoop link types without a pcap equivalent can not be converted.
+use crate::format::{DataLinkType, PacketHeader, SnoopPacket, MAX_CAPTURE_LEN};
+use crate::parse::Parser;
+
+pub(crate) use crate::read::read_full;
+
+pub mod pcap;
+pub mod pcapng;
+
+/// `LINKTYPE_ETHERNET`, IEEE 802.3 Ethernet
+pub const LINKTYPE_ETHERNET: u32 = 1;
+/// `LINKTYPE_IEEE802_5`, IEEE 802.5 Token Ring
+pub const LINKTYPE_IEEE802_5: u32 = 6;
+/// `LINKTYPE_FDDI`, FDDI as specified by ANSI INCITS 239-1994
+pub const LINKTYPE_FDDI: u32 = 10;
+/// `LINKTYPE_C_HDLC`, Cisco HDLC
+pub const LINKTYPE_C_HDLC: u32 = 104;
+
+/// counters of a conversion into snoop.
+#[derive(Debug, Default, PartialEq, Eq, Copy, Clone)]
+pub struct ConvertStats {
+    /// number of written packets
+    pub packets: u64,
+    /// number of written packets whose data was cut to the snoop capture length
+    pub truncated: u64,
+    /// number of packets which are not written because their link type has no snoop equivalent
+    pub skipped: u64,
+}
+
+/// map a snoop link type to a pcap `LINKTYPE_*` value.
+/// returns `None` if there is no pcap equivalent.
+#[must_use]
+pub fn to_linktype(link_type: DataLinkType) -> Option<u32> {
+    match link_type {
+        DataLinkType::Ethernet | DataLinkType::IEEE8023 => Some(LINKTYPE_ETHERNET),
+        DataLinkType::TokenRing => Some(LINKTYPE_IEEE802_5),
+        DataLinkType::Fddi => Some(LINKTYPE_FDDI),
+        DataLinkType::Hdlc => Some(LINKTYPE_C_HDLC),
+        _ => None,
+    }
+}
+
+/// map a pcap `LINKTYPE_*` value to a snoop link type.
+/// returns `None` if there is no snoop equivalent.
+#[must_use]
+pub fn from_linktype(linktype: u32) -> Option<DataLinkType> {
+    match linktype {
+        LINKTYPE_ETHERNET => Some(DataLinkType::Ethernet),
+        LINKTYPE_IEEE802_5 => Some(DataLinkType::TokenRing),
+        LINKTYPE_FDDI => Some(DataLinkType::Fddi),
+        LINKTYPE_C_HDLC => Some(DataLinkType::Hdlc),
+        _ => None,
+    }
+}
+
+/// build a snoop packet from foreign capture data.
+/// data longer than [`MAX_CAPTURE_LEN`] is truncated, the original length is kept.
+pub(crate) fn snoop_packet(
+    timestamp_seconds: u32,
+    timestamp_microseconds: u32,
+    original_length: u32,
+    cumulative_drops: u32,
+    mut data: Vec<u8>,
+) -> SnoopPacket {
+    data.truncate(MAX_CAPTURE_LEN as usize);
+    #[allow(clippy::cast_possible_truncation)]
+    let included_length = data.len() as u32;
+    SnoopPacket {
+        header: PacketHeader {
+            original_length: original_length.max(included_length),
+            included_length,
+            packet_record_length: Parser::record_len(included_length),
+            cumulative_drops,
+            timestamp_seconds,
+            timestamp_microseconds,
+        },
+        data,
+    }
+}
@@ -0,0 +1,344 @@
+//! read and write classic libpcap files and convert them from and to snoop.
+//!
+//! the reader supports both byte orders with microsecond and nanosecond timestamps.
+//! the writer creates little-endian files with microsecond timestamps.
+use crate::capture::{CaptureSink, CaptureSource, Packet};
+use crate::convert::{from_linktype, read_full, snoop_packet, to_linktype, ConvertStats};
+use crate::format::{DataLinkType, SnoopPacket, MAX_CAPTURE_LEN};
+use crate::read::Reader;
+use crate::write::Writer;
+use crate::Error;
+use std::io::{Read, Write};
+use std::time::Duration;
+
+/// magic number of a pcap file with microsecond timestamps
+pub const PCAP_MAGIC_MICROS: u32 = 0xa1b2_c3d4;
+/// magic number of a pcap file with nanosecond timestamps
+pub const PCAP_MAGIC_NANOS: u32 = 0xa1b2_3c4d;
+/// size of the pcap file header
+pub const PCAP_HEADER_SIZE: usize = 24;
+/// size of the pcap packet header
+pub const PCAP_PACKET_HEADER_SIZE: usize = 16;
+/// snaplen written into the header of new pcap files
+pub const PCAP_SNAPLEN: u32 = 65535;
+/// largest packet record accepted by the reader, like libpcap
+const MAX_PCAP_CAPTURE_LEN: u32 = 0x0400_0000;
+
+/// representing the pcap file header.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct PcapHeader {
+    /// major version, 2 for all known files
+    pub version_major: u16,
+    /// minor version, 4 for all known files
+    pub version_minor: u16,
+    /// maximum captured length of a packet
+    pub snaplen: u32,
+    /// `LINKTYPE_*` value of the captured link
+    pub linktype: u32,
+    /// packet timestamps have nanosecond instead of microsecond resolution
+    pub nanoseconds: bool,
+    /// the file is stored in big-endian byte order
+    pub big_endian: bool,
+}
+
+/// represents a captured pcap packet with header fields and data.
+#[derive(Debug, Default, Clone)]
+pub struct PcapPacket {
+    /// seconds since the epoch
+    pub timestamp_seconds: u32,
+    /// nanoseconds of the timestamp, microsecond files are scaled up
+    pub timestamp_nanoseconds: u32,
+    /// length of the packet on the wire
+    pub original_length: u32,
+    /// captured packet data
+    pub data: Vec<u8>,
+}
+
+impl From<&SnoopPacket> for PcapPacket {
+    fn from(packet: &SnoopPacket) -> Self {
+        PcapPacket {
+            timestamp_seconds: packet.header.timestamp_seconds,
+            timestamp_nanoseconds: packet.header.timestamp_microseconds.saturating_mul(1000),
+            original_length: packet.header.original_length,
+            data: packet.data.clone(),
+        }
+    }
+}
+
+impl From<PcapPacket> for SnoopPacket {
+    /// convert to a snoop packet, data longer than the snoop capture len is truncated.
+    fn from(packet: PcapPacket) -> Self {
+        snoop_packet(
+            packet.timestamp_seconds,
+            packet.timestamp_nanoseconds / 1000,
+            packet.original_length,
+            0,
+            packet.data,
+        )
+    }
+}
+
+/// reader to read packets from a pcap file or buffer.
+#[derive(Debug)]
+pub struct PcapReader<R> {
+    r: R,
+    header: PcapHeader,
+}
+
+impl<R> PcapReader<R>
+where
+    R: Read,
+{
+    /// create a new pcap reader and parse the pcap file header.
+    /// # Errors
+    /// will return [`Error::InvalidFormat`] if no pcap magic is present at the beginning
+    pub fn new(mut r: R) -> Result<Self, Error> {
+        let mut buf = [0u8; PCAP_HEADER_SIZE];
+        read_full(&mut r, &mut buf)?;
+        let magic = [buf[0], buf[1], buf[2], buf[3]];
+        let (nanoseconds, big_endian) = if magic == PCAP_MAGIC_MICROS.to_be_bytes() {
+            (false, true)
+        } else if magic == PCAP_MAGIC_MICROS.to_le_bytes() {
+            (false, false)
+        } else if magic == PCAP_MAGIC_NANOS.to_be_bytes() {
+            (true, true)
+        } else if magic == PCAP_MAGIC_NANOS.to_le_bytes() {
+            (true, false)
+        } else {
+            return Err(Error::InvalidFormat("unknown pcap magic"));
+        };
+        let u16_at = |i: usize| {
+            let b = [buf[i], buf[i + 1]];
+            if big_endian {
+                u16::from_be_bytes(b)
+            } else {
+                u16::from_le_bytes(b)
+            }
+        };
+        let header = PcapHeader {
+            version_major: u16_at(4),
+            version_minor: u16_at(6),
+            snaplen: u32_at(&buf, 16, big_endian),
+            linktype: u32_at(&buf, 20, big_endian) & 0xFFFF,
+            nanoseconds,
+            big_endian,
+        };
+        Ok(Self { r, header })
+    }
+
+    /// get a reference to the pcap file header
+    pub fn header(&self) -> &PcapHeader {
+        &self.header
+    }
+
+    /// read the next packet.
+    /// # Errors
+    /// will return [`Error::Eof`] at the end of the file and [`Error`] if something unexpected happen.
+    pub fn read(&mut self) -> Result<PcapPacket, Error> {
+        let mut buf = [0u8; PCAP_PACKET_HEADER_SIZE];
+        read_full(&mut self.r, &mut buf)?;
+        let be = self.header.big_endian;
+        let fraction = u32_at(&buf, 4, be);
+        let included_length = u32_at(&buf, 8, be);
+        if included_length > MAX_PCAP_CAPTURE_LEN {
+            return Err(Error::InvalidFormat("pcap record too large"));
+        }
+        let mut data = vec![0u8; included_length as usize];
+        match read_full(&mut self.r, &mut data) {
+            Err(Error::Eof) => return Err(Error::UnexpectedEof(0)),
+            r => r?,
+        }
+        Ok(PcapPacket {
+            timestamp_seconds: u32_at(&buf, 0, be),
+            timestamp_nanoseconds: if self.header.nanoseconds {
+                fraction
+            } else {
+                fraction.saturating_mul(1000)
+            },
+            original_length: u32_at(&buf, 12, be),
+            data,
+        })
+    }
+}
+
+impl<R> Iterator for PcapReader<R>
+where
+    R: Read,
+{
+    type Item = Result<PcapPacket, Error>;
+
+    /// iterate over packets inside a pcap file until a valid eof or error occurs.
+    fn next(&mut self) -> Option<Self::Item> {
+        match self.read() {
+            Ok(packet) => Some(Ok(packet)),
+            Err(Error::Eof) => None,
+            Err(e) => Some(Err(e)),
+        }
+    }
+}
+
+/// writer to write packets as little-endian pcap file with microsecond timestamps.
+#[derive(Debug)]
+pub struct PcapWriter<W: Write> {
+    w: W,
+    linktype: u32,
+}
+
+impl<W> PcapWriter<W>
+where
+    W: Write,
+{
+    /// create a new pcap writer and write the pcap file header.
+    /// # Errors
+    /// will return [`Error::Io`] if something unexpected happen.
+    pub fn new(mut w: W, linktype: u32) -> Result<Self, Error> {
+        let mut buf = [0u8; PCAP_HEADER_SIZE];
+        buf[0..4].copy_from_slice(&PCAP_MAGIC_MICROS.to_le_bytes());
+        buf[4..6].copy_from_slice(&2u16.to_le_bytes());
+        buf[6..8].copy_from_slice(&4u16.to_le_bytes());
+        buf[16..20].copy_from_slice(&PCAP_SNAPLEN.to_le_bytes());
+        buf[20..24].copy_from_slice(&linktype.to_le_bytes());
+        w.write_all(&buf).map_err(Error::Io)?;
+        Ok(Self { w, linktype })
+    }
+
+    /// write a pcap packet.
+    /// # Errors
+    /// will return [`Error`] if something unexpected happen.
+    pub fn write_packet(&mut self, packet: &PcapPacket) -> Result<(), Error> {
+        self.write_record(
+            packet.timestamp_seconds,
+            packet.timestamp_nanoseconds,
+            packet.original_length,
+            &packet.data,
+        )
+    }
+
+    /// write the packet header and data.
+    fn write_record(
+        &mut self,
+        timestamp_seconds: u32,
+        timestamp_nanoseconds: u32,
+        original_length: u32,
+        data: &[u8],
+    ) -> Result<(), Error> {
+        let included_length = u32::try_from(data.len()).map_err(|_| Error::CaptureLenExceeded)?;
+        let mut buf = [0u8; PCAP_PACKET_HEADER_SIZE];
+        buf[0..4].copy_from_slice(&timestamp_seconds.to_le_bytes());
+        buf[4..8].copy_from_slice(&(timestamp_nanoseconds / 1000).to_le_bytes());
+        buf[8..12].copy_from_slice(&included_length.to_le_bytes());
+        buf[12..16].copy_from_slice(&original_length.max(included_length).to_le_bytes());
+        self.w.write_all(&buf).map_err(Error::Io)?;
+        self.w.write_all(data).map_err(Error::Io)
+    }
+
+    /// flush the underlying writer.
+    /// # Errors
+    /// will return [`Error::Io`] if something unexpected happen.
+    pub fn flush(&mut self) -> Result<(), Error> {
+        self.w.flush().map_err(Error::Io)
+    }
+
+    /// unwrap this writer and return the underlying writer.
+    pub fn into_inner(self) -> W {
+        self.w
+    }
+}
+
+/// convert a pcap file into a snoop file and return the counters of the conversion.
+/// packets longer than the snoop capture len are truncated and counted in [`ConvertStats::truncated`].
+/// # Errors
+/// will return [`Error::UnsupportedLinkType`] if the pcap link type has no snoop equivalent
+/// and [`Error`] if something unexpected happen.
+pub fn pcap_to_snoop<R: Read, W: Write>(r: R, w: W) -> Result<ConvertStats, Error> {
+    let reader = PcapReader::new(r)?;
+    let network = reader.header().linktype;
+    let link_type = from_linktype(network).ok_or(Error::UnsupportedLinkType(network))?;
+    let mut writer = Writer::new(w, link_type)?;
+    let mut stats = ConvertStats::default();
+    for packet in reader {
+        let packet = packet?;
+        if packet.data.len() > MAX_CAPTURE_LEN as usize {
+            stats.truncated += 1;
+        }
+        writer.write_packet(&SnoopPacket::from(packet))?;
+        stats.packets += 1;
+    }
+    writer.flush()?;
+    Ok(stats)
+}
+
+/// convert the packets of a snoop reader into a pcap file and return the number of converted packets.
+/// # Errors
+/// will return [`Error::UnsupportedLinkType`] if the snoop link type has no pcap equivalent
+/// and [`Error`] if something unexpected happen.
+pub fn snoop_to_pcap<R: Read, W: Write>(reader: &mut Reader<R>, w: W) -> Result<u64, Error> {
+    let link_type = reader.header().link_type;
+    let network = to_linktype(link_type).ok_or(Error::UnsupportedLinkType(link_type as u32))?;
+    let mut writer = PcapWriter::new(w, network)?;
+    let mut cnt = 0;
+    for packet in reader {
+        writer.write_packet(&PcapPacket::from(&packet?))?;
+        cnt += 1;
+    }
+    writer.flush()?;
+    Ok(cnt)
+}
+
+impl<R> CaptureSource for PcapReader<R>
+where
+    R: Read,
+{
+    /// link type of the pcap file, [`DataLinkType::Unassigned`] if there is no snoop equivalent
+    fn link_type(&self) -> DataLinkType {
+        from_linktype(self.header.linktype).unwrap_or_default()
+    }
+
+    fn next_packet(&mut self) -> Option<Result<Packet, Error>> {
+        self.next().map(|r| {
+            r.map(|packet| Packet {
+                timestamp: Duration::new(
+                    u64::from(packet.timestamp_seconds),
+                    packet.timestamp_nanoseconds,
+                ),
+                original_length: packet.original_length,
+                drops: 0,
+                data: packet.data,
+            })
+        })
+    }
+}
+
+impl<W> CaptureSink for PcapWriter<W>
+where
+    W: Write,
+{
+    /// link type of the pcap file, [`DataLinkType::Unassigned`] if there is no snoop equivalent
+    fn link_type(&self) -> DataLinkType {
+        from_linktype(self.linktype).unwrap_or_default()
+    }
+
+    fn write_capture(&mut self, packet: &Packet) -> Result<(), Error> {
+        let seconds = u32::try_from(packet.timestamp.as_secs()).map_err(|_| Error::TimeEpoch)?;
+        self.write_record(
+            seconds,
+            packet.timestamp.subsec_nanos(),
+            packet.original_length,
+            &packet.data,
+        )
+    }
+
+    fn flush_capture(&mut self) -> Result<(), Error> {
+        self.flush()
+    }
+}
+
+/// read a u32 at the given offset with the byte order of the file.
+fn u32_at(buf: &[u8], i: usize, big_endian: bool) -> u32 {
+    let b = [buf[i], buf[i + 1], buf[i + 2], buf[i + 3]];
+    if big_endian {
+        u32::from_be_bytes(b)
+    } else {
+        u32::from_le_bytes(b)
+    }
+}
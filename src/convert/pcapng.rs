@@ -0,0 +1,590 @@
+//! read and write pcapng files and convert them from and to snoop.
+//!
+//! the writer creates a little-endian section with one interface in microsecond resolution.
+//! snoop drop counters are stored as `epb_dropcount` option with the drops since the previous packet.
+//! the reader supports both byte orders, multiple sections and multiple interfaces.
+use crate::capture::{CaptureSink, CaptureSource, Packet};
+use crate::convert::{from_linktype, read_full, snoop_packet, to_linktype, ConvertStats};
+use crate::format::{DataLinkType, SnoopPacket, MAX_CAPTURE_LEN};
+use crate::read::Reader;
+use crate::write::Writer;
+use crate::Error;
+use std::io::{Read, Write};
+use std::time::Duration;
+
+/// block type of the section header block
+pub const SECTION_HEADER_BLOCK: u32 = 0x0A0D_0D0A;
+/// block type of the interface description block
+pub const INTERFACE_DESCRIPTION_BLOCK: u32 = 0x0000_0001;
+/// block type of the simple packet block
+pub const SIMPLE_PACKET_BLOCK: u32 = 0x0000_0003;
+/// block type of the enhanced packet block
+pub const ENHANCED_PACKET_BLOCK: u32 = 0x0000_0006;
+/// byte order magic inside the section header block
+pub const BYTE_ORDER_MAGIC: u32 = 0x1A2B_3C4D;
+
+const OPT_ENDOFOPT: u16 = 0;
+const OPT_IF_TSRESOL: u16 = 9;
+const OPT_EPB_DROPCOUNT: u16 = 4;
+/// default timestamp resolution of an interface, 10^-6 seconds
+const DEFAULT_TSRESOL: u8 = 6;
+/// largest block accepted by the reader
+const MAX_BLOCK_LEN: u32 = 0x0400_0000;
+
+/// description of a capture interface inside a pcapng section.
+#[derive(Debug, Clone, Copy)]
+pub struct Interface {
+    /// `LINKTYPE_*` value of the interface
+    pub linktype: u32,
+    /// maximum captured length of a packet, 0 if there is no limit
+    pub snaplen: u32,
+    /// `if_tsresol` of the interface, power of 10 or of 2 if the high bit is set.
+    /// the reader accepts up to 10^-19 and 2^-63, which fit into the 64 bit timestamp units
+    pub tsresol: u8,
+}
+
+/// represents a captured pcapng packet with the interface it was captured on.
+#[derive(Debug, Default, Clone)]
+pub struct PcapngPacket {
+    /// index of the interface inside the current section
+    pub interface_id: u32,
+    /// seconds since the epoch
+    pub timestamp_seconds: u64,
+    /// nanoseconds of the timestamp
+    pub timestamp_nanoseconds: u32,
+    /// length of the packet on the wire
+    pub original_length: u32,
+    /// packets dropped between the previous and this packet, from `epb_dropcount`
+    pub drops: Option<u64>,
+    /// captured packet data
+    pub data: Vec<u8>,
+}
+
+/// reader to read packets from a pcapng file or buffer.
+#[derive(Debug)]
+pub struct PcapngReader<R> {
+    r: R,
+    big_endian: bool,
+    interfaces: Vec<Interface>,
+    drops: u32,
+    pending: Option<PcapngPacket>,
+}
+
+impl<R> PcapngReader<R>
+where
+    R: Read,
+{
+    /// create a new pcapng reader and parse the first section header block
+    /// and the interface description blocks in front of the first packet.
+    /// # Errors
+    /// will return [`Error::InvalidFormat`] if the data not starts with a section header block
+    pub fn new(r: R) -> Result<Self, Error> {
+        let mut reader = Self {
+            r,
+            big_endian: false,
+            interfaces: Vec::new(),
+            drops: 0,
+            pending: None,
+        };
+        let mut buf = [0u8; 4];
+        read_full(&mut reader.r, &mut buf)?;
+        if u32::from_le_bytes(buf) != SECTION_HEADER_BLOCK {
+            return Err(Error::InvalidFormat("missing pcapng section header block"));
+        }
+        reader.read_section()?;
+        // read ahead until the first packet, so the interfaces are known
+        reader.pending = match reader.read() {
+            Ok(packet) => Some(packet),
+            Err(Error::Eof) => None,
+            Err(e) => return Err(e),
+        };
+        Ok(reader)
+    }
+
+    /// interfaces of the current section, indexed by the interface id of the packets
+    pub fn interfaces(&self) -> &[Interface] {
+        &self.interfaces
+    }
+
+    /// read the next packet, interface and section blocks are processed on the way.
+    /// # Errors
+    /// will return [`Error::Eof`] at the end of the file and [`Error`] if something unexpected happen.
+    pub fn read(&mut self) -> Result<PcapngPacket, Error> {
+        if let Some(packet) = self.pending.take() {
+            return Ok(packet);
+        }
+        loop {
+            let mut buf = [0u8; 4];
+            read_full(&mut self.r, &mut buf)?;
+            let block_type = self.u32(buf);
+            if block_type == SECTION_HEADER_BLOCK {
+                self.read_section()?;
+                continue;
+            }
+            let body = self.read_body()?;
+            match block_type {
+                INTERFACE_DESCRIPTION_BLOCK => self.parse_interface(&body)?,
+                ENHANCED_PACKET_BLOCK => return self.parse_enhanced_packet(&body),
+                SIMPLE_PACKET_BLOCK => return self.parse_simple_packet(&body),
+                _ => {}
+            }
+        }
+    }
+
+    /// read a section header block after its block type and reset the interfaces.
+    fn read_section(&mut self) -> Result<(), Error> {
+        let mut buf = [0u8; 8];
+        read_full(&mut self.r, &mut buf).map_err(truncated)?;
+        let magic = [buf[4], buf[5], buf[6], buf[7]];
+        self.big_endian = if magic == BYTE_ORDER_MAGIC.to_be_bytes() {
+            true
+        } else if magic == BYTE_ORDER_MAGIC.to_le_bytes() {
+            false
+        } else {
+            return Err(Error::InvalidFormat("unknown pcapng byte order magic"));
+        };
+        let total = self.u32([buf[0], buf[1], buf[2], buf[3]]);
+        if !(28..=MAX_BLOCK_LEN).contains(&total) || total & 3 != 0 {
+            return Err(Error::InvalidFormat("invalid pcapng block length"));
+        }
+        // skip version, section length, options and the trailing block length
+        let mut rest = vec![0u8; total as usize - 12];
+        read_full(&mut self.r, &mut rest).map_err(truncated)?;
+        self.interfaces.clear();
+        Ok(())
+    }
+
+    /// read the block body after the block type and return it without the length fields.
+    fn read_body(&mut self) -> Result<Vec<u8>, Error> {
+        let mut buf = [0u8; 4];
+        read_full(&mut self.r, &mut buf).map_err(truncated)?;
+        let total = self.u32(buf);
+        if !(12..=MAX_BLOCK_LEN).contains(&total) || total & 3 != 0 {
+            return Err(Error::InvalidFormat("invalid pcapng block length"));
+        }
+        let mut body = vec![0u8; total as usize - 8];
+        read_full(&mut self.r, &mut body).map_err(truncated)?;
+        body.truncate(total as usize - 12);
+        Ok(body)
+    }
+
+    fn parse_interface(&mut self, body: &[u8]) -> Result<(), Error> {
+        if body.len() < 8 {
+            return Err(Error::InvalidFormat(
+                "short pcapng interface description block",
+            ));
+        }
+        let mut interface = Interface {
+            linktype: u32::from(self.u16([body[0], body[1]])),
+            snaplen: self.u32_at(body, 4),
+            tsresol: DEFAULT_TSRESOL,
+        };
+        for (code, value) in self.options(&body[8..]) {
+            if code == OPT_IF_TSRESOL && !value.is_empty() {
+                if tsresol_units(value[0]).is_none() {
+                    return Err(Error::InvalidFormat("unsupported pcapng if_tsresol"));
+                }
+                interface.tsresol = value[0];
+            }
+        }
+        self.interfaces.push(interface);
+        Ok(())
+    }
+
+    fn parse_enhanced_packet(&self, body: &[u8]) -> Result<PcapngPacket, Error> {
+        if body.len() < 20 {
+            return Err(Error::InvalidFormat("short pcapng enhanced packet block"));
+        }
+        let interface_id = self.u32_at(body, 0);
+        let interface = self.interface(interface_id)?;
+        let ts = u64::from(self.u32_at(body, 4)) << 32 | u64::from(self.u32_at(body, 8));
+        let captured = self.u32_at(body, 12) as usize;
+        let data_end = 20 + captured;
+        if data_end > body.len() {
+            return Err(Error::InvalidFormat("pcapng packet data exceeds block"));
+        }
+        let units = tsresol_units(interface.tsresol)
+            .ok_or(Error::InvalidFormat("unsupported pcapng if_tsresol"))?;
+        let (timestamp_seconds, timestamp_nanoseconds) = split_timestamp(ts, units);
+        let mut packet = PcapngPacket {
+            interface_id,
+            timestamp_seconds,
+            timestamp_nanoseconds,
+            original_length: self.u32_at(body, 16),
+            drops: None,
+            data: body[20..data_end].to_vec(),
+        };
+        let options = (data_end + 3) & !3;
+        if options < body.len() {
+            for (code, value) in self.options(&body[options..]) {
+                if code == OPT_EPB_DROPCOUNT && value.len() == 8 {
+                    let v = value.try_into().unwrap_or_default();
+                    packet.drops = Some(if self.big_endian {
+                        u64::from_be_bytes(v)
+                    } else {
+                        u64::from_le_bytes(v)
+                    });
+                }
+            }
+        }
+        Ok(packet)
+    }
+
+    fn parse_simple_packet(&self, body: &[u8]) -> Result<PcapngPacket, Error> {
+        if body.len() < 4 {
+            return Err(Error::InvalidFormat("short pcapng simple packet block"));
+        }
+        let interface = self.interface(0)?;
+        let original_length = self.u32_at(body, 0);
+        let mut captured = (original_length as usize).min(body.len() - 4);
+        // a snaplen of 0 means no limit
+        if interface.snaplen != 0 {
+            captured = captured.min(interface.snaplen as usize);
+        }
+        Ok(PcapngPacket {
+            original_length,
+            data: body[4..4 + captured].to_vec(),
+            ..Default::default()
+        })
+    }
+
+    fn interface(&self, id: u32) -> Result<Interface, Error> {
+        self.interfaces
+            .get(id as usize)
+            .copied()
+            .ok_or(Error::InvalidFormat("unknown pcapng interface id"))
+    }
+
+    /// iterate over the options as (code, value) until the end of options.
+    fn options<'a>(&self, mut buf: &'a [u8]) -> Vec<(u16, &'a [u8])> {
+        let mut options = Vec::new();
+        while buf.len() >= 4 {
+            let code = self.u16([buf[0], buf[1]]);
+            let len = self.u16([buf[2], buf[3]]) as usize;
+            if code == OPT_ENDOFOPT || 4 + len > buf.len() {
+                break;
+            }
+            options.push((code, &buf[4..4 + len]));
+            buf = &buf[((4 + len + 3) & !3).min(buf.len())..];
+        }
+        options
+    }
+
+    fn u16(&self, b: [u8; 2]) -> u16 {
+        if self.big_endian {
+            u16::from_be_bytes(b)
+        } else {
+            u16::from_le_bytes(b)
+        }
+    }
+
+    fn u32(&self, b: [u8; 4]) -> u32 {
+        if self.big_endian {
+            u32::from_be_bytes(b)
+        } else {
+            u32::from_le_bytes(b)
+        }
+    }
+
+    fn u32_at(&self, buf: &[u8], i: usize) -> u32 {
+        self.u32([buf[i], buf[i + 1], buf[i + 2], buf[i + 3]])
+    }
+}
+
+impl<R> Iterator for PcapngReader<R>
+where
+    R: Read,
+{
+    type Item = Result<PcapngPacket, Error>;
+
+    /// iterate over packets inside a pcapng file until a valid eof or error occurs.
+    fn next(&mut self) -> Option<Self::Item> {
+        match self.read() {
+            Ok(packet) => Some(Ok(packet)),
+            Err(Error::Eof) => None,
+            Err(e) => Some(Err(e)),
+        }
+    }
+}
+
+/// writer to write packets as little-endian pcapng file with a single interface.
+#[derive(Debug)]
+pub struct PcapngWriter<W: Write> {
+    w: W,
+    linktype: u32,
+    drops: u32,
+}
+
+impl<W> PcapngWriter<W>
+where
+    W: Write,
+{
+    /// create a new pcapng writer and write the section header and interface description block.
+    /// the interface uses microsecond timestamps.
+    /// # Errors
+    /// will return [`Error::Io`] if something unexpected happen.
+    pub fn new(w: W, linktype: u32) -> Result<Self, Error> {
+        let mut writer = Self {
+            w,
+            linktype,
+            drops: 0,
+        };
+
+        let mut shb = Vec::with_capacity(16);
+        shb.extend_from_slice(&BYTE_ORDER_MAGIC.to_le_bytes());
+        shb.extend_from_slice(&1u16.to_le_bytes());
+        shb.extend_from_slice(&0u16.to_le_bytes());
+        shb.extend_from_slice(&(-1i64).to_le_bytes()); // section length not specified
+        writer.write_block(SECTION_HEADER_BLOCK, &shb)?;
+
+        let mut idb = Vec::with_capacity(20);
+        let linktype = u16::try_from(linktype & 0xFFFF).unwrap_or_default();
+        idb.extend_from_slice(&linktype.to_le_bytes());
+        idb.extend_from_slice(&0u16.to_le_bytes());
+        idb.extend_from_slice(&0u32.to_le_bytes());
+        push_option(&mut idb, OPT_IF_TSRESOL, &[DEFAULT_TSRESOL]);
+        push_option(&mut idb, OPT_ENDOFOPT, &[]);
+        writer.write_block(INTERFACE_DESCRIPTION_BLOCK, &idb)?;
+        Ok(writer)
+    }
+
+    /// write a snoop packet as enhanced packet block.
+    /// the increase of the cumulative drops since the previous packet is written as `epb_dropcount`.
+    /// # Errors
+    /// will return [`Error`] if something unexpected happen.
+    pub fn write_packet(&mut self, packet: &SnoopPacket) -> Result<(), Error> {
+        let ph = &packet.header;
+        let ts = u64::from(ph.timestamp_seconds) * 1_000_000 + u64::from(ph.timestamp_microseconds);
+        self.write_record(ts, ph.original_length, ph.cumulative_drops, &packet.data)
+    }
+
+    /// write an enhanced packet block with a timestamp in microseconds.
+    fn write_record(
+        &mut self,
+        ts: u64,
+        original_length: u32,
+        cumulative_drops: u32,
+        data: &[u8],
+    ) -> Result<(), Error> {
+        let included_length = u32::try_from(data.len()).map_err(|_| Error::CaptureLenExceeded)?;
+
+        let mut epb = Vec::with_capacity(data.len() + 40);
+        epb.extend_from_slice(&0u32.to_le_bytes());
+        #[allow(clippy::cast_possible_truncation)]
+        {
+            epb.extend_from_slice(&((ts >> 32) as u32).to_le_bytes());
+            epb.extend_from_slice(&(ts as u32).to_le_bytes());
+        }
+        epb.extend_from_slice(&included_length.to_le_bytes());
+        epb.extend_from_slice(&original_length.max(included_length).to_le_bytes());
+        epb.extend_from_slice(data);
+        epb.resize((epb.len() + 3) & !3, 0);
+
+        // a lower counter means the counter was reset, count from zero again
+        let drops = if cumulative_drops >= self.drops {
+            cumulative_drops - self.drops
+        } else {
+            cumulative_drops
+        };
+        self.drops = cumulative_drops;
+        if drops > 0 {
+            push_option(&mut epb, OPT_EPB_DROPCOUNT, &u64::from(drops).to_le_bytes());
+            push_option(&mut epb, OPT_ENDOFOPT, &[]);
+        }
+        self.write_block(ENHANCED_PACKET_BLOCK, &epb)
+    }
+
+    /// flush the underlying writer.
+    /// # Errors
+    /// will return [`Error::Io`] if something unexpected happen.
+    pub fn flush(&mut self) -> Result<(), Error> {
+        self.w.flush().map_err(Error::Io)
+    }
+
+    /// unwrap this writer and return the underlying writer.
+    pub fn into_inner(self) -> W {
+        self.w
+    }
+
+    /// write a block with type, body and both block length fields.
+    fn write_block(&mut self, block_type: u32, body: &[u8]) -> Result<(), Error> {
+        let total = u32::try_from(body.len() + 12).map_err(|_| Error::CaptureLenExceeded)?;
+        self.w
+            .write_all(&block_type.to_le_bytes())
+            .map_err(Error::Io)?;
+        self.w.write_all(&total.to_le_bytes()).map_err(Error::Io)?;
+        self.w.write_all(body).map_err(Error::Io)?;
+        self.w.write_all(&total.to_le_bytes()).map_err(Error::Io)
+    }
+}
+
+impl<R> CaptureSource for PcapngReader<R>
+where
+    R: Read,
+{
+    /// link type of the first interface, [`DataLinkType::Unassigned`] if there is no snoop equivalent.
+    /// use [`pcapng_to_snoop`] to split files with interfaces of different link types.
+    fn link_type(&self) -> DataLinkType {
+        self.interfaces
+            .first()
+            .and_then(|i| from_linktype(i.linktype))
+            .unwrap_or_default()
+    }
+
+    /// read the next packet, the `epb_dropcount` of the packets is summed up into the drops.
+    fn next_packet(&mut self) -> Option<Result<Packet, Error>> {
+        let packet = match self.next()? {
+            Ok(packet) => packet,
+            Err(e) => return Some(Err(e)),
+        };
+        let drop_delta = u32::try_from(packet.drops.unwrap_or(0)).unwrap_or(u32::MAX);
+        self.drops = self.drops.saturating_add(drop_delta);
+        Some(Ok(Packet {
+            timestamp: Duration::new(packet.timestamp_seconds, packet.timestamp_nanoseconds),
+            original_length: packet.original_length,
+            drops: self.drops,
+            data: packet.data,
+        }))
+    }
+}
+
+impl<W> CaptureSink for PcapngWriter<W>
+where
+    W: Write,
+{
+    /// link type of the interface, [`DataLinkType::Unassigned`] if there is no snoop equivalent
+    fn link_type(&self) -> DataLinkType {
+        from_linktype(self.linktype).unwrap_or_default()
+    }
+
+    fn write_capture(&mut self, packet: &Packet) -> Result<(), Error> {
+        let ts = u64::try_from(packet.timestamp.as_micros()).map_err(|_| Error::TimeEpoch)?;
+        self.write_record(ts, packet.original_length, packet.drops, &packet.data)
+    }
+
+    fn flush_capture(&mut self) -> Result<(), Error> {
+        self.flush()
+    }
+}
+
+/// convert the packets of a snoop reader into a pcapng file and return the number of converted packets.
+/// # Errors
+/// will return [`Error::UnsupportedLinkType`] if the snoop link type has no pcap equivalent
+/// and [`Error`] if something unexpected happen.
+pub fn snoop_to_pcapng<R: Read, W: Write>(reader: &mut Reader<R>, w: W) -> Result<u64, Error> {
+    let link_type = reader.header().link_type;
+    let network = to_linktype(link_type).ok_or(Error::UnsupportedLinkType(link_type as u32))?;
+    let mut writer = PcapngWriter::new(w, network)?;
+    let mut cnt = 0;
+    for packet in reader {
+        writer.write_packet(&packet?)?;
+        cnt += 1;
+    }
+    writer.flush()?;
+    Ok(cnt)
+}
+
+/// convert a pcapng file into snoop files and return the outputs with their link type
+/// and the counters of the conversion.
+///
+/// snoop supports only one link type per file, so packets are split by the link type of
+/// their interface. `create` is called once for each link type to create the output of it,
+/// in the order the link types appear.
+/// packets of interfaces whose link type has no snoop equivalent are skipped and counted
+/// in [`ConvertStats::skipped`], so the other interfaces are still converted.
+/// the `epb_dropcount` of the packets is summed up into the cumulative drops per output.
+/// packets longer than the snoop capture len are truncated and counted in [`ConvertStats::truncated`].
+/// # Errors
+/// will return [`Error::TimeEpoch`] if a timestamp not fits into snoop
+/// and [`Error`] if something unexpected happen.
+pub fn pcapng_to_snoop<R, W, F>(
+    r: R,
+    mut create: F,
+) -> Result<(Vec<(DataLinkType, W)>, ConvertStats), Error>
+where
+    R: Read,
+    W: Write,
+    F: FnMut(DataLinkType) -> Result<W, Error>,
+{
+    let mut reader = PcapngReader::new(r)?;
+    let mut outputs: Vec<(DataLinkType, Writer<W>, u32)> = Vec::new();
+    let mut stats = ConvertStats::default();
+    loop {
+        let packet = match reader.read() {
+            Ok(packet) => packet,
+            Err(Error::Eof) => break,
+            Err(e) => return Err(e),
+        };
+        let network = reader.interface(packet.interface_id)?.linktype;
+        let Some(link_type) = from_linktype(network) else {
+            stats.skipped += 1;
+            continue;
+        };
+        let i = if let Some(i) = outputs.iter().position(|(l, _, _)| *l == link_type) {
+            i
+        } else {
+            outputs.push((link_type, Writer::new(create(link_type)?, link_type)?, 0));
+            outputs.len() - 1
+        };
+        let (_, writer, drops) = &mut outputs[i];
+        let drop_delta = u32::try_from(packet.drops.unwrap_or(0)).unwrap_or(u32::MAX);
+        *drops = drops.saturating_add(drop_delta);
+        let seconds = u32::try_from(packet.timestamp_seconds).map_err(|_| Error::TimeEpoch)?;
+        if packet.data.len() > MAX_CAPTURE_LEN as usize {
+            stats.truncated += 1;
+        }
+        writer.write_packet(&snoop_packet(
+            seconds,
+            packet.timestamp_nanoseconds / 1000,
+            packet.original_length,
+            *drops,
+            packet.data,
+        ))?;
+        stats.packets += 1;
+    }
+    let mut out = Vec::with_capacity(outputs.len());
+    for (link_type, mut writer, _) in outputs {
+        writer.flush()?;
+        out.push((link_type, writer.into_inner()));
+    }
+    Ok((out, stats))
+}
+
+/// timestamp units per second of an `if_tsresol` value,
+/// `None` if the resolution is finer than 64 bit units can hold.
+fn tsresol_units(tsresol: u8) -> Option<u64> {
+    let exp = u32::from(tsresol & 0x7F);
+    if tsresol & 0x80 == 0 {
+        10u64.checked_pow(exp)
+    } else {
+        1u64.checked_shl(exp)
+    }
+}
+
+/// split a timestamp in units per second into seconds and nanoseconds.
+#[allow(clippy::cast_possible_truncation)]
+fn split_timestamp(ts: u64, units: u64) -> (u64, u32) {
+    let units = u128::from(units);
+    let ts = u128::from(ts);
+    (
+        (ts / units) as u64,
+        (ts % units * 1_000_000_000 / units) as u32,
+    )
+}
+
+/// append a little-endian option padded to 32 bits.
+#[allow(clippy::cast_possible_truncation)]
+fn push_option(buf: &mut Vec<u8>, code: u16, value: &[u8]) {
+    buf.extend_from_slice(&code.to_le_bytes());
+    buf.extend_from_slice(&(value.len() as u16).to_le_bytes());
+    buf.extend_from_slice(value);
+    buf.resize((buf.len() + 3) & !3, 0);
+}
+
+/// a missing block part is always an unexpected end of file.
+fn truncated(e: Error) -> Error {
+    match e {
+        Error::Eof => Error::UnexpectedEof(0),
+        e => e,
+    }
+}
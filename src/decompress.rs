@@ -0,0 +1,282 @@
+//! transparent decompression of compressed captures like `.snoop.gz`, `.snoop.zst` or `.snoop.xz`.
+//!
+//! the compression is detected from the magic bytes. gzip, zstd and xz need the
+//! **gzip**, **zstd** or **xz** feature, uncompressed data is passed through.
+//!
+//! a decompressor created with [`Decompressor::follow`] returns the end of the data instead of an
+//! error if a zstd frame is not complete yet, so [`Reader::read_stream`] keeps working on zstd files
+//! which are still written. otherwise an incomplete frame is an unexpected end of file.
+use crate::read::Reader;
+use crate::Error;
+use std::fmt;
+use std::fs::File;
+use std::io::{self, BufReader, Chain, Cursor, Read};
+use std::path::Path;
+
+/// magic bytes of a gzip stream
+pub const GZIP_MAGIC: &[u8] = &[0x1F, 0x8B];
+/// magic bytes of a zstd frame
+pub const ZSTD_MAGIC: &[u8] = &[0x28, 0xB5, 0x2F, 0xFD];
+/// magic bytes of a xz stream
+pub const XZ_MAGIC: &[u8] = &[0xFD, 0x37, 0x7A, 0x58, 0x5A, 0x00];
+
+/// number of bytes needed to detect every supported format
+pub(crate) const SNIFF_LEN: usize = 8;
+
+/// compression of a capture detected from the first bytes.
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum Compression {
+    /// gzip
+    Gzip,
+    /// zstd
+    Zstd,
+    /// xz
+    Xz,
+}
+
+impl Compression {
+    /// detect the compression from the first bytes, `None` if the data is not compressed.
+    #[must_use]
+    pub fn detect(buf: &[u8]) -> Option<Compression> {
+        if buf.starts_with(GZIP_MAGIC) {
+            Some(Compression::Gzip)
+        } else if buf.starts_with(ZSTD_MAGIC) {
+            Some(Compression::Zstd)
+        } else if buf.starts_with(XZ_MAGIC) {
+            Some(Compression::Xz)
+        } else {
+            None
+        }
+    }
+}
+
+/// reader which replays the sniffed bytes in front of the remaining data.
+pub(crate) type Sniffed<R> = Chain<Cursor<Vec<u8>>, R>;
+
+/// read the first bytes and return them with a reader starting at the beginning again.
+pub(crate) fn sniff<R: Read>(mut r: R) -> Result<(Vec<u8>, Sniffed<R>), Error> {
+    let mut buf = vec![0u8; SNIFF_LEN];
+    let mut n = 0;
+    while n < buf.len() {
+        match r.read(&mut buf[n..]) {
+            Ok(0) => break,
+            Ok(m) => n += m,
+            Err(ref e) if e.kind() == io::ErrorKind::Interrupted => {}
+            Err(e) => return Err(Error::Io(e)),
+        }
+    }
+    buf.truncate(n);
+    let magic = buf.clone();
+    Ok((magic, Cursor::new(buf).chain(r)))
+}
+
+/// reader that decompresses the underlying reader depending on the detected compression.
+pub enum Decompressor<R: Read> {
+    /// uncompressed data
+    Plain(Sniffed<R>),
+    /// gzip compressed data, multiple members are read as one stream
+    #[cfg(feature = "gzip")]
+    Gzip(flate2::read::MultiGzDecoder<Sniffed<R>>),
+    /// zstd compressed data, multiple frames are read as one stream
+    #[cfg(feature = "zstd")]
+    Zstd(ZstdDecoder<Sniffed<R>>),
+    /// xz compressed data, multiple streams are read as one stream
+    #[cfg(feature = "xz")]
+    Xz(xz2::read::XzDecoder<Sniffed<R>>),
+}
+
+impl<R: Read> Decompressor<R> {
+    /// detect the compression of the underlying reader and create the matching decoder.
+    /// # Errors
+    /// will return [`Error::InvalidFormat`] if the detected compression is not enabled
+    pub fn new(r: R) -> Result<Self, Error> {
+        Self::with_follow(r, false)
+    }
+
+    /// create a decompressor for a capture which is still written.
+    /// an incomplete zstd frame at the end of the data is returned as end of data,
+    /// further reads continue with the frame when new data arrives.
+    /// # Errors
+    /// will return [`Error::InvalidFormat`] if the detected compression is not enabled
+    pub fn follow(r: R) -> Result<Self, Error> {
+        Self::with_follow(r, true)
+    }
+
+    #[allow(unused_variables)]
+    fn with_follow(r: R, follow: bool) -> Result<Self, Error> {
+        let (magic, r) = sniff(r)?;
+        match Compression::detect(&magic) {
+            None => Ok(Decompressor::Plain(r)),
+            #[cfg(feature = "gzip")]
+            Some(Compression::Gzip) => Ok(Decompressor::Gzip(flate2::read::MultiGzDecoder::new(r))),
+            #[cfg(feature = "zstd")]
+            Some(Compression::Zstd) => Ok(Decompressor::Zstd(if follow {
+                ZstdDecoder::follow(r)?
+            } else {
+                ZstdDecoder::new(r)?
+            })),
+            #[cfg(feature = "xz")]
+            Some(Compression::Xz) => {
+                Ok(Decompressor::Xz(xz2::read::XzDecoder::new_multi_decoder(r)))
+            }
+            #[allow(unreachable_patterns)]
+            Some(c) => Err(Error::InvalidFormat(match c {
+                Compression::Gzip => "gzip compression not supported",
+                Compression::Zstd => "zstd compression not supported",
+                Compression::Xz => "xz compression not supported",
+            })),
+        }
+    }
+
+    /// the detected compression, `None` for uncompressed data.
+    pub fn compression(&self) -> Option<Compression> {
+        match self {
+            Decompressor::Plain(_) => None,
+            #[cfg(feature = "gzip")]
+            Decompressor::Gzip(_) => Some(Compression::Gzip),
+            #[cfg(feature = "zstd")]
+            Decompressor::Zstd(_) => Some(Compression::Zstd),
+            #[cfg(feature = "xz")]
+            Decompressor::Xz(_) => Some(Compression::Xz),
+        }
+    }
+}
+
+impl<R: Read> Read for Decompressor<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        match self {
+            Decompressor::Plain(r) => r.read(buf),
+            #[cfg(feature = "gzip")]
+            Decompressor::Gzip(r) => r.read(buf),
+            #[cfg(feature = "zstd")]
+            Decompressor::Zstd(r) => r.read(buf),
+            #[cfg(feature = "xz")]
+            Decompressor::Xz(r) => r.read(buf),
+        }
+    }
+}
+
+impl<R: Read> fmt::Debug for Decompressor<R> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_tuple("Decompressor")
+            .field(&self.compression())
+            .finish()
+    }
+}
+
+impl Reader<Decompressor<BufReader<File>>> {
+    /// open a snoop file which may be compressed and read the snoop header.
+    /// # Errors
+    /// will return [`Error::InvalidFormat`] if the detected compression is not enabled
+    /// and [`Error::UnknownMagic`] if the decompressed data is not in snoop format
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self, Error> {
+        let fp = File::open(path).map_err(Error::Io)?;
+        Reader::new(Decompressor::new(BufReader::new(fp))?)
+    }
+}
+
+/// zstd decoder which can follow a growing stream.
+/// in follow mode the end of data is returned instead of an incomplete frame error
+/// if the underlying reader has no more data. further reads continue with the frame
+/// when new data arrives.
+#[cfg(feature = "zstd")]
+pub struct ZstdDecoder<R> {
+    r: R,
+    decoder: zstd::stream::raw::Decoder<'static>,
+    buf: Vec<u8>,
+    pos: usize,
+    len: usize,
+    follow: bool,
+    in_frame: bool,
+}
+
+#[cfg(feature = "zstd")]
+impl<R: Read> ZstdDecoder<R> {
+    /// create a new zstd decoder, an incomplete frame at the end of the data is an error.
+    /// # Errors
+    /// will return [`Error::Io`] if the zstd context can not be created
+    pub fn new(r: R) -> Result<Self, Error> {
+        Ok(Self {
+            r,
+            decoder: zstd::stream::raw::Decoder::new().map_err(Error::Io)?,
+            buf: vec![0u8; zstd::zstd_safe::DCtx::in_size()],
+            pos: 0,
+            len: 0,
+            follow: false,
+            in_frame: false,
+        })
+    }
+
+    /// create a new zstd decoder which follows a growing stream.
+    /// # Errors
+    /// will return [`Error::Io`] if the zstd context can not be created
+    pub fn follow(r: R) -> Result<Self, Error> {
+        let mut d = Self::new(r)?;
+        d.follow = true;
+        Ok(d)
+    }
+}
+
+#[cfg(feature = "zstd")]
+impl<R: Read> Read for ZstdDecoder<R> {
+    fn read(&mut self, out: &mut [u8]) -> io::Result<usize> {
+        use zstd::stream::raw::Operation;
+        if out.is_empty() {
+            return Ok(0);
+        }
+        loop {
+            // decode the buffered input first, the decoder may also hold decoded data
+            let status = self
+                .decoder
+                .run_on_buffers(&self.buf[self.pos..self.len], out)?;
+            self.pos += status.bytes_read;
+            if status.bytes_read > 0 || status.bytes_written > 0 {
+                // the decoder returns 0 when a frame is complete and flushed
+                self.in_frame = status.remaining != 0;
+            }
+            if status.bytes_written > 0 {
+                return Ok(status.bytes_written);
+            }
+            if self.pos < self.len {
+                if status.bytes_read > 0 {
+                    continue;
+                }
+                // keep the unused input and append new data
+                self.buf.copy_within(self.pos..self.len, 0);
+                self.len -= self.pos;
+            } else {
+                self.len = 0;
+            }
+            self.pos = 0;
+            if self.len == self.buf.len() {
+                return Err(io::Error::other("zstd decoder made no progress"));
+            }
+            let n = loop {
+                match self.r.read(&mut self.buf[self.len..]) {
+                    Err(ref e) if e.kind() == io::ErrorKind::Interrupted => {}
+                    r => break r?,
+                }
+            };
+            if n == 0 {
+                if self.follow || (!self.in_frame && self.len == 0) {
+                    return Ok(0); // end of data, in follow mode maybe the stream grows later
+                }
+                return Err(io::Error::new(
+                    io::ErrorKind::UnexpectedEof,
+                    "incomplete zstd frame",
+                ));
+            }
+            self.len += n;
+        }
+    }
+}
+
+#[cfg(feature = "zstd")]
+impl<R> fmt::Debug for ZstdDecoder<R> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("ZstdDecoder")
+            .field("pos", &self.pos)
+            .field("len", &self.len)
+            .finish_non_exhaustive()
+    }
+}
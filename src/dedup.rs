@@ -0,0 +1,162 @@
+//! remove duplicate packets, like `editcap -d/-D/-w`.
+//!
+//! a packet is a duplicate if a packet with the same data was seen within the window
+//! of previous packets or the time window before it. the data is looked up by its hash
+//! and compared byte by byte, so a hash collision is not taken as a duplicate. fields which are changed by routers,
+//! the IPv4 TTL and header checksum and the IPv6 hop limit, can be ignored for Ethernet captures.
+use crate::ether::{ip_offset, ETHERTYPE_IPV4, ETHERTYPE_IPV6};
+use crate::format::{DataLinkType, PacketHeader};
+use crate::read::Reader;
+use crate::write::Writer;
+use crate::Error;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{HashMap, VecDeque};
+use std::hash::Hasher;
+use std::io::{Read, Write};
+use std::time::Duration;
+
+/// default window of previous packets, like `editcap -d`
+pub const DEFAULT_WINDOW: usize = 5;
+
+/// window in which duplicates are detected.
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub enum Window {
+    /// the previous n packets
+    Packets(usize),
+    /// the packets captured within this time before the packet
+    Time(Duration),
+}
+
+impl Default for Window {
+    fn default() -> Self {
+        Window::Packets(DEFAULT_WINDOW)
+    }
+}
+
+/// options of the duplicate detection.
+#[derive(Debug, Default, Clone)]
+pub struct DedupOptions {
+    /// window in which duplicates are detected
+    pub window: Window,
+    /// ignore the IPv4 TTL and header checksum and the IPv6 hop limit
+    pub ignore_mutable: bool,
+}
+
+/// counters of a dedup run.
+#[derive(Debug, Default, PartialEq, Eq, Copy, Clone)]
+pub struct DedupStats {
+    /// number of read packets
+    pub packets: u64,
+    /// number of removed duplicates
+    pub removed: u64,
+}
+
+/// duplicate detection over a stream of packets.
+#[derive(Debug)]
+pub struct Deduplicator {
+    link_type: DataLinkType,
+    options: DedupOptions,
+    window: VecDeque<(Duration, u64, Vec<u8>)>,
+    seen: HashMap<u64, usize>,
+}
+
+impl Deduplicator {
+    /// create a new duplicate detection for packets of the link type.
+    #[must_use]
+    pub fn new(link_type: DataLinkType, options: DedupOptions) -> Self {
+        Self {
+            link_type,
+            options,
+            window: VecDeque::new(),
+            seen: HashMap::new(),
+        }
+    }
+
+    /// check if the packet is a duplicate of a packet in the window and add it to the window.
+    /// # Errors
+    /// will return [`Error::InvalidMicroseconds`] if the timestamp of the packet is invalid.
+    pub fn is_duplicate(&mut self, header: &PacketHeader, data: &[u8]) -> Result<bool, Error> {
+        let time = header.timestamp()?;
+        let data = self.key(data);
+        let mut hasher = DefaultHasher::new();
+        hasher.write(&data);
+        let hash = hasher.finish();
+
+        // remove packets which are out of the window
+        while let Some(&(t, h, _)) = self.window.front() {
+            let expired = match self.options.window {
+                Window::Packets(n) => self.window.len() > n,
+                Window::Time(d) => time.saturating_sub(t) > d,
+            };
+            if !expired {
+                break;
+            }
+            self.window.pop_front();
+            if let Some(cnt) = self.seen.get_mut(&h) {
+                *cnt -= 1;
+                if *cnt == 0 {
+                    self.seen.remove(&h);
+                }
+            }
+        }
+
+        // compare the data only if a packet with the same hash is in the window
+        let duplicate = self.seen.contains_key(&hash)
+            && self.window.iter().any(|(_, h, d)| *h == hash && *d == data);
+        self.window.push_back((time, hash, data));
+        *self.seen.entry(hash).or_insert(0) += 1;
+        Ok(duplicate)
+    }
+
+    /// copy of the data which is compared, without the mutable fields if they are ignored.
+    fn key(&self, data: &[u8]) -> Vec<u8> {
+        let mut key = data.to_vec();
+        if self.options.ignore_mutable
+            && matches!(
+                self.link_type,
+                DataLinkType::Ethernet | DataLinkType::IEEE8023
+            )
+        {
+            clear_mutable(&mut key);
+        }
+        key
+    }
+}
+
+/// zero the IPv4 TTL and header checksum or the IPv6 hop limit of an Ethernet frame.
+fn clear_mutable(frame: &mut [u8]) {
+    match ip_offset(frame) {
+        Some((ETHERTYPE_IPV4, offset)) if frame.len() >= offset + 20 => {
+            frame[offset + 8] = 0;
+            frame[offset + 10] = 0;
+            frame[offset + 11] = 0;
+        }
+        Some((ETHERTYPE_IPV6, offset)) if frame.len() >= offset + 40 => {
+            frame[offset + 7] = 0;
+        }
+        _ => {}
+    }
+}
+
+/// write all packets which are not duplicates and return the counters.
+/// # Errors
+/// will return [`Error`] if a packet can not be read or written.
+pub fn dedup<R: Read, W: Write>(
+    reader: &mut Reader<R>,
+    writer: &mut Writer<W>,
+    options: DedupOptions,
+) -> Result<DedupStats, Error> {
+    let mut d = Deduplicator::new(reader.header().link_type, options);
+    let mut stats = DedupStats::default();
+    while let Some(packet) = reader.iter_ref() {
+        let packet = packet?;
+        stats.packets += 1;
+        if d.is_duplicate(packet.header, packet.data)? {
+            stats.removed += 1;
+            continue;
+        }
+        writer.write_packet_ref(&packet)?;
+    }
+    writer.flush()?;
+    Ok(stats)
+}
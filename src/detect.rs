@@ -0,0 +1,87 @@
+//! detect the format of capture files and open them as [`CaptureSource`].
+//!
+//! snoop, pcap and pcapng are detected by their magic bytes. gzip, zstd and xz
+//! compressed captures are decompressed if the **gzip**, **zstd** or **xz** feature is enabled,
+//! see [`crate::decompress`].
+use crate::capture::CaptureSource;
+use crate::convert::pcap::{PcapReader, PCAP_MAGIC_MICROS, PCAP_MAGIC_NANOS};
+use crate::convert::pcapng::{PcapngReader, SECTION_HEADER_BLOCK};
+use crate::decompress::{sniff, Compression, Decompressor};
+use crate::format::SNOOP_MAGIC;
+use crate::read::Reader;
+use crate::Error;
+use std::fs::File;
+use std::io::{BufReader, Read};
+use std::path::Path;
+
+pub use crate::decompress::{GZIP_MAGIC, XZ_MAGIC, ZSTD_MAGIC};
+
+/// capture or compression format detected from the first bytes of a file.
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum Format {
+    /// snoop version 2
+    Snoop,
+    /// classic libpcap in any byte order and resolution
+    Pcap,
+    /// pcapng
+    Pcapng,
+    /// gzip compressed data
+    Gzip,
+    /// zstd compressed data
+    Zstd,
+    /// xz compressed data
+    Xz,
+}
+
+impl Format {
+    /// detect the format from the first bytes of a file, 8 bytes are enough for all formats.
+    /// compressed data is detected as the compression format.
+    #[must_use]
+    pub fn detect(buf: &[u8]) -> Option<Format> {
+        let pcap = [
+            PCAP_MAGIC_MICROS.to_be_bytes(),
+            PCAP_MAGIC_MICROS.to_le_bytes(),
+            PCAP_MAGIC_NANOS.to_be_bytes(),
+            PCAP_MAGIC_NANOS.to_le_bytes(),
+        ];
+        if buf.starts_with(SNOOP_MAGIC) {
+            Some(Format::Snoop)
+        } else if pcap.iter().any(|m| buf.starts_with(m)) {
+            Some(Format::Pcap)
+        } else if buf.starts_with(&SECTION_HEADER_BLOCK.to_le_bytes()) {
+            Some(Format::Pcapng)
+        } else {
+            Compression::detect(buf).map(|c| match c {
+                Compression::Gzip => Format::Gzip,
+                Compression::Zstd => Format::Zstd,
+                Compression::Xz => Format::Xz,
+            })
+        }
+    }
+}
+
+/// open a capture file of any supported format as capture source.
+/// # Errors
+/// will return [`Error::InvalidFormat`] if the format is unknown or the decompression is not enabled
+/// and [`Error`] if something unexpected happen.
+pub fn open<P: AsRef<Path>>(path: P) -> Result<Box<dyn CaptureSource>, Error> {
+    let fp = File::open(path).map_err(Error::Io)?;
+    from_reader(BufReader::new(fp))
+}
+
+/// open a capture of any supported format from a reader as capture source.
+/// # Errors
+/// will return [`Error::InvalidFormat`] if the format is unknown or the decompression is not enabled
+/// and [`Error`] if something unexpected happen.
+pub fn from_reader<R: Read + 'static>(r: R) -> Result<Box<dyn CaptureSource>, Error> {
+    let (magic, r) = sniff(Decompressor::new(r)?)?;
+    match Format::detect(&magic) {
+        Some(Format::Snoop) => Ok(Box::new(Reader::new(r)?)),
+        Some(Format::Pcap) => Ok(Box::new(PcapReader::new(r)?)),
+        Some(Format::Pcapng) => Ok(Box::new(PcapngReader::new(r)?)),
+        Some(Format::Gzip | Format::Zstd | Format::Xz) => {
+            Err(Error::InvalidFormat("nested compression not supported"))
+        }
+        None => Err(Error::InvalidFormat("unknown capture format")),
+    }
+}
@@ -0,0 +1,154 @@
+//! ARP and RARP packets.
+//!
+//! the addresses have the lengths given in the header, [`ArpPacket::sender_mac`] and
+//! [`ArpPacket::sender_ip`] decode the common case of Ethernet and IPv4 addresses.
+use crate::dissect::ethernet::ETHERTYPE_IPV4;
+use crate::dissect::{be16, need, MacAddr};
+use crate::Error;
+use std::net::Ipv4Addr;
+
+/// size of the fixed ARP header before the addresses
+pub const ARP_HEADER_SIZE: usize = 8;
+
+/// hardware type of Ethernet
+pub const ARP_HARDWARE_ETHERNET: u16 = 1;
+/// hardware type of IEEE 802 networks like Token Ring and FDDI
+pub const ARP_HARDWARE_IEEE802: u16 = 6;
+
+/// ARP request
+pub const ARP_REQUEST: u16 = 1;
+/// ARP reply
+pub const ARP_REPLY: u16 = 2;
+/// RARP request
+pub const RARP_REQUEST: u16 = 3;
+/// RARP reply
+pub const RARP_REPLY: u16 = 4;
+
+/// ARP or RARP packet.
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub struct ArpPacket<'a> {
+    data: &'a [u8],
+}
+
+impl<'a> ArpPacket<'a> {
+    /// decode an ARP or RARP packet.
+    /// # Errors
+    /// will return [`Error::Truncated`] if the header or an address is cut off.
+    pub fn parse(data: &'a [u8]) -> Result<Self, Error> {
+        need("arp", data, ARP_HEADER_SIZE)?;
+        let len = ARP_HEADER_SIZE + 2 * (usize::from(data[4]) + usize::from(data[5]));
+        need("arp", data, len)?;
+        Ok(ArpPacket { data: &data[..len] })
+    }
+
+    /// hardware type, like [`ARP_HARDWARE_ETHERNET`]
+    #[must_use]
+    pub fn hardware_type(&self) -> u16 {
+        be16(self.data, 0)
+    }
+
+    /// protocol type as ethertype
+    #[must_use]
+    pub fn protocol_type(&self) -> u16 {
+        be16(self.data, 2)
+    }
+
+    /// length of a hardware address
+    #[must_use]
+    pub fn hardware_len(&self) -> u8 {
+        self.data[4]
+    }
+
+    /// length of a protocol address
+    #[must_use]
+    pub fn protocol_len(&self) -> u8 {
+        self.data[5]
+    }
+
+    /// operation, like [`ARP_REQUEST`] or [`RARP_REPLY`]
+    #[must_use]
+    pub fn operation(&self) -> u16 {
+        be16(self.data, 6)
+    }
+
+    /// is a RARP request or reply
+    #[must_use]
+    pub fn is_rarp(&self) -> bool {
+        matches!(self.operation(), RARP_REQUEST | RARP_REPLY)
+    }
+
+    /// sender hardware address
+    #[must_use]
+    pub fn sender_hardware(&self) -> &'a [u8] {
+        let start = ARP_HEADER_SIZE;
+        &self.data[start..start + usize::from(self.hardware_len())]
+    }
+
+    /// sender protocol address
+    #[must_use]
+    pub fn sender_protocol(&self) -> &'a [u8] {
+        let start = ARP_HEADER_SIZE + usize::from(self.hardware_len());
+        &self.data[start..start + usize::from(self.protocol_len())]
+    }
+
+    /// target hardware address
+    #[must_use]
+    pub fn target_hardware(&self) -> &'a [u8] {
+        let start =
+            ARP_HEADER_SIZE + usize::from(self.hardware_len()) + usize::from(self.protocol_len());
+        &self.data[start..start + usize::from(self.hardware_len())]
+    }
+
+    /// target protocol address
+    #[must_use]
+    pub fn target_protocol(&self) -> &'a [u8] {
+        let start = ARP_HEADER_SIZE
+            + 2 * usize::from(self.hardware_len())
+            + usize::from(self.protocol_len());
+        &self.data[start..start + usize::from(self.protocol_len())]
+    }
+
+    /// sender mac address if hardware addresses have 6 bytes
+    #[must_use]
+    pub fn sender_mac(&self) -> Option<MacAddr> {
+        mac(self.sender_hardware())
+    }
+
+    /// target mac address if hardware addresses have 6 bytes
+    #[must_use]
+    pub fn target_mac(&self) -> Option<MacAddr> {
+        mac(self.target_hardware())
+    }
+
+    /// sender IPv4 address if the protocol is IPv4
+    #[must_use]
+    pub fn sender_ip(&self) -> Option<Ipv4Addr> {
+        self.ipv4(self.sender_protocol())
+    }
+
+    /// target IPv4 address if the protocol is IPv4
+    #[must_use]
+    pub fn target_ip(&self) -> Option<Ipv4Addr> {
+        self.ipv4(self.target_protocol())
+    }
+
+    /// is a gratuitous ARP, a request or reply which announces the sender's own address
+    #[must_use]
+    pub fn is_gratuitous(&self) -> bool {
+        matches!(self.operation(), ARP_REQUEST | ARP_REPLY)
+            && self.sender_protocol() == self.target_protocol()
+            && self.sender_ip().is_some_and(|ip| !ip.is_unspecified())
+    }
+
+    fn ipv4(&self, addr: &[u8]) -> Option<Ipv4Addr> {
+        if self.protocol_type() != ETHERTYPE_IPV4 {
+            return None;
+        }
+        let addr: [u8; 4] = addr.try_into().ok()?;
+        Some(Ipv4Addr::from(addr))
+    }
+}
+
+fn mac(addr: &[u8]) -> Option<MacAddr> {
+    addr.try_into().ok().map(MacAddr)
+}
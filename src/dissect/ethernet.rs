@@ -0,0 +1,311 @@
+//! Ethernet II and IEEE 802.3 frames with 802.1Q and `QinQ` VLAN tags and LLC/SNAP headers.
+//!
+//! the two bytes after the mac addresses are an ethertype if they are 0x0600 or more,
+//! otherwise they are the length of an 802.3 payload which starts with an LLC header.
+use crate::dissect::{be16, need};
+use crate::Error;
+use std::fmt;
+
+/// size of an Ethernet header without VLAN tags
+pub const ETHERNET_HEADER_SIZE: usize = 14;
+/// size of a VLAN tag with tag protocol identifier
+pub const VLAN_TAG_SIZE: usize = 4;
+
+/// ethertype of IPv4
+pub const ETHERTYPE_IPV4: u16 = 0x0800;
+/// ethertype of ARP
+pub const ETHERTYPE_ARP: u16 = 0x0806;
+/// ethertype of RARP
+pub const ETHERTYPE_RARP: u16 = 0x8035;
+/// ethertype of an 802.1Q VLAN tag
+pub const ETHERTYPE_VLAN: u16 = 0x8100;
+/// ethertype of IPv6
+pub const ETHERTYPE_IPV6: u16 = 0x86DD;
+/// ethertype of an 802.1ad service VLAN tag (`QinQ`)
+pub const ETHERTYPE_QINQ: u16 = 0x88A8;
+/// ethertype of a `QinQ` tag used before 802.1ad
+pub const ETHERTYPE_QINQ_OLD: u16 = 0x9100;
+
+/// smallest value of the type field which is an ethertype and not an 802.3 length
+const MIN_ETHERTYPE: u16 = 0x0600;
+/// service access point of a SNAP header
+const SAP_SNAP: u8 = 0xAA;
+
+/// mac address.
+#[derive(Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Copy, Clone)]
+pub struct MacAddr(pub [u8; 6]);
+
+impl MacAddr {
+    /// broadcast address ff:ff:ff:ff:ff:ff
+    pub const BROADCAST: MacAddr = MacAddr([0xFF; 6]);
+
+    /// read a mac address from the first 6 bytes, the caller checked the length.
+    pub(crate) fn from_slice(data: &[u8]) -> Self {
+        let mut addr = [0u8; 6];
+        addr.copy_from_slice(&data[..6]);
+        MacAddr(addr)
+    }
+
+    /// is the broadcast address.
+    #[must_use]
+    pub fn is_broadcast(&self) -> bool {
+        *self == Self::BROADCAST
+    }
+
+    /// is a group address, broadcast included.
+    #[must_use]
+    pub fn is_multicast(&self) -> bool {
+        self.0[0] & 0x01 != 0
+    }
+
+    /// address with the bits of each byte reversed,
+    /// converts between canonical and non-canonical (bit-reversed) order.
+    #[must_use]
+    pub fn bit_reversed(&self) -> Self {
+        MacAddr(self.0.map(u8::reverse_bits))
+    }
+
+    /// is a locally administered address.
+    #[must_use]
+    pub fn is_local(&self) -> bool {
+        self.0[0] & 0x02 != 0
+    }
+}
+
+impl From<[u8; 6]> for MacAddr {
+    fn from(addr: [u8; 6]) -> Self {
+        MacAddr(addr)
+    }
+}
+
+impl fmt::Display for MacAddr {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let a = self.0;
+        write!(
+            f,
+            "{:02x}:{:02x}:{:02x}:{:02x}:{:02x}:{:02x}",
+            a[0], a[1], a[2], a[3], a[4], a[5]
+        )
+    }
+}
+
+/// 802.1Q or 802.1ad VLAN tag.
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub struct VlanTag {
+    /// tag protocol identifier, like [`ETHERTYPE_VLAN`] or [`ETHERTYPE_QINQ`]
+    pub tpid: u16,
+    /// priority code point
+    pub priority: u8,
+    /// drop eligible indicator
+    pub drop_eligible: bool,
+    /// VLAN identifier
+    pub id: u16,
+}
+
+impl VlanTag {
+    /// decode a tag from 4 bytes, the caller checked the length.
+    fn from_slice(data: &[u8]) -> Self {
+        let tci = be16(data, 2);
+        #[allow(clippy::cast_possible_truncation)]
+        VlanTag {
+            tpid: be16(data, 0),
+            priority: (tci >> 13) as u8,
+            drop_eligible: tci & 0x1000 != 0,
+            id: tci & 0x0FFF,
+        }
+    }
+}
+
+/// the field after the mac addresses and VLAN tags.
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum LengthType {
+    /// Ethernet II ethertype of the payload
+    EtherType(u16),
+    /// IEEE 802.3 length of the LLC payload
+    Length(u16),
+}
+
+/// SNAP extension of an LLC header.
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub struct Snap {
+    /// organizationally unique identifier, 0 for encapsulated ethertypes
+    pub oui: [u8; 3],
+    /// protocol id, an ethertype if the oui is 0
+    pub protocol_id: u16,
+}
+
+/// IEEE 802.2 LLC header with optional SNAP header.
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub struct Llc<'a> {
+    data: &'a [u8],
+    header_len: usize,
+}
+
+impl<'a> Llc<'a> {
+    /// decode an LLC header at the beginning of the data.
+    /// # Errors
+    /// will return [`Error::Truncated`] if the header is cut off.
+    pub fn parse(data: &'a [u8]) -> Result<Self, Error> {
+        need("llc", data, 3)?;
+        // information and supervisory frames have a 2 byte control field
+        let mut header_len = if data[2] & 0x03 == 0x03 { 3 } else { 4 };
+        if data[0] == SAP_SNAP && data[1] == SAP_SNAP && header_len == 3 {
+            header_len += 5;
+        }
+        need("llc", data, header_len)?;
+        Ok(Llc { data, header_len })
+    }
+
+    /// destination service access point
+    #[must_use]
+    pub fn dsap(&self) -> u8 {
+        self.data[0]
+    }
+
+    /// source service access point
+    #[must_use]
+    pub fn ssap(&self) -> u8 {
+        self.data[1]
+    }
+
+    /// control field, 1 byte for unnumbered frames and 2 bytes otherwise
+    #[must_use]
+    pub fn control(&self) -> u16 {
+        if self.header_len == 4 {
+            be16(self.data, 2)
+        } else {
+            u16::from(self.data[2])
+        }
+    }
+
+    /// SNAP header if the service access points are 0xAA.
+    #[must_use]
+    pub fn snap(&self) -> Option<Snap> {
+        (self.header_len == 8).then(|| Snap {
+            oui: [self.data[3], self.data[4], self.data[5]],
+            protocol_id: be16(self.data, 6),
+        })
+    }
+
+    /// ethertype of the payload from a SNAP header with oui 0.
+    #[must_use]
+    pub fn ether_type(&self) -> Option<u16> {
+        self.snap()
+            .filter(|snap| snap.oui == [0; 3])
+            .map(|snap| snap.protocol_id)
+    }
+
+    /// LLC and SNAP header bytes.
+    #[must_use]
+    pub fn header(&self) -> &'a [u8] {
+        &self.data[..self.header_len]
+    }
+
+    /// payload after the LLC and SNAP header.
+    #[must_use]
+    pub fn payload(&self) -> &'a [u8] {
+        &self.data[self.header_len..]
+    }
+}
+
+/// Ethernet II or IEEE 802.3 frame.
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub struct EthernetFrame<'a> {
+    data: &'a [u8],
+    tags: usize,
+    length_type: LengthType,
+    llc: Option<Llc<'a>>,
+    payload: &'a [u8],
+}
+
+impl<'a> EthernetFrame<'a> {
+    /// decode an Ethernet frame without frame check sequence.
+    /// the payload of an 802.3 frame ends at its length, padding is removed.
+    /// # Errors
+    /// will return [`Error::Truncated`] if the Ethernet, VLAN or LLC header is cut off.
+    pub fn parse(data: &'a [u8]) -> Result<Self, Error> {
+        need("ethernet", data, ETHERNET_HEADER_SIZE)?;
+        let mut offset = 12;
+        let mut tags = 0;
+        while matches!(
+            be16(data, offset),
+            ETHERTYPE_VLAN | ETHERTYPE_QINQ | ETHERTYPE_QINQ_OLD
+        ) {
+            offset += VLAN_TAG_SIZE;
+            tags += 1;
+            need("vlan", data, offset + 2)?;
+        }
+        let field = be16(data, offset);
+        let rest = &data[offset + 2..];
+        if field >= MIN_ETHERTYPE {
+            return Ok(EthernetFrame {
+                data,
+                tags,
+                length_type: LengthType::EtherType(field),
+                llc: None,
+                payload: rest,
+            });
+        }
+        let rest = &rest[..rest.len().min(usize::from(field))];
+        let llc = Llc::parse(rest)?;
+        Ok(EthernetFrame {
+            data,
+            tags,
+            length_type: LengthType::Length(field),
+            llc: Some(llc),
+            payload: llc.payload(),
+        })
+    }
+
+    /// destination mac address
+    #[must_use]
+    pub fn destination(&self) -> MacAddr {
+        MacAddr::from_slice(self.data)
+    }
+
+    /// source mac address
+    #[must_use]
+    pub fn source(&self) -> MacAddr {
+        MacAddr::from_slice(&self.data[6..])
+    }
+
+    /// VLAN tags from the outer to the inner tag.
+    pub fn vlan_tags(&self) -> impl Iterator<Item = VlanTag> + 'a {
+        self.data[12..12 + self.tags * VLAN_TAG_SIZE]
+            .chunks_exact(VLAN_TAG_SIZE)
+            .map(VlanTag::from_slice)
+    }
+
+    /// ethertype or 802.3 length field.
+    #[must_use]
+    pub fn length_type(&self) -> LengthType {
+        self.length_type
+    }
+
+    /// LLC header of an 802.3 frame.
+    #[must_use]
+    pub fn llc(&self) -> Option<Llc<'a>> {
+        self.llc
+    }
+
+    /// ethertype of the payload, from the type field or a SNAP header.
+    #[must_use]
+    pub fn ether_type(&self) -> Option<u16> {
+        match self.length_type {
+            LengthType::EtherType(ether_type) => Some(ether_type),
+            LengthType::Length(_) => self.llc.and_then(|llc| llc.ether_type()),
+        }
+    }
+
+    /// Ethernet header bytes with VLAN tags, without LLC header.
+    #[must_use]
+    pub fn header(&self) -> &'a [u8] {
+        &self.data[..ETHERNET_HEADER_SIZE + self.tags * VLAN_TAG_SIZE]
+    }
+
+    /// payload after all headers.
+    #[must_use]
+    pub fn payload(&self) -> &'a [u8] {
+        self.payload
+    }
+}
@@ -0,0 +1,100 @@
+//! FDDI frames.
+//!
+//! the frame starts at the frame control field, asynchronous LLC frames carry an LLC header.
+//! snoop captures of `DL_FDDI` usually store the mac addresses in non-canonical order with the
+//! bits of each byte reversed, [`FddiFrame::destination`] and [`FddiFrame::source`] return them
+//! as captured and the `canonical_*` accessors return them in Ethernet order.
+use crate::dissect::ethernet::Llc;
+use crate::dissect::{need, MacAddr};
+use crate::Error;
+
+/// size of an FDDI header with frame control and long addresses
+pub const FDDI_HEADER_SIZE: usize = 13;
+
+/// frame control bits of an asynchronous LLC frame, the priority bits are ignored
+const FC_LLC_ASYNC: u8 = 0x50;
+
+/// FDDI frame.
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub struct FddiFrame<'a> {
+    data: &'a [u8],
+    llc: Option<Llc<'a>>,
+}
+
+impl<'a> FddiFrame<'a> {
+    /// decode an FDDI frame, LLC frames are decoded with their LLC header.
+    /// # Errors
+    /// will return [`Error::Truncated`] if the header or LLC header is cut off.
+    pub fn parse(data: &'a [u8]) -> Result<Self, Error> {
+        need("fddi", data, FDDI_HEADER_SIZE)?;
+        let llc = if data[0] & 0xF8 == FC_LLC_ASYNC {
+            Some(Llc::parse(&data[FDDI_HEADER_SIZE..])?)
+        } else {
+            None
+        };
+        Ok(FddiFrame { data, llc })
+    }
+
+    /// frame control
+    #[must_use]
+    pub fn frame_control(&self) -> u8 {
+        self.data[0]
+    }
+
+    /// is an asynchronous LLC frame
+    #[must_use]
+    pub fn is_llc(&self) -> bool {
+        self.llc.is_some()
+    }
+
+    /// destination address as captured, usually in non-canonical bit order
+    #[must_use]
+    pub fn destination(&self) -> MacAddr {
+        MacAddr::from_slice(&self.data[1..])
+    }
+
+    /// source address as captured, usually in non-canonical bit order
+    #[must_use]
+    pub fn source(&self) -> MacAddr {
+        MacAddr::from_slice(&self.data[7..])
+    }
+
+    /// destination address in canonical order like Ethernet, for non-canonical captures
+    #[must_use]
+    pub fn canonical_destination(&self) -> MacAddr {
+        self.destination().bit_reversed()
+    }
+
+    /// source address in canonical order like Ethernet, for non-canonical captures
+    #[must_use]
+    pub fn canonical_source(&self) -> MacAddr {
+        self.source().bit_reversed()
+    }
+
+    /// LLC header of an LLC frame
+    #[must_use]
+    pub fn llc(&self) -> Option<Llc<'a>> {
+        self.llc
+    }
+
+    /// ethertype of the payload from a SNAP header
+    #[must_use]
+    pub fn ether_type(&self) -> Option<u16> {
+        self.llc.and_then(|llc| llc.ether_type())
+    }
+
+    /// FDDI header bytes without LLC header
+    #[must_use]
+    pub fn header(&self) -> &'a [u8] {
+        &self.data[..FDDI_HEADER_SIZE]
+    }
+
+    /// payload after all headers
+    #[must_use]
+    pub fn payload(&self) -> &'a [u8] {
+        match self.llc {
+            Some(llc) => llc.payload(),
+            None => &self.data[FDDI_HEADER_SIZE..],
+        }
+    }
+}
@@ -0,0 +1,134 @@
+//! Cisco HDLC frames.
+//!
+//! the address byte is followed by a control byte and the ethertype of the payload.
+//! protocol 0x8035 is the serial line ARP (SLARP) and not RARP like on Ethernet.
+use crate::dissect::{be16, be32, need};
+use crate::Error;
+use std::net::Ipv4Addr;
+
+/// size of a Cisco HDLC header
+pub const HDLC_HEADER_SIZE: usize = 4;
+/// address of a unicast frame
+pub const HDLC_UNICAST: u8 = 0x0F;
+/// address of a broadcast frame
+pub const HDLC_BROADCAST: u8 = 0x8F;
+/// protocol of the serial line ARP keepalives
+pub const HDLC_SLARP: u16 = 0x8035;
+/// size of a SLARP packet without the optional trailing bytes
+pub const SLARP_SIZE: usize = 18;
+
+/// serial line ARP packet.
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum Slarp {
+    /// request for the address of the peer
+    Request {
+        /// address, usually unspecified
+        address: Ipv4Addr,
+        /// network mask, usually unspecified
+        mask: Ipv4Addr,
+    },
+    /// reply with the address of the peer
+    Reply {
+        /// address of the sender
+        address: Ipv4Addr,
+        /// network mask of the sender
+        mask: Ipv4Addr,
+    },
+    /// keepalive
+    Keepalive {
+        /// sequence number of the sender
+        sequence: u32,
+        /// last sequence number seen from the peer
+        peer_sequence: u32,
+        /// reliability, 0xffff
+        reliability: u16,
+    },
+}
+
+impl Slarp {
+    /// decode a SLARP packet.
+    /// # Errors
+    /// will return [`Error::Truncated`] if the packet is cut off
+    /// and [`Error::InvalidPacket`] if the code is unknown.
+    pub fn parse(data: &[u8]) -> Result<Self, Error> {
+        need("slarp", data, SLARP_SIZE)?;
+        let address = Ipv4Addr::from(be32(data, 4));
+        let mask = Ipv4Addr::from(be32(data, 8));
+        match be32(data, 0) {
+            0 => Ok(Slarp::Request { address, mask }),
+            1 => Ok(Slarp::Reply { address, mask }),
+            2 => Ok(Slarp::Keepalive {
+                sequence: be32(data, 4),
+                peer_sequence: be32(data, 8),
+                reliability: be16(data, 12),
+            }),
+            _ => Err(Error::InvalidPacket("unknown slarp code")),
+        }
+    }
+}
+
+/// Cisco HDLC frame.
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub struct HdlcFrame<'a> {
+    data: &'a [u8],
+}
+
+impl<'a> HdlcFrame<'a> {
+    /// decode a Cisco HDLC frame.
+    /// # Errors
+    /// will return [`Error::Truncated`] if the header is cut off
+    /// and [`Error::InvalidPacket`] if the address is not unicast or broadcast.
+    pub fn parse(data: &'a [u8]) -> Result<Self, Error> {
+        need("hdlc", data, HDLC_HEADER_SIZE)?;
+        if !matches!(data[0], HDLC_UNICAST | HDLC_BROADCAST) {
+            return Err(Error::InvalidPacket("hdlc address is not 0x0f or 0x8f"));
+        }
+        Ok(HdlcFrame { data })
+    }
+
+    /// address byte
+    #[must_use]
+    pub fn address(&self) -> u8 {
+        self.data[0]
+    }
+
+    /// is a broadcast frame
+    #[must_use]
+    pub fn is_broadcast(&self) -> bool {
+        self.data[0] == HDLC_BROADCAST
+    }
+
+    /// control byte
+    #[must_use]
+    pub fn control(&self) -> u8 {
+        self.data[1]
+    }
+
+    /// ethertype of the payload
+    #[must_use]
+    pub fn protocol(&self) -> u16 {
+        be16(self.data, 2)
+    }
+
+    /// HDLC header bytes
+    #[must_use]
+    pub fn header(&self) -> &'a [u8] {
+        &self.data[..HDLC_HEADER_SIZE]
+    }
+
+    /// payload after the header
+    #[must_use]
+    pub fn payload(&self) -> &'a [u8] {
+        &self.data[HDLC_HEADER_SIZE..]
+    }
+
+    /// decode the payload as SLARP packet, `None` if the protocol is not SLARP.
+    /// # Errors
+    /// will return [`Error`] if the SLARP packet can not be decoded.
+    pub fn slarp(&self) -> Result<Option<Slarp>, Error> {
+        if self.protocol() != HDLC_SLARP {
+            return Ok(None);
+        }
+        Slarp::parse(self.payload()).map(Some)
+    }
+}
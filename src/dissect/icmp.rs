@@ -0,0 +1,112 @@
+//! `ICMPv4` and `ICMPv6` messages.
+use crate::dissect::{be16, need};
+use crate::Error;
+
+/// protocol number of `ICMPv4`
+pub const IPPROTO_ICMP: u8 = 1;
+/// protocol number of `ICMPv6`
+pub const IPPROTO_ICMPV6: u8 = 58;
+/// size of the ICMP header with the rest of header field
+pub const ICMP_HEADER_SIZE: usize = 8;
+
+/// `ICMPv4` echo reply
+pub const ICMP_ECHO_REPLY: u8 = 0;
+/// `ICMPv4` destination unreachable
+pub const ICMP_DEST_UNREACHABLE: u8 = 3;
+/// `ICMPv4` echo request
+pub const ICMP_ECHO_REQUEST: u8 = 8;
+/// `ICMPv4` time exceeded
+pub const ICMP_TIME_EXCEEDED: u8 = 11;
+/// `ICMPv6` destination unreachable
+pub const ICMPV6_DEST_UNREACHABLE: u8 = 1;
+/// `ICMPv6` packet too big
+pub const ICMPV6_PACKET_TOO_BIG: u8 = 2;
+/// `ICMPv6` time exceeded
+pub const ICMPV6_TIME_EXCEEDED: u8 = 3;
+/// `ICMPv6` echo request
+pub const ICMPV6_ECHO_REQUEST: u8 = 128;
+/// `ICMPv6` echo reply
+pub const ICMPV6_ECHO_REPLY: u8 = 129;
+/// `ICMPv6` neighbor solicitation
+pub const ICMPV6_NEIGHBOR_SOLICITATION: u8 = 135;
+/// `ICMPv6` neighbor advertisement
+pub const ICMPV6_NEIGHBOR_ADVERTISEMENT: u8 = 136;
+
+/// `ICMPv4` or `ICMPv6` message.
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub struct IcmpPacket<'a> {
+    data: &'a [u8],
+    v6: bool,
+}
+
+impl<'a> IcmpPacket<'a> {
+    /// decode an `ICMPv4` message.
+    /// # Errors
+    /// will return [`Error::Truncated`] if the header is cut off.
+    pub fn parse_v4(data: &'a [u8]) -> Result<Self, Error> {
+        need("icmp", data, ICMP_HEADER_SIZE)?;
+        Ok(IcmpPacket { data, v6: false })
+    }
+
+    /// decode an `ICMPv6` message.
+    /// # Errors
+    /// will return [`Error::Truncated`] if the header is cut off.
+    pub fn parse_v6(data: &'a [u8]) -> Result<Self, Error> {
+        need("icmpv6", data, ICMP_HEADER_SIZE)?;
+        Ok(IcmpPacket { data, v6: true })
+    }
+
+    /// is an `ICMPv6` message
+    #[must_use]
+    pub fn is_v6(&self) -> bool {
+        self.v6
+    }
+
+    /// message type
+    #[must_use]
+    pub fn icmp_type(&self) -> u8 {
+        self.data[0]
+    }
+
+    /// message code
+    #[must_use]
+    pub fn code(&self) -> u8 {
+        self.data[1]
+    }
+
+    /// checksum
+    #[must_use]
+    pub fn checksum(&self) -> u16 {
+        be16(self.data, 2)
+    }
+
+    /// the 4 bytes after the checksum, their meaning depends on the type
+    #[must_use]
+    pub fn rest_of_header(&self) -> &'a [u8] {
+        &self.data[4..ICMP_HEADER_SIZE]
+    }
+
+    /// identifier and sequence number of an echo request or reply
+    #[must_use]
+    pub fn echo(&self) -> Option<(u16, u16)> {
+        let echo = if self.v6 {
+            matches!(self.icmp_type(), ICMPV6_ECHO_REQUEST | ICMPV6_ECHO_REPLY)
+        } else {
+            matches!(self.icmp_type(), ICMP_ECHO_REQUEST | ICMP_ECHO_REPLY)
+        };
+        echo.then(|| (be16(self.data, 4), be16(self.data, 6)))
+    }
+
+    /// captured message body after the header,
+    /// for errors the start of the packet which caused the error
+    #[must_use]
+    pub fn payload(&self) -> &'a [u8] {
+        &self.data[ICMP_HEADER_SIZE..]
+    }
+
+    /// header and payload, the data covered by the checksum
+    #[must_use]
+    pub fn data(&self) -> &'a [u8] {
+        self.data
+    }
+}
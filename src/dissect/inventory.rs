@@ -0,0 +1,147 @@
+//! IP to MAC address inventory from ARP and RARP packets, e.g. to audit a segment for ARP spoofing.
+//!
+//! each IPv4 address and mac address pair announced by a sender, or assigned by a RARP reply,
+//! is a [`Binding`] with the time it was first and last seen. an IPv4 address with more than one
+//! mac address is a [`Conflict`].
+use crate::dissect::arp::{ArpPacket, RARP_REPLY};
+use crate::dissect::{dissect, MacAddr};
+use crate::format::{DataLinkType, SnoopPacket};
+use crate::Error;
+use std::collections::BTreeMap;
+use std::net::Ipv4Addr;
+use std::time::Duration;
+
+/// an IPv4 address seen with a mac address.
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub struct Binding {
+    /// IPv4 address
+    pub ip: Ipv4Addr,
+    /// mac address
+    pub mac: MacAddr,
+    /// timestamp of the first packet with this binding
+    pub first_seen: Duration,
+    /// timestamp of the last packet with this binding
+    pub last_seen: Duration,
+    /// number of packets with this binding
+    pub packets: u64,
+    /// number of gratuitous ARPs with this binding
+    pub gratuitous: u64,
+}
+
+/// an IPv4 address seen with multiple mac addresses.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Conflict<'a> {
+    /// IPv4 address
+    pub ip: Ipv4Addr,
+    /// bindings of the address ordered by mac address
+    pub bindings: Vec<&'a Binding>,
+}
+
+/// table of IPv4 to mac address bindings.
+#[derive(Debug, Default, Clone)]
+pub struct ArpInventory {
+    bindings: BTreeMap<(Ipv4Addr, MacAddr), Binding>,
+}
+
+impl ArpInventory {
+    /// create an empty inventory.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// add the bindings of an ARP or RARP packet captured at the timestamp.
+    /// ARP probes with sender address 0.0.0.0 are ignored.
+    pub fn add(&mut self, timestamp: Duration, arp: &ArpPacket<'_>) {
+        let gratuitous = arp.is_gratuitous();
+        if let (Some(ip), Some(mac)) = (arp.sender_ip(), arp.sender_mac()) {
+            self.insert(ip, mac, timestamp, gratuitous);
+        }
+        if arp.operation() == RARP_REPLY {
+            if let (Some(ip), Some(mac)) = (arp.target_ip(), arp.target_mac()) {
+                self.insert(ip, mac, timestamp, false);
+            }
+        }
+    }
+
+    fn insert(&mut self, ip: Ipv4Addr, mac: MacAddr, timestamp: Duration, gratuitous: bool) {
+        if ip.is_unspecified() {
+            return;
+        }
+        let binding = self.bindings.entry((ip, mac)).or_insert(Binding {
+            ip,
+            mac,
+            first_seen: timestamp,
+            last_seen: timestamp,
+            packets: 0,
+            gratuitous: 0,
+        });
+        binding.first_seen = binding.first_seen.min(timestamp);
+        binding.last_seen = binding.last_seen.max(timestamp);
+        binding.packets += 1;
+        binding.gratuitous += u64::from(gratuitous);
+    }
+
+    /// all bindings ordered by IPv4 address and mac address.
+    pub fn bindings(&self) -> impl Iterator<Item = &Binding> {
+        self.bindings.values()
+    }
+
+    /// bindings of an IPv4 address.
+    pub fn macs(&self, ip: Ipv4Addr) -> impl Iterator<Item = &Binding> {
+        self.bindings
+            .range((ip, MacAddr([0; 6]))..=(ip, MacAddr::BROADCAST))
+            .map(|(_, binding)| binding)
+    }
+
+    /// bindings of a mac address, more than one for routers with proxy ARP or multiple addresses.
+    pub fn ips(&self, mac: MacAddr) -> impl Iterator<Item = &Binding> {
+        self.bindings.values().filter(move |b| b.mac == mac)
+    }
+
+    /// IPv4 addresses seen with more than one mac address.
+    #[must_use]
+    pub fn conflicts(&self) -> Vec<Conflict<'_>> {
+        let mut conflicts: Vec<Conflict> = Vec::new();
+        for binding in self.bindings.values() {
+            match conflicts.last_mut() {
+                Some(conflict) if conflict.ip == binding.ip => conflict.bindings.push(binding),
+                _ => conflicts.push(Conflict {
+                    ip: binding.ip,
+                    bindings: vec![binding],
+                }),
+            }
+        }
+        conflicts.retain(|conflict| conflict.bindings.len() > 1);
+        conflicts
+    }
+
+    /// bindings which were announced by gratuitous ARPs.
+    pub fn gratuitous(&self) -> impl Iterator<Item = &Binding> {
+        self.bindings.values().filter(|b| b.gratuitous > 0)
+    }
+}
+
+/// build an inventory from the ARP and RARP packets of a capture with the given link type.
+/// frames which can not be decoded are skipped, a packet with invalid microseconds
+/// is counted with the seconds of its timestamp.
+/// # Errors
+/// will return [`Error`] if a packet can not be read.
+pub fn inventory<I>(link_type: DataLinkType, packets: I) -> Result<ArpInventory, Error>
+where
+    I: IntoIterator<Item = Result<SnoopPacket, Error>>,
+{
+    let mut inventory = ArpInventory::new();
+    for packet in packets {
+        let packet = packet?;
+        let Ok(Some(arp)) = dissect(link_type, &packet.data).and_then(|link| link.arp()) else {
+            continue;
+        };
+        let time = packet
+            .header
+            .timestamp()
+            .unwrap_or_else(|_| Duration::from_secs(u64::from(packet.header.timestamp_seconds)));
+        inventory.add(time, &arp);
+    }
+    Ok(inventory)
+}
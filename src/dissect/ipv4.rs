@@ -0,0 +1,208 @@
+//! IPv4 packets with options, fragmentation fields and header checksum.
+use crate::dissect::{be16, checksum, need};
+use crate::Error;
+use std::net::Ipv4Addr;
+
+/// size of an IPv4 header without options
+pub const IPV4_HEADER_SIZE: usize = 20;
+
+/// option which ends the option list
+pub const IPV4_OPTION_END: u8 = 0;
+/// option without length used as padding
+pub const IPV4_OPTION_NOP: u8 = 1;
+
+/// IPv4 option.
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub struct Ipv4Option<'a> {
+    /// option type with copied flag, class and number
+    pub kind: u8,
+    /// option data after type and length
+    pub data: &'a [u8],
+}
+
+/// IPv4 packet.
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub struct Ipv4Packet<'a> {
+    data: &'a [u8],
+    header_len: usize,
+    payload: &'a [u8],
+}
+
+impl<'a> Ipv4Packet<'a> {
+    /// decode an IPv4 packet, the payload ends at the total length or at the end of the capture.
+    /// # Errors
+    /// will return [`Error::Truncated`] if the header is cut off
+    /// and [`Error::InvalidPacket`] if the version, header length or an option is invalid.
+    pub fn parse(data: &'a [u8]) -> Result<Self, Error> {
+        need("ipv4", data, IPV4_HEADER_SIZE)?;
+        if data[0] >> 4 != 4 {
+            return Err(Error::InvalidPacket("ipv4 version is not 4"));
+        }
+        let header_len = usize::from(data[0] & 0x0F) * 4;
+        if header_len < IPV4_HEADER_SIZE {
+            return Err(Error::InvalidPacket("ipv4 header length below 20 bytes"));
+        }
+        need("ipv4", data, header_len)?;
+        let total_len = usize::from(be16(data, 2));
+        if total_len < header_len {
+            return Err(Error::InvalidPacket(
+                "ipv4 total length below header length",
+            ));
+        }
+        let packet = Ipv4Packet {
+            data,
+            header_len,
+            payload: &data[header_len..total_len.min(data.len())],
+        };
+        for option in packet.raw_options() {
+            option?;
+        }
+        Ok(packet)
+    }
+
+    /// header length in bytes with options
+    #[must_use]
+    pub fn header_len(&self) -> usize {
+        self.header_len
+    }
+
+    /// differentiated services code point
+    #[must_use]
+    pub fn dscp(&self) -> u8 {
+        self.data[1] >> 2
+    }
+
+    /// explicit congestion notification
+    #[must_use]
+    pub fn ecn(&self) -> u8 {
+        self.data[1] & 0x03
+    }
+
+    /// length of header and payload on the wire
+    #[must_use]
+    pub fn total_length(&self) -> u16 {
+        be16(self.data, 2)
+    }
+
+    /// identification of the fragments of a datagram
+    #[must_use]
+    pub fn identification(&self) -> u16 {
+        be16(self.data, 4)
+    }
+
+    /// don't fragment flag
+    #[must_use]
+    pub fn dont_fragment(&self) -> bool {
+        self.data[6] & 0x40 != 0
+    }
+
+    /// more fragments flag
+    #[must_use]
+    pub fn more_fragments(&self) -> bool {
+        self.data[6] & 0x20 != 0
+    }
+
+    /// fragment offset in units of 8 bytes
+    #[must_use]
+    pub fn fragment_offset(&self) -> u16 {
+        be16(self.data, 6) & 0x1FFF
+    }
+
+    /// is a fragment of a larger datagram
+    #[must_use]
+    pub fn is_fragment(&self) -> bool {
+        self.more_fragments() || self.fragment_offset() != 0
+    }
+
+    /// time to live
+    #[must_use]
+    pub fn ttl(&self) -> u8 {
+        self.data[8]
+    }
+
+    /// protocol of the payload
+    #[must_use]
+    pub fn protocol(&self) -> u8 {
+        self.data[9]
+    }
+
+    /// header checksum
+    #[must_use]
+    pub fn checksum(&self) -> u16 {
+        be16(self.data, 10)
+    }
+
+    /// verify the header checksum.
+    #[must_use]
+    pub fn verify_checksum(&self) -> bool {
+        checksum(0, self.header()) == 0
+    }
+
+    /// source address
+    #[must_use]
+    pub fn source(&self) -> Ipv4Addr {
+        Ipv4Addr::new(self.data[12], self.data[13], self.data[14], self.data[15])
+    }
+
+    /// destination address
+    #[must_use]
+    pub fn destination(&self) -> Ipv4Addr {
+        Ipv4Addr::new(self.data[16], self.data[17], self.data[18], self.data[19])
+    }
+
+    /// options until the end of option list, padding is skipped.
+    pub fn options(&self) -> impl Iterator<Item = Ipv4Option<'a>> + 'a {
+        self.raw_options().map_while(Result::ok)
+    }
+
+    fn raw_options(&self) -> Options<'a> {
+        Options(&self.data[IPV4_HEADER_SIZE..self.header_len])
+    }
+
+    /// header bytes with options
+    #[must_use]
+    pub fn header(&self) -> &'a [u8] {
+        &self.data[..self.header_len]
+    }
+
+    /// captured payload up to the total length
+    #[must_use]
+    pub fn payload(&self) -> &'a [u8] {
+        self.payload
+    }
+
+    /// the payload is cut off by the capture length
+    #[must_use]
+    pub fn is_truncated(&self) -> bool {
+        self.header_len + self.payload.len() < usize::from(self.total_length())
+    }
+}
+
+/// iterator over the raw option bytes.
+struct Options<'a>(&'a [u8]);
+
+impl<'a> Iterator for Options<'a> {
+    type Item = Result<Ipv4Option<'a>, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            match *self.0.first()? {
+                IPV4_OPTION_END => return None,
+                IPV4_OPTION_NOP => self.0 = &self.0[1..],
+                kind => {
+                    let len = self.0.get(1).map_or(0, |len| usize::from(*len));
+                    if len < 2 || len > self.0.len() {
+                        self.0 = &[];
+                        return Some(Err(Error::InvalidPacket("ipv4 option length")));
+                    }
+                    let option = Ipv4Option {
+                        kind,
+                        data: &self.0[2..len],
+                    };
+                    self.0 = &self.0[len..];
+                    return Some(Ok(option));
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,241 @@
+//! IPv6 packets with the chain of extension headers.
+//!
+//! hop-by-hop options, routing, fragment and destination options headers are decoded,
+//! the chain ends at the first other next header which is the upper layer protocol.
+use crate::dissect::{be16, be32, need};
+use crate::Error;
+use std::net::Ipv6Addr;
+
+/// size of the fixed IPv6 header
+pub const IPV6_HEADER_SIZE: usize = 40;
+
+/// next header of hop-by-hop options
+pub const IPPROTO_HOPOPTS: u8 = 0;
+/// next header of a routing header
+pub const IPPROTO_ROUTING: u8 = 43;
+/// next header of a fragment header
+pub const IPPROTO_FRAGMENT: u8 = 44;
+/// next header if no header follows
+pub const IPPROTO_NONE: u8 = 59;
+/// next header of destination options
+pub const IPPROTO_DSTOPTS: u8 = 60;
+
+/// size of a fragment header
+const FRAGMENT_HEADER_SIZE: usize = 8;
+
+/// IPv6 extension header.
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub struct ExtensionHeader<'a> {
+    /// next header value which identifies this header, like [`IPPROTO_ROUTING`]
+    pub protocol: u8,
+    /// header bytes including next header and length
+    pub data: &'a [u8],
+}
+
+impl<'a> ExtensionHeader<'a> {
+    /// protocol of the following header
+    #[must_use]
+    pub fn next_header(&self) -> u8 {
+        self.data[0]
+    }
+
+    /// options of a hop-by-hop or destination options header, in type-length-value encoding
+    #[must_use]
+    pub fn options(&self) -> Option<&'a [u8]> {
+        matches!(self.protocol, IPPROTO_HOPOPTS | IPPROTO_DSTOPTS).then(|| &self.data[2..])
+    }
+
+    /// routing type and segments left of a routing header
+    #[must_use]
+    pub fn routing(&self) -> Option<(u8, u8)> {
+        (self.protocol == IPPROTO_ROUTING).then(|| (self.data[2], self.data[3]))
+    }
+
+    /// decoded fragment header
+    #[must_use]
+    pub fn fragment(&self) -> Option<Fragment> {
+        (self.protocol == IPPROTO_FRAGMENT).then(|| Fragment {
+            offset: be16(self.data, 2) >> 3,
+            more_fragments: self.data[3] & 0x01 != 0,
+            identification: be32(self.data, 4),
+        })
+    }
+}
+
+/// IPv6 fragment header.
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub struct Fragment {
+    /// fragment offset in units of 8 bytes
+    pub offset: u16,
+    /// more fragments flag
+    pub more_fragments: bool,
+    /// identification of the fragments of a packet
+    pub identification: u32,
+}
+
+/// IPv6 packet.
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub struct Ipv6Packet<'a> {
+    data: &'a [u8],
+    header_len: usize,
+    protocol: u8,
+    payload: &'a [u8],
+}
+
+impl<'a> Ipv6Packet<'a> {
+    /// decode an IPv6 packet and its extension headers.
+    /// the payload ends at the payload length or at the end of the capture.
+    /// # Errors
+    /// will return [`Error::Truncated`] if the header or an extension header is cut off
+    /// and [`Error::InvalidPacket`] if the version is not 6.
+    pub fn parse(data: &'a [u8]) -> Result<Self, Error> {
+        need("ipv6", data, IPV6_HEADER_SIZE)?;
+        if data[0] >> 4 != 6 {
+            return Err(Error::InvalidPacket("ipv6 version is not 6"));
+        }
+        let mut protocol = data[6];
+        let mut header_len = IPV6_HEADER_SIZE;
+        while is_extension(protocol) {
+            let ext = &data[header_len..];
+            need("ipv6 extension", ext, 2)?;
+            let len = if protocol == IPPROTO_FRAGMENT {
+                FRAGMENT_HEADER_SIZE
+            } else {
+                (usize::from(ext[1]) + 1) * 8
+            };
+            need("ipv6 extension", ext, len)?;
+            protocol = ext[0];
+            header_len += len;
+        }
+        // a jumbogram has payload length 0 and takes the whole capture
+        let end = match usize::from(be16(data, 4)) {
+            0 => data.len(),
+            len => (IPV6_HEADER_SIZE + len).min(data.len()),
+        };
+        Ok(Ipv6Packet {
+            data,
+            header_len,
+            protocol,
+            payload: data.get(header_len..end).unwrap_or_default(),
+        })
+    }
+
+    /// traffic class
+    #[must_use]
+    #[allow(clippy::cast_possible_truncation)]
+    pub fn traffic_class(&self) -> u8 {
+        ((be16(self.data, 0) >> 4) & 0xFF) as u8
+    }
+
+    /// flow label
+    #[must_use]
+    pub fn flow_label(&self) -> u32 {
+        be32(self.data, 0) & 0x000F_FFFF
+    }
+
+    /// length of extension headers and payload on the wire
+    #[must_use]
+    pub fn payload_length(&self) -> u16 {
+        be16(self.data, 4)
+    }
+
+    /// next header of the fixed header
+    #[must_use]
+    pub fn next_header(&self) -> u8 {
+        self.data[6]
+    }
+
+    /// hop limit
+    #[must_use]
+    pub fn hop_limit(&self) -> u8 {
+        self.data[7]
+    }
+
+    /// source address
+    #[must_use]
+    pub fn source(&self) -> Ipv6Addr {
+        Ipv6Addr::from(address(&self.data[8..24]))
+    }
+
+    /// destination address
+    #[must_use]
+    pub fn destination(&self) -> Ipv6Addr {
+        Ipv6Addr::from(address(&self.data[24..40]))
+    }
+
+    /// extension headers in chain order.
+    pub fn extension_headers(&self) -> impl Iterator<Item = ExtensionHeader<'a>> + 'a {
+        let data = self.data;
+        let end = self.header_len;
+        let mut protocol = data[6];
+        let mut offset = IPV6_HEADER_SIZE;
+        std::iter::from_fn(move || {
+            if offset >= end {
+                return None;
+            }
+            let len = if protocol == IPPROTO_FRAGMENT {
+                FRAGMENT_HEADER_SIZE
+            } else {
+                (usize::from(data[offset + 1]) + 1) * 8
+            };
+            let ext = ExtensionHeader {
+                protocol,
+                data: &data[offset..offset + len],
+            };
+            protocol = data[offset];
+            offset += len;
+            Some(ext)
+        })
+    }
+
+    /// fragment header if the packet is a fragment
+    #[must_use]
+    pub fn fragment(&self) -> Option<Fragment> {
+        self.extension_headers().find_map(|ext| ext.fragment())
+    }
+
+    /// upper layer protocol after the extension headers
+    #[must_use]
+    pub fn protocol(&self) -> u8 {
+        self.protocol
+    }
+
+    /// fixed header and extension headers
+    #[must_use]
+    pub fn header(&self) -> &'a [u8] {
+        &self.data[..self.header_len]
+    }
+
+    /// header length in bytes with extension headers
+    #[must_use]
+    pub fn header_len(&self) -> usize {
+        self.header_len
+    }
+
+    /// captured upper layer payload up to the payload length
+    #[must_use]
+    pub fn payload(&self) -> &'a [u8] {
+        self.payload
+    }
+
+    /// the payload is cut off by the capture length
+    #[must_use]
+    pub fn is_truncated(&self) -> bool {
+        self.payload_length() != 0
+            && self.header_len + self.payload.len()
+                < IPV6_HEADER_SIZE + usize::from(self.payload_length())
+    }
+}
+
+fn is_extension(protocol: u8) -> bool {
+    matches!(
+        protocol,
+        IPPROTO_HOPOPTS | IPPROTO_ROUTING | IPPROTO_FRAGMENT | IPPROTO_DSTOPTS
+    )
+}
+
+fn address(data: &[u8]) -> [u8; 16] {
+    let mut addr = [0u8; 16];
+    addr.copy_from_slice(data);
+    addr
+}
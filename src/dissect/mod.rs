@@ -0,0 +1,385 @@
+//! decode captured frames into borrowed views of the protocol headers.
+//!
+//! the decoders do not copy the packet data, every view borrows from the captured bytes.
+//! a header which is cut off, e.g. by the capture length, returns [`Error::Truncated`]
+//! and a malformed header returns [`Error::InvalidPacket`].
+use crate::format::{DataLinkType, SnoopPacketRef};
+use crate::Error;
+use std::net::IpAddr;
+
+pub mod arp;
+pub mod ethernet;
+pub mod fddi;
+pub mod hdlc;
+pub mod icmp;
+pub mod inventory;
+pub mod ipv4;
+pub mod ipv6;
+pub mod tcp;
+pub mod tokenring;
+pub mod udp;
+
+pub use arp::ArpPacket;
+pub use ethernet::{EthernetFrame, MacAddr};
+pub use fddi::FddiFrame;
+pub use hdlc::HdlcFrame;
+pub use icmp::IcmpPacket;
+pub use ipv4::Ipv4Packet;
+pub use ipv6::Ipv6Packet;
+pub use tcp::TcpSegment;
+pub use tokenring::TokenRingFrame;
+pub use udp::UdpDatagram;
+
+use ethernet::{ETHERTYPE_ARP, ETHERTYPE_IPV4, ETHERTYPE_IPV6, ETHERTYPE_RARP};
+use icmp::{IPPROTO_ICMP, IPPROTO_ICMPV6};
+use tcp::IPPROTO_TCP;
+use udp::IPPROTO_UDP;
+
+/// decoded link layer frame.
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum LinkLayer<'a> {
+    /// Ethernet II or IEEE 802.3 frame
+    Ethernet(EthernetFrame<'a>),
+    /// IEEE 802.5 Token Ring frame
+    TokenRing(TokenRingFrame<'a>),
+    /// FDDI frame
+    Fddi(FddiFrame<'a>),
+    /// Cisco HDLC frame
+    Hdlc(HdlcFrame<'a>),
+}
+
+impl<'a> LinkLayer<'a> {
+    /// ethertype of the payload, also from a SNAP header, `None` if the payload has no ethertype.
+    #[must_use]
+    pub fn ether_type(&self) -> Option<u16> {
+        match self {
+            LinkLayer::Ethernet(frame) => frame.ether_type(),
+            LinkLayer::TokenRing(frame) => frame.ether_type(),
+            LinkLayer::Fddi(frame) => frame.ether_type(),
+            LinkLayer::Hdlc(frame) => Some(frame.protocol()),
+        }
+    }
+
+    /// payload after the link layer headers.
+    #[must_use]
+    pub fn payload(&self) -> &'a [u8] {
+        match self {
+            LinkLayer::Ethernet(frame) => frame.payload(),
+            LinkLayer::TokenRing(frame) => frame.payload(),
+            LinkLayer::Fddi(frame) => frame.payload(),
+            LinkLayer::Hdlc(frame) => frame.payload(),
+        }
+    }
+
+    /// decode the IPv4 or IPv6 packet of the payload, `None` if the ethertype is not IP.
+    /// # Errors
+    /// will return [`Error`] if the network layer header can not be decoded.
+    pub fn network(&self) -> Result<Option<NetworkLayer<'a>>, Error> {
+        match self.ether_type() {
+            Some(ether_type) => network(ether_type, self.payload()),
+            None => Ok(None),
+        }
+    }
+
+    /// decode the payload as ARP or RARP packet, `None` if the ethertype is not ARP or RARP.
+    /// on Cisco HDLC 0x8035 is SLARP and not RARP, see [`HdlcFrame::slarp`].
+    /// # Errors
+    /// will return [`Error::Truncated`] if the packet is cut off.
+    pub fn arp(&self) -> Result<Option<ArpPacket<'a>>, Error> {
+        match (self, self.ether_type()) {
+            (LinkLayer::Hdlc(_), Some(ETHERTYPE_RARP)) => Ok(None),
+            (_, Some(ETHERTYPE_ARP | ETHERTYPE_RARP)) => ArpPacket::parse(self.payload()).map(Some),
+            _ => Ok(None),
+        }
+    }
+}
+
+/// decoded network layer packet.
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum NetworkLayer<'a> {
+    /// IPv4 packet
+    Ipv4(Ipv4Packet<'a>),
+    /// IPv6 packet
+    Ipv6(Ipv6Packet<'a>),
+}
+
+impl<'a> NetworkLayer<'a> {
+    /// source address
+    #[must_use]
+    pub fn source(&self) -> IpAddr {
+        match self {
+            NetworkLayer::Ipv4(ip) => IpAddr::V4(ip.source()),
+            NetworkLayer::Ipv6(ip) => IpAddr::V6(ip.source()),
+        }
+    }
+
+    /// destination address
+    #[must_use]
+    pub fn destination(&self) -> IpAddr {
+        match self {
+            NetworkLayer::Ipv4(ip) => IpAddr::V4(ip.destination()),
+            NetworkLayer::Ipv6(ip) => IpAddr::V6(ip.destination()),
+        }
+    }
+
+    /// upper layer protocol, after the IPv6 extension headers
+    #[must_use]
+    pub fn protocol(&self) -> u8 {
+        match self {
+            NetworkLayer::Ipv4(ip) => ip.protocol(),
+            NetworkLayer::Ipv6(ip) => ip.protocol(),
+        }
+    }
+
+    /// captured upper layer payload
+    #[must_use]
+    pub fn payload(&self) -> &'a [u8] {
+        match self {
+            NetworkLayer::Ipv4(ip) => ip.payload(),
+            NetworkLayer::Ipv6(ip) => ip.payload(),
+        }
+    }
+
+    /// the payload is cut off by the capture length
+    #[must_use]
+    pub fn is_truncated(&self) -> bool {
+        match self {
+            NetworkLayer::Ipv4(ip) => ip.is_truncated(),
+            NetworkLayer::Ipv6(ip) => ip.is_truncated(),
+        }
+    }
+
+    /// is a fragment of a larger packet
+    #[must_use]
+    pub fn is_fragment(&self) -> bool {
+        match self {
+            NetworkLayer::Ipv4(ip) => ip.is_fragment(),
+            NetworkLayer::Ipv6(ip) => ip.fragment().is_some(),
+        }
+    }
+
+    /// decode the transport layer of the payload,
+    /// `None` if there is no decoder for the protocol or the packet is not the first fragment.
+    /// # Errors
+    /// will return [`Error::Truncated`] or [`Error::InvalidPacket`] if the header can not be decoded.
+    pub fn transport(&self) -> Result<Option<TransportLayer<'a>>, Error> {
+        let first = match self {
+            NetworkLayer::Ipv4(ip) => ip.fragment_offset() == 0,
+            NetworkLayer::Ipv6(ip) => ip.fragment().is_none_or(|f| f.offset == 0),
+        };
+        if !first {
+            return Ok(None);
+        }
+        let data = self.payload();
+        Ok(Some(match (self.protocol(), self) {
+            (IPPROTO_TCP, _) => TransportLayer::Tcp(TcpSegment::parse(data)?),
+            (IPPROTO_UDP, _) => TransportLayer::Udp(UdpDatagram::parse(data)?),
+            (IPPROTO_ICMP, NetworkLayer::Ipv4(_)) => {
+                TransportLayer::Icmp(IcmpPacket::parse_v4(data)?)
+            }
+            (IPPROTO_ICMPV6, NetworkLayer::Ipv6(_)) => {
+                TransportLayer::Icmp(IcmpPacket::parse_v6(data)?)
+            }
+            _ => return Ok(None),
+        }))
+    }
+
+    /// sum of the pseudo header for the transport checksum.
+    fn pseudo_header(&self, protocol: u8, len: usize) -> u32 {
+        match self {
+            NetworkLayer::Ipv4(ip) => {
+                let mut header = [0u8; 12];
+                header[..4].copy_from_slice(&ip.source().octets());
+                header[4..8].copy_from_slice(&ip.destination().octets());
+                header[9] = protocol;
+                #[allow(clippy::cast_possible_truncation)]
+                header[10..].copy_from_slice(&(len as u16).to_be_bytes());
+                sum(&header)
+            }
+            NetworkLayer::Ipv6(ip) => {
+                let mut header = [0u8; 40];
+                header[..16].copy_from_slice(&ip.source().octets());
+                header[16..32].copy_from_slice(&ip.destination().octets());
+                #[allow(clippy::cast_possible_truncation)]
+                header[32..36].copy_from_slice(&(len as u32).to_be_bytes());
+                header[39] = protocol;
+                sum(&header)
+            }
+        }
+    }
+}
+
+/// result of a transport checksum verification.
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum Checksum {
+    /// the checksum matches the data
+    Valid,
+    /// the checksum does not match the data
+    Invalid,
+    /// the checksum is not set, only allowed for UDP over IPv4
+    Unset,
+    /// not verifiable, the payload is cut off by the capture length
+    Truncated,
+    /// not verifiable, the packet is a fragment and the rest of the payload is in other packets
+    Fragment,
+}
+
+/// decoded transport layer segment or message.
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum TransportLayer<'a> {
+    /// TCP segment
+    Tcp(TcpSegment<'a>),
+    /// UDP datagram
+    Udp(UdpDatagram<'a>),
+    /// `ICMPv4` message over IPv4 or `ICMPv6` message over IPv6
+    Icmp(IcmpPacket<'a>),
+}
+
+impl<'a> TransportLayer<'a> {
+    /// source port of TCP and UDP
+    #[must_use]
+    pub fn source_port(&self) -> Option<u16> {
+        match self {
+            TransportLayer::Tcp(tcp) => Some(tcp.source_port()),
+            TransportLayer::Udp(udp) => Some(udp.source_port()),
+            TransportLayer::Icmp(_) => None,
+        }
+    }
+
+    /// destination port of TCP and UDP
+    #[must_use]
+    pub fn destination_port(&self) -> Option<u16> {
+        match self {
+            TransportLayer::Tcp(tcp) => Some(tcp.destination_port()),
+            TransportLayer::Udp(udp) => Some(udp.destination_port()),
+            TransportLayer::Icmp(_) => None,
+        }
+    }
+
+    /// captured payload after the transport header
+    #[must_use]
+    pub fn payload(&self) -> &'a [u8] {
+        match self {
+            TransportLayer::Tcp(tcp) => tcp.payload(),
+            TransportLayer::Udp(udp) => udp.payload(),
+            TransportLayer::Icmp(icmp) => icmp.payload(),
+        }
+    }
+
+    /// verify the checksum with the pseudo header of the network layer the segment came from.
+    /// a payload which is cut off by the capture length returns [`Checksum::Truncated`]
+    /// and a fragment returns [`Checksum::Fragment`], the checksum is not verifiable then.
+    #[must_use]
+    pub fn verify_checksum(&self, network: &NetworkLayer<'_>) -> Checksum {
+        if network.is_fragment() {
+            return Checksum::Fragment;
+        }
+        if network.is_truncated() {
+            return Checksum::Truncated;
+        }
+        let (protocol, data) = match self {
+            TransportLayer::Tcp(tcp) => (Some(IPPROTO_TCP), tcp.data()),
+            TransportLayer::Udp(udp) => {
+                if udp.checksum() == 0 && matches!(network, NetworkLayer::Ipv4(_)) {
+                    return Checksum::Unset;
+                }
+                if udp.data().len() < usize::from(udp.length()) {
+                    return Checksum::Truncated;
+                }
+                (Some(IPPROTO_UDP), udp.data())
+            }
+            // ICMPv4 has no pseudo header
+            TransportLayer::Icmp(icmp) if !icmp.is_v6() => (None, icmp.data()),
+            TransportLayer::Icmp(icmp) => (Some(IPPROTO_ICMPV6), icmp.data()),
+        };
+        let init = protocol.map_or(0, |p| network.pseudo_header(p, data.len()));
+        if checksum(init, data) == 0 {
+            Checksum::Valid
+        } else {
+            Checksum::Invalid
+        }
+    }
+}
+
+/// decode the network layer of a payload with the given ethertype,
+/// `None` if there is no decoder for the ethertype.
+/// # Errors
+/// will return [`Error::Truncated`] or [`Error::InvalidPacket`] if the header can not be decoded.
+pub fn network(ether_type: u16, data: &[u8]) -> Result<Option<NetworkLayer<'_>>, Error> {
+    Ok(Some(match ether_type {
+        ETHERTYPE_IPV4 => NetworkLayer::Ipv4(Ipv4Packet::parse(data)?),
+        ETHERTYPE_IPV6 => NetworkLayer::Ipv6(Ipv6Packet::parse(data)?),
+        _ => return Ok(None),
+    }))
+}
+
+/// decode the link layer of a frame captured on the given link type.
+/// # Errors
+/// will return [`Error::UnsupportedLinkType`] if there is no decoder for the link type
+/// and [`Error::Truncated`] or [`Error::InvalidPacket`] if the frame can not be decoded.
+pub fn dissect(link_type: DataLinkType, data: &[u8]) -> Result<LinkLayer<'_>, Error> {
+    match link_type {
+        DataLinkType::Ethernet | DataLinkType::IEEE8023 => {
+            EthernetFrame::parse(data).map(LinkLayer::Ethernet)
+        }
+        DataLinkType::TokenRing => TokenRingFrame::parse(data).map(LinkLayer::TokenRing),
+        DataLinkType::Fddi => FddiFrame::parse(data).map(LinkLayer::Fddi),
+        DataLinkType::Hdlc => HdlcFrame::parse(data).map(LinkLayer::Hdlc),
+        _ => Err(Error::UnsupportedLinkType(link_type as u32)),
+    }
+}
+
+impl<'a> SnoopPacketRef<'a> {
+    /// decode the link layer of the packet data captured on the given link type.
+    /// # Errors
+    /// will return [`Error`] if the frame can not be decoded, see [`dissect()`].
+    pub fn dissect(&self, link_type: DataLinkType) -> Result<LinkLayer<'a>, Error> {
+        dissect(link_type, self.data)
+    }
+}
+
+/// check that the data has at least `len` bytes for a header of the protocol.
+pub(crate) fn need(protocol: &'static str, data: &[u8], len: usize) -> Result<(), Error> {
+    if data.len() < len {
+        return Err(Error::Truncated(protocol, len, data.len()));
+    }
+    Ok(())
+}
+
+/// read a big endian u16, the caller checked the length.
+pub(crate) fn be16(data: &[u8], offset: usize) -> u16 {
+    u16::from_be_bytes([data[offset], data[offset + 1]])
+}
+
+/// read a big endian u32, the caller checked the length.
+pub(crate) fn be32(data: &[u8], offset: usize) -> u32 {
+    u32::from_be_bytes([
+        data[offset],
+        data[offset + 1],
+        data[offset + 2],
+        data[offset + 3],
+    ])
+}
+
+/// sum of the big endian 16 bit words, an odd last byte is padded with zero.
+fn sum(data: &[u8]) -> u32 {
+    let mut words = data.chunks_exact(2);
+    let mut sum = words.by_ref().fold(0u32, |sum, word| {
+        sum.wrapping_add(u32::from(u16::from_be_bytes([word[0], word[1]])))
+    });
+    if let [last] = words.remainder() {
+        sum = sum.wrapping_add(u32::from(*last) << 8);
+    }
+    sum
+}
+
+/// internet checksum over the data, started with a partial sum like a pseudo header.
+/// returns 0 if data with a valid checksum field is summed.
+pub(crate) fn checksum(init: u32, data: &[u8]) -> u16 {
+    let mut sum = u64::from(init) + u64::from(sum(data));
+    while sum > 0xFFFF {
+        sum = (sum & 0xFFFF) + (sum >> 16);
+    }
+    #[allow(clippy::cast_possible_truncation)]
+    !(sum as u16)
+}
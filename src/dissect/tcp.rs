@@ -0,0 +1,237 @@
+//! TCP segments with flags and options.
+use crate::dissect::{be16, be32, need};
+use crate::Error;
+
+/// protocol number of TCP
+pub const IPPROTO_TCP: u8 = 6;
+/// size of a TCP header without options
+pub const TCP_HEADER_SIZE: usize = 20;
+
+const OPTION_END: u8 = 0;
+const OPTION_NOP: u8 = 1;
+const OPTION_MSS: u8 = 2;
+const OPTION_WINDOW_SCALE: u8 = 3;
+const OPTION_SACK_PERMITTED: u8 = 4;
+const OPTION_SACK: u8 = 5;
+const OPTION_TIMESTAMPS: u8 = 8;
+
+/// TCP control flags.
+#[derive(Debug, Default, PartialEq, Eq, Copy, Clone)]
+pub struct TcpFlags(pub u16);
+
+impl TcpFlags {
+    /// no more data from sender
+    pub const FIN: u16 = 0x001;
+    /// synchronize sequence numbers
+    pub const SYN: u16 = 0x002;
+    /// reset the connection
+    pub const RST: u16 = 0x004;
+    /// push function
+    pub const PSH: u16 = 0x008;
+    /// acknowledgment field is significant
+    pub const ACK: u16 = 0x010;
+    /// urgent pointer field is significant
+    pub const URG: u16 = 0x020;
+    /// ECN echo
+    pub const ECE: u16 = 0x040;
+    /// congestion window reduced
+    pub const CWR: u16 = 0x080;
+    /// ECN nonce, historic
+    pub const NS: u16 = 0x100;
+
+    /// all given flags are set.
+    #[must_use]
+    pub fn contains(&self, flags: u16) -> bool {
+        self.0 & flags == flags
+    }
+}
+
+/// TCP option.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum TcpOption<'a> {
+    /// maximum segment size
+    Mss(u16),
+    /// window scale shift count
+    WindowScale(u8),
+    /// selective acknowledgment permitted
+    SackPermitted,
+    /// selective acknowledgment blocks
+    Sack(SackBlocks<'a>),
+    /// timestamp value and echo reply
+    Timestamps {
+        /// timestamp value of the sender
+        value: u32,
+        /// echoed timestamp of the peer
+        echo: u32,
+    },
+    /// other option with kind and data after kind and length
+    Other {
+        /// option kind
+        kind: u8,
+        /// option data
+        data: &'a [u8],
+    },
+}
+
+/// iterator over the left and right edges of SACK blocks.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct SackBlocks<'a>(&'a [u8]);
+
+impl Iterator for SackBlocks<'_> {
+    type Item = (u32, u32);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.0.len() < 8 {
+            return None;
+        }
+        let block = (be32(self.0, 0), be32(self.0, 4));
+        self.0 = &self.0[8..];
+        Some(block)
+    }
+}
+
+/// TCP segment.
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub struct TcpSegment<'a> {
+    data: &'a [u8],
+    header_len: usize,
+}
+
+impl<'a> TcpSegment<'a> {
+    /// decode a TCP segment.
+    /// # Errors
+    /// will return [`Error::Truncated`] if the header is cut off
+    /// and [`Error::InvalidPacket`] if the data offset or an option is invalid.
+    pub fn parse(data: &'a [u8]) -> Result<Self, Error> {
+        need("tcp", data, TCP_HEADER_SIZE)?;
+        let header_len = usize::from(data[12] >> 4) * 4;
+        if header_len < TCP_HEADER_SIZE {
+            return Err(Error::InvalidPacket("tcp data offset below 20 bytes"));
+        }
+        need("tcp", data, header_len)?;
+        let segment = TcpSegment { data, header_len };
+        for option in segment.raw_options() {
+            option?;
+        }
+        Ok(segment)
+    }
+
+    /// source port
+    #[must_use]
+    pub fn source_port(&self) -> u16 {
+        be16(self.data, 0)
+    }
+
+    /// destination port
+    #[must_use]
+    pub fn destination_port(&self) -> u16 {
+        be16(self.data, 2)
+    }
+
+    /// sequence number
+    #[must_use]
+    pub fn sequence(&self) -> u32 {
+        be32(self.data, 4)
+    }
+
+    /// acknowledgment number
+    #[must_use]
+    pub fn acknowledgment(&self) -> u32 {
+        be32(self.data, 8)
+    }
+
+    /// header length in bytes with options
+    #[must_use]
+    pub fn header_len(&self) -> usize {
+        self.header_len
+    }
+
+    /// control flags
+    #[must_use]
+    pub fn flags(&self) -> TcpFlags {
+        TcpFlags(be16(self.data, 12) & 0x01FF)
+    }
+
+    /// receive window, not scaled
+    #[must_use]
+    pub fn window(&self) -> u16 {
+        be16(self.data, 14)
+    }
+
+    /// checksum
+    #[must_use]
+    pub fn checksum(&self) -> u16 {
+        be16(self.data, 16)
+    }
+
+    /// urgent pointer
+    #[must_use]
+    pub fn urgent_pointer(&self) -> u16 {
+        be16(self.data, 18)
+    }
+
+    /// options until the end of option list, padding is skipped.
+    pub fn options(&self) -> impl Iterator<Item = TcpOption<'a>> + 'a {
+        self.raw_options().map_while(Result::ok)
+    }
+
+    fn raw_options(&self) -> Options<'a> {
+        Options(&self.data[TCP_HEADER_SIZE..self.header_len])
+    }
+
+    /// header bytes with options
+    #[must_use]
+    pub fn header(&self) -> &'a [u8] {
+        &self.data[..self.header_len]
+    }
+
+    /// captured payload
+    #[must_use]
+    pub fn payload(&self) -> &'a [u8] {
+        &self.data[self.header_len..]
+    }
+
+    /// header and payload, the data covered by the checksum
+    #[must_use]
+    pub fn data(&self) -> &'a [u8] {
+        self.data
+    }
+}
+
+/// iterator over the raw option bytes.
+struct Options<'a>(&'a [u8]);
+
+impl<'a> Iterator for Options<'a> {
+    type Item = Result<TcpOption<'a>, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            match *self.0.first()? {
+                OPTION_END => return None,
+                OPTION_NOP => self.0 = &self.0[1..],
+                kind => {
+                    let len = self.0.get(1).map_or(0, |len| usize::from(*len));
+                    if len < 2 || len > self.0.len() {
+                        self.0 = &[];
+                        return Some(Err(Error::InvalidPacket("tcp option length")));
+                    }
+                    let data = &self.0[2..len];
+                    self.0 = &self.0[len..];
+                    return Some(Ok(match (kind, data.len()) {
+                        (OPTION_MSS, 2) => TcpOption::Mss(be16(data, 0)),
+                        (OPTION_WINDOW_SCALE, 1) => TcpOption::WindowScale(data[0]),
+                        (OPTION_SACK_PERMITTED, 0) => TcpOption::SackPermitted,
+                        (OPTION_SACK, n) if n.checked_rem(8) == Some(0) => {
+                            TcpOption::Sack(SackBlocks(data))
+                        }
+                        (OPTION_TIMESTAMPS, 8) => TcpOption::Timestamps {
+                            value: be32(data, 0),
+                            echo: be32(data, 4),
+                        },
+                        _ => TcpOption::Other { kind, data },
+                    }));
+                }
+            }
+        }
+    }
+}
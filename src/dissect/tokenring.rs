@@ -0,0 +1,176 @@
+//! IEEE 802.5 Token Ring frames with routing information field.
+//!
+//! the frame starts at the access control field, start and end delimiter and frame check
+//! sequence are not captured. source routed frames have the highest bit of the source
+//! address set and a routing information field after the source address.
+use crate::dissect::ethernet::Llc;
+use crate::dissect::{be16, need, MacAddr};
+use crate::Error;
+
+/// size of a Token Ring header without routing information
+pub const TOKEN_RING_HEADER_SIZE: usize = 14;
+
+/// frame type of LLC frames in the frame control field
+const FRAME_TYPE_LLC: u8 = 0x40;
+/// routing information indicator in the source address
+const ROUTING_INDICATOR: u8 = 0x80;
+
+/// routing information field of a source routed frame.
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub struct RoutingInformation<'a> {
+    data: &'a [u8],
+}
+
+impl<'a> RoutingInformation<'a> {
+    /// broadcast indicator, 0 for a specifically routed frame
+    #[must_use]
+    pub fn broadcast(&self) -> u8 {
+        self.data[0] >> 5
+    }
+
+    /// number of route designators, the length of the field in bytes is `data().len()`
+    #[must_use]
+    pub fn len(&self) -> usize {
+        (self.data.len() - 2) / 2
+    }
+
+    /// has no route designators
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// the route designators are read from the last to the first
+    #[must_use]
+    pub fn direction(&self) -> bool {
+        self.data[1] & 0x80 != 0
+    }
+
+    /// largest frame bits
+    #[must_use]
+    pub fn largest_frame(&self) -> u8 {
+        (self.data[1] >> 4) & 0x07
+    }
+
+    /// ring numbers and bridge numbers of the route.
+    pub fn route_designators(&self) -> impl Iterator<Item = (u16, u8)> + 'a {
+        #[allow(clippy::cast_possible_truncation)]
+        self.data[2..].chunks_exact(2).map(|rd| {
+            let rd = be16(rd, 0);
+            (rd >> 4, (rd & 0x0F) as u8)
+        })
+    }
+
+    /// raw field bytes
+    #[must_use]
+    pub fn data(&self) -> &'a [u8] {
+        self.data
+    }
+}
+
+/// IEEE 802.5 Token Ring frame.
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub struct TokenRingFrame<'a> {
+    data: &'a [u8],
+    header_len: usize,
+    llc: Option<Llc<'a>>,
+}
+
+impl<'a> TokenRingFrame<'a> {
+    /// decode a Token Ring frame, LLC frames are decoded with their LLC header.
+    /// # Errors
+    /// will return [`Error::Truncated`] if the header, routing information or LLC header is cut off
+    /// and [`Error::InvalidPacket`] if the routing information length is invalid.
+    pub fn parse(data: &'a [u8]) -> Result<Self, Error> {
+        need("token ring", data, TOKEN_RING_HEADER_SIZE)?;
+        let mut header_len = TOKEN_RING_HEADER_SIZE;
+        if data[8] & ROUTING_INDICATOR != 0 {
+            need("token ring routing", data, header_len + 2)?;
+            let len = usize::from(data[header_len] & 0x1F);
+            if len < 2 || len % 2 != 0 {
+                return Err(Error::InvalidPacket(
+                    "token ring routing information length",
+                ));
+            }
+            header_len += len;
+            need("token ring routing", data, header_len)?;
+        }
+        let llc = if data[1] & 0xC0 == FRAME_TYPE_LLC {
+            Some(Llc::parse(&data[header_len..])?)
+        } else {
+            None
+        };
+        Ok(TokenRingFrame {
+            data,
+            header_len,
+            llc,
+        })
+    }
+
+    /// access control
+    #[must_use]
+    pub fn access_control(&self) -> u8 {
+        self.data[0]
+    }
+
+    /// frame control
+    #[must_use]
+    pub fn frame_control(&self) -> u8 {
+        self.data[1]
+    }
+
+    /// is an LLC frame and not a MAC frame
+    #[must_use]
+    pub fn is_llc(&self) -> bool {
+        self.llc.is_some()
+    }
+
+    /// destination address
+    #[must_use]
+    pub fn destination(&self) -> MacAddr {
+        MacAddr::from_slice(&self.data[2..])
+    }
+
+    /// source address without the routing information indicator
+    #[must_use]
+    pub fn source(&self) -> MacAddr {
+        let mut source = MacAddr::from_slice(&self.data[8..]);
+        source.0[0] &= !ROUTING_INDICATOR;
+        source
+    }
+
+    /// routing information field of a source routed frame
+    #[must_use]
+    pub fn routing_information(&self) -> Option<RoutingInformation<'a>> {
+        (self.header_len > TOKEN_RING_HEADER_SIZE).then(|| RoutingInformation {
+            data: &self.data[TOKEN_RING_HEADER_SIZE..self.header_len],
+        })
+    }
+
+    /// LLC header of an LLC frame
+    #[must_use]
+    pub fn llc(&self) -> Option<Llc<'a>> {
+        self.llc
+    }
+
+    /// ethertype of the payload from a SNAP header
+    #[must_use]
+    pub fn ether_type(&self) -> Option<u16> {
+        self.llc.and_then(|llc| llc.ether_type())
+    }
+
+    /// Token Ring header bytes with routing information, without LLC header
+    #[must_use]
+    pub fn header(&self) -> &'a [u8] {
+        &self.data[..self.header_len]
+    }
+
+    /// payload after all headers, the MAC frame information for MAC frames
+    #[must_use]
+    pub fn payload(&self) -> &'a [u8] {
+        match self.llc {
+            Some(llc) => llc.payload(),
+            None => &self.data[self.header_len..],
+        }
+    }
+}
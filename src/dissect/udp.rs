@@ -0,0 +1,67 @@
+//! UDP datagrams.
+use crate::dissect::{be16, need};
+use crate::Error;
+
+/// protocol number of UDP
+pub const IPPROTO_UDP: u8 = 17;
+/// size of a UDP header
+pub const UDP_HEADER_SIZE: usize = 8;
+
+/// UDP datagram.
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub struct UdpDatagram<'a> {
+    data: &'a [u8],
+}
+
+impl<'a> UdpDatagram<'a> {
+    /// decode a UDP datagram, the payload ends at the length or at the end of the capture.
+    /// # Errors
+    /// will return [`Error::Truncated`] if the header is cut off
+    /// and [`Error::InvalidPacket`] if the length is below the header size.
+    pub fn parse(data: &'a [u8]) -> Result<Self, Error> {
+        need("udp", data, UDP_HEADER_SIZE)?;
+        let len = usize::from(be16(data, 4));
+        if len < UDP_HEADER_SIZE {
+            return Err(Error::InvalidPacket("udp length below 8 bytes"));
+        }
+        Ok(UdpDatagram {
+            data: &data[..len.min(data.len())],
+        })
+    }
+
+    /// source port
+    #[must_use]
+    pub fn source_port(&self) -> u16 {
+        be16(self.data, 0)
+    }
+
+    /// destination port
+    #[must_use]
+    pub fn destination_port(&self) -> u16 {
+        be16(self.data, 2)
+    }
+
+    /// length of header and payload on the wire
+    #[must_use]
+    pub fn length(&self) -> u16 {
+        be16(self.data, 4)
+    }
+
+    /// checksum, 0 if the sender did not compute it over IPv4
+    #[must_use]
+    pub fn checksum(&self) -> u16 {
+        be16(self.data, 6)
+    }
+
+    /// captured payload
+    #[must_use]
+    pub fn payload(&self) -> &'a [u8] {
+        &self.data[UDP_HEADER_SIZE..]
+    }
+
+    /// header and payload, the data covered by the checksum
+    #[must_use]
+    pub fn data(&self) -> &'a [u8] {
+        self.data
+    }
+}
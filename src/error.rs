@@ -1,5 +1,6 @@
 //! custom errors that can happen using snoop.
 
+use crate::format::DataLinkType;
 use std::error;
 use std::fmt;
 use std::io;
@@ -9,12 +10,18 @@ const UNKNOWN_MAGIC: &str = "unknown snoop magic bytes";
 const UNKNOWN_VERSION: &str = "unknown snoop format version";
 const ORIGINAL_LEN_EXCEEDED: &str = "capture length exceeds original packet length";
 const CAPTURE_LEN_EXCEEDED: &str = "capture length exceeds max capture length";
-const INVALID_RECORD_LENGTH: &str = "invalid record length calculation with original len";
+const INVALID_RECORD_LENGTH: &str = "invalid record length calculation with included len";
 const INVALID_PAD_LENGTH: &str =
     "invalid pad length. only 4 bytes as pads are supported in this implementation";
 const EOF: &str = "end of file";
 const UNEXEOF: &str = "unexpected end of file";
 const TIME_EPOCH_EXEEDED: &str = "u32 time epoch exeeded use u64 instad";
+const UNSUPPORTED_LINK_TYPE: &str = "unsupported link type";
+const INVALID_FORMAT: &str = "invalid capture format";
+const INVALID_MICROSECONDS: &str = "timestamp microseconds exceed one second";
+const LINK_TYPE_MISMATCH: &str = "link types of the captures differ";
+const TRUNCATED: &str = "packet truncated";
+const INVALID_PACKET: &str = "invalid packet";
 
 /// Errors that can happen inside snoop.
 #[derive(Debug)]
@@ -41,6 +48,18 @@ pub enum Error {
     Time(time::SystemTimeError),
     /// the value of time in seconds not fit into u32 anymore
     TimeEpoch,
+    /// the link type has no representation in the target format
+    UnsupportedLinkType(u32),
+    /// the data of a foreign capture format is invalid
+    InvalidFormat(&'static str),
+    /// the microseconds of a timestamp are not below one second
+    InvalidMicroseconds(u32),
+    /// the captures have different link types, the expected and the found link type
+    LinkTypeMismatch(DataLinkType, DataLinkType),
+    /// a protocol header is cut off, the protocol, the needed and the available bytes
+    Truncated(&'static str, usize, usize),
+    /// a protocol header is malformed
+    InvalidPacket(&'static str),
 }
 
 impl fmt::Display for Error {
@@ -57,6 +76,14 @@ impl fmt::Display for Error {
             Error::Io(ref err) => err.fmt(f),
             Error::Time(ref err) => err.fmt(f),
             Error::TimeEpoch => write!(f, "{TIME_EPOCH_EXEEDED}"),
+            Error::UnsupportedLinkType(t) => write!(f, "{UNSUPPORTED_LINK_TYPE} {t}"),
+            Error::InvalidFormat(s) => write!(f, "{INVALID_FORMAT}: {s}"),
+            Error::InvalidMicroseconds(n) => write!(f, "{INVALID_MICROSECONDS}: {n}"),
+            Error::LinkTypeMismatch(a, b) => write!(f, "{LINK_TYPE_MISMATCH}: {a:?} and {b:?}"),
+            Error::Truncated(p, n, a) => {
+                write!(f, "{TRUNCATED}: {p} header needs {n} bytes, {a} captured")
+            }
+            Error::InvalidPacket(s) => write!(f, "{INVALID_PACKET}: {s}"),
         }
     }
 }
@@ -65,7 +92,7 @@ impl error::Error for Error {} // add source: https://doc.rust-lang.org/std/erro
 
 impl From<Error> for io::Error {
     fn from(size_err: Error) -> io::Error {
-        io::Error::new(io::ErrorKind::Other, size_err)
+        io::Error::other(size_err)
     }
 }
 
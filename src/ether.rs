@@ -0,0 +1,22 @@
+//! walk the Ethernet header and VLAN tags of a frame to the network layer.
+
+pub(crate) const ETHERTYPE_IPV4: u16 = 0x0800;
+pub(crate) const ETHERTYPE_IPV6: u16 = 0x86DD;
+pub(crate) const ETHERTYPE_VLAN: u16 = 0x8100;
+pub(crate) const ETHERTYPE_QINQ: u16 = 0x88A8;
+
+/// ethertype and offset of the IPv4 or IPv6 packet in an Ethernet frame,
+/// VLAN and `QinQ` tags are skipped. `None` for other ethertypes or a truncated header.
+pub(crate) fn ip_offset(frame: &[u8]) -> Option<(u16, usize)> {
+    let mut offset = 12;
+    loop {
+        let ethertype = frame.get(offset..offset + 2)?;
+        let ethertype = u16::from_be_bytes([ethertype[0], ethertype[1]]);
+        offset += 2;
+        match ethertype {
+            ETHERTYPE_VLAN | ETHERTYPE_QINQ => offset += 2,
+            ETHERTYPE_IPV4 | ETHERTYPE_IPV6 => return Some((ethertype, offset)),
+            _ => return None,
+        }
+    }
+}
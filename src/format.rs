@@ -57,7 +57,7 @@ impl TryFrom<u32> for DataLinkType {
 }
 /// representing the file header with version and link type.
 /// magic bytes are skipped
-#[derive(Debug, Default)]
+#[derive(Debug, Default, PartialEq, Copy, Clone)]
 pub struct SnoopHeader {
     /// snoop version
     pub version: u32,
@@ -66,7 +66,7 @@ pub struct SnoopHeader {
 }
 
 /// contains the captured informations of the packet.
-#[derive(Debug, Default, Clone)]
+#[derive(Debug, Default, PartialEq, Clone)]
 pub struct PacketHeader {
     /// OriginalLength uint32 4
     pub original_length: u32,
@@ -83,6 +83,7 @@ pub struct PacketHeader {
 }
 
 /// represents the captured packet as header and data.
+#[derive(Debug, Default, PartialEq, Clone)]
 pub struct SnoopPacket {
     /// packet header
     pub header: PacketHeader,
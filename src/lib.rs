@@ -19,24 +19,101 @@
 //!
 //! ### default features
 //!
-//! * **parser**: format parser
+//! * **parser**: format parser and generic capture source and sink traits
 //!
 //! ### optional features
 //!
-//! * **read**: read from a reader like files or buf
-//! * **write**: write to a writer like files or buf
-//! * **full**: include parser, reader and writer
+//! * **read**: read from a reader like files or buf, merge multiple captures by time,
+//!   read multiple files as one capture
+//! * **write**: write to a writer like files or buf, also rotating over multiple files
+//! * **edit**: edit captures like `editcap`, split into multiple files, slice by time or index,
+//!   sort by timestamp, remove duplicates, shift timestamps,
+//!   truncate to a snaplen, change the link type, sample packets
+//! * **convert**: convert between snoop, pcap and pcapng, open files of any of these formats
+//! * **gzip**: read gzip compressed captures
+//! * **zstd**: read zstd compressed captures, also while they are written,
+//!   with **write** also write and randomly access zstd seekable captures
+//! * **xz**: read xz compressed captures
+//! * **dissect**: decode Ethernet, Token Ring, FDDI and HDLC frames, ARP, IPv4, IPv6,
+//!   TCP, UDP and ICMP, build an IP to MAC inventory from ARP
+//! * **rayon**: read many files or chunks of a large capture in parallel
+//! * **chrono**: convert packet timestamps to and from `chrono` types
+//! * **time**: convert packet timestamps to and from `time` types
+//! * **full**: include parser, reader, writer, editor, converter and dissector
 
 pub mod error;
 pub mod format;
+pub mod timestamp;
+
+mod civil;
+
+#[cfg(feature = "edit")]
+mod ether;
 
 use crate::error::Error;
 
 #[cfg(feature = "parse")]
 pub mod parse;
 
+#[cfg(feature = "parse")]
+pub mod capture;
+
 #[cfg(feature = "read")]
 pub mod read;
 
+#[cfg(feature = "read")]
+pub mod decompress;
+
+#[cfg(feature = "read")]
+pub mod merge;
+
+#[cfg(feature = "read")]
+pub mod multi;
+
+#[cfg(feature = "rayon")]
+pub mod parallel;
+
+#[cfg(feature = "dissect")]
+pub mod dissect;
+
 #[cfg(feature = "write")]
 pub mod write;
+
+#[cfg(feature = "write")]
+pub mod rotate;
+
+#[cfg(all(feature = "zstd", feature = "write"))]
+pub mod seekable;
+
+#[cfg(feature = "edit")]
+pub mod split;
+
+#[cfg(feature = "edit")]
+pub mod slice;
+
+#[cfg(feature = "edit")]
+pub mod reorder;
+
+#[cfg(feature = "edit")]
+pub mod dedup;
+
+#[cfg(feature = "edit")]
+pub mod timeshift;
+
+#[cfg(feature = "edit")]
+pub mod snaplen;
+
+#[cfg(feature = "edit")]
+pub mod relink;
+
+#[cfg(feature = "edit")]
+pub mod sample;
+
+#[cfg(feature = "convert")]
+pub mod convert;
+
+#[cfg(feature = "convert")]
+pub mod detect;
+
+#[cfg(feature = "convert")]
+pub use detect::open;
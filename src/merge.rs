@@ -0,0 +1,190 @@
+//! merge multiple snoop captures into one time ordered stream, like `mergecap`.
+//!
+//! the packets of all readers are interleaved by a k-way merge on the packet timestamps.
+//! packets with the same timestamp keep the order of the readers.
+//! the cumulative drops of the merged packets are the sum of the drops of all readers,
+//! unless [`MergeOptions::keep_drops`] is set.
+use crate::format::{DataLinkType, SnoopHeader, SnoopPacket};
+use crate::read::Reader;
+use crate::Error;
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+use std::io::Read;
+
+/// what to do if the link types of the merged captures differ.
+#[derive(Debug, Default, PartialEq, Copy, Clone)]
+pub enum LinkTypePolicy {
+    /// return [`Error::LinkTypeMismatch`]
+    #[default]
+    Error,
+    /// use the link type of the first capture
+    First,
+    /// use the given link type
+    Use(DataLinkType),
+}
+
+/// options of the merge.
+#[derive(Debug, Default, Clone)]
+pub struct MergeOptions {
+    /// append the captures in the given order instead of interleaving them by time
+    pub concatenate: bool,
+    /// link type policy for captures with different link types
+    pub link_type: LinkTypePolicy,
+    /// keep the cumulative drops of each packet instead of summing them over all captures
+    pub keep_drops: bool,
+}
+
+/// iterator over the merged packets of multiple readers.
+#[derive(Debug)]
+pub struct Merger<R> {
+    readers: Vec<Reader<R>>,
+    header: SnoopHeader,
+    concatenate: bool,
+    keep_drops: bool,
+    // next packet of each reader
+    pending: Vec<Option<SnoopPacket>>,
+    // timestamp and reader index of the pending packets
+    heap: BinaryHeap<Reverse<(u32, u32, usize)>>,
+    drops: Vec<u32>,
+    current: usize,
+    started: bool,
+}
+
+impl<R> Merger<R>
+where
+    R: Read,
+{
+    /// create a new merger over the readers.
+    /// # Errors
+    /// will return [`Error::LinkTypeMismatch`] if the link types differ and the policy is [`LinkTypePolicy::Error`]
+    /// and [`Error::InvalidFormat`] if no reader is given.
+    pub fn new(readers: Vec<Reader<R>>, options: &MergeOptions) -> Result<Self, Error> {
+        let first = readers
+            .first()
+            .ok_or(Error::InvalidFormat("no captures to merge"))?
+            .header()
+            .link_type;
+        let link_type = match options.link_type {
+            LinkTypePolicy::Error => {
+                if let Some(r) = readers.iter().find(|r| r.header().link_type != first) {
+                    return Err(Error::LinkTypeMismatch(first, r.header().link_type));
+                }
+                first
+            }
+            LinkTypePolicy::First => first,
+            LinkTypePolicy::Use(link_type) => link_type,
+        };
+        Ok(Self {
+            header: SnoopHeader {
+                link_type,
+                ..*readers[0].header()
+            },
+            pending: vec![None; readers.len()],
+            drops: vec![0; readers.len()],
+            readers,
+            concatenate: options.concatenate,
+            keep_drops: options.keep_drops,
+            heap: BinaryHeap::new(),
+            current: 0,
+            started: false,
+        })
+    }
+
+    /// get a reference to the snoop file format header of the merged capture
+    #[must_use]
+    pub fn header(&self) -> &SnoopHeader {
+        &self.header
+    }
+
+    /// read the next packet of a reader into the heap.
+    fn fill(&mut self, idx: usize) -> Result<(), Error> {
+        if let Some(packet) = self.readers[idx].next().transpose()? {
+            self.heap.push(Reverse((
+                packet.header.timestamp_seconds,
+                packet.header.timestamp_microseconds,
+                idx,
+            )));
+            self.pending[idx] = Some(packet);
+        }
+        Ok(())
+    }
+
+    /// set the cumulative drops of a packet to the sum over all readers.
+    fn drops(&mut self, idx: usize, mut packet: SnoopPacket) -> SnoopPacket {
+        if self.keep_drops {
+            return packet;
+        }
+        self.drops[idx] = packet.header.cumulative_drops;
+        packet.header.cumulative_drops = self
+            .drops
+            .iter()
+            .fold(0u32, |sum, d| sum.saturating_add(*d));
+        packet
+    }
+
+    fn next_concatenated(&mut self) -> Option<Result<SnoopPacket, Error>> {
+        while self.current < self.readers.len() {
+            match self.readers[self.current].next() {
+                Some(Ok(packet)) => return Some(Ok(self.drops(self.current, packet))),
+                Some(Err(e)) => return Some(Err(e)),
+                None => self.current += 1,
+            }
+        }
+        None
+    }
+
+    fn next_merged(&mut self) -> Option<Result<SnoopPacket, Error>> {
+        if !self.started {
+            self.started = true;
+            for idx in 0..self.readers.len() {
+                if let Err(e) = self.fill(idx) {
+                    return Some(Err(e));
+                }
+            }
+        }
+        let Reverse((_, _, idx)) = self.heap.pop()?;
+        let packet = self.pending[idx].take()?;
+        if let Err(e) = self.fill(idx) {
+            return Some(Err(e));
+        }
+        Some(Ok(self.drops(idx, packet)))
+    }
+}
+
+impl<R> Iterator for Merger<R>
+where
+    R: Read,
+{
+    type Item = Result<SnoopPacket, Error>;
+
+    /// return the next packet of the merged captures until all readers reach a valid eof or an error occurs.
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.concatenate {
+            self.next_concatenated()
+        } else {
+            self.next_merged()
+        }
+    }
+}
+
+impl<R> crate::capture::CaptureSource for Merger<R>
+where
+    R: Read,
+{
+    fn link_type(&self) -> DataLinkType {
+        self.header.link_type
+    }
+
+    fn next_packet(&mut self) -> Option<Result<crate::capture::Packet, Error>> {
+        self.next()
+            .map(|r| r.and_then(crate::capture::Packet::try_from))
+    }
+}
+
+/// merge the readers into one time ordered stream.
+/// # Errors
+/// will return [`Error::LinkTypeMismatch`] if the link types differ and the policy is [`LinkTypePolicy::Error`]
+/// and [`Error::InvalidFormat`] if no reader is given.
+pub fn merge<R: Read>(readers: Vec<Reader<R>>) -> Result<Merger<R>, Error> {
+    Merger::new(readers, &MergeOptions::default())
+}
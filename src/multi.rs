@@ -0,0 +1,148 @@
+//! read an ordered set of snoop files, like a directory of rotated captures, as one packet stream.
+//!
+//! every file must have the link type of the first file, empty files are skipped.
+//! each packet is returned with the index of the file it came from.
+use crate::capture::{CaptureSource, Packet};
+use crate::format::{DataLinkType, SnoopHeader, SnoopPacket};
+use crate::read::Reader;
+use crate::Error;
+use std::fs::{self, File};
+use std::io::BufReader;
+use std::path::{Path, PathBuf};
+
+/// reader over multiple snoop files in the given order.
+#[derive(Debug)]
+pub struct MultiReader {
+    paths: Vec<PathBuf>,
+    header: Option<SnoopHeader>,
+    reader: Option<Reader<BufReader<File>>>,
+    current: usize,
+}
+
+impl MultiReader {
+    /// create a new reader over the files in the given order and open the first non empty file.
+    /// # Errors
+    /// will return [`Error`] if the first non empty file can not be opened or is not in snoop format.
+    pub fn new<I, P>(paths: I) -> Result<Self, Error>
+    where
+        I: IntoIterator<Item = P>,
+        P: Into<PathBuf>,
+    {
+        let mut r = Self {
+            paths: paths.into_iter().map(Into::into).collect(),
+            header: None,
+            reader: None,
+            current: 0,
+        };
+        r.open_next()?;
+        Ok(r)
+    }
+
+    /// create a new reader over all files of a directory ordered by file name.
+    /// # Errors
+    /// will return [`Error::Io`] if the directory can not be read
+    /// and [`Error`] if the first non empty file can not be opened or is not in snoop format.
+    pub fn from_dir<P: AsRef<Path>>(dir: P) -> Result<Self, Error> {
+        let mut paths = Vec::new();
+        for entry in fs::read_dir(dir).map_err(Error::Io)? {
+            let entry = entry.map_err(Error::Io)?;
+            if entry.file_type().map_err(Error::Io)?.is_file() {
+                paths.push(entry.path());
+            }
+        }
+        paths.sort();
+        Self::new(paths)
+    }
+
+    /// snoop header of the first non empty file, `None` if all files are empty.
+    #[must_use]
+    pub fn header(&self) -> Option<&SnoopHeader> {
+        self.header.as_ref()
+    }
+
+    /// paths of all files in read order.
+    #[must_use]
+    pub fn paths(&self) -> &[PathBuf] {
+        &self.paths
+    }
+
+    /// path of the file with the index returned with the packets.
+    pub fn path(&self, file: usize) -> Option<&Path> {
+        self.paths.get(file).map(PathBuf::as_path)
+    }
+
+    /// index of the file which is read at the moment.
+    #[must_use]
+    pub fn current(&self) -> usize {
+        self.current
+    }
+
+    /// open the next non empty file starting at the current index.
+    fn open_next(&mut self) -> Result<(), Error> {
+        while let Some(path) = self.paths.get(self.current) {
+            let fp = File::open(path).map_err(Error::Io)?;
+            if fp.metadata().map_err(Error::Io)?.len() == 0 {
+                self.current += 1;
+                continue;
+            }
+            let reader = Reader::new(BufReader::new(fp))?;
+            match self.header {
+                Some(h) if h.link_type != reader.header().link_type => {
+                    return Err(Error::LinkTypeMismatch(
+                        h.link_type,
+                        reader.header().link_type,
+                    ));
+                }
+                Some(_) => {}
+                None => self.header = Some(*reader.header()),
+            }
+            self.reader = Some(reader);
+            break;
+        }
+        Ok(())
+    }
+}
+
+impl Iterator for MultiReader {
+    type Item = Result<(usize, SnoopPacket), Error>;
+
+    /// return the next packet with the index of its file until the last file ends.
+    /// after an error the next call continues with the next file.
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let Some(reader) = self.reader.as_mut() else {
+                if self.current >= self.paths.len() {
+                    return None;
+                }
+                if let Err(e) = self.open_next() {
+                    self.current += 1;
+                    return Some(Err(e));
+                }
+                continue;
+            };
+            match reader.next() {
+                Some(Ok(packet)) => return Some(Ok((self.current, packet))),
+                Some(Err(e)) => {
+                    self.reader = None;
+                    self.current += 1;
+                    return Some(Err(e));
+                }
+                None => {
+                    self.reader = None;
+                    self.current += 1;
+                }
+            }
+        }
+    }
+}
+
+impl CaptureSource for MultiReader {
+    fn link_type(&self) -> DataLinkType {
+        self.header.map(|h| h.link_type).unwrap_or_default()
+    }
+
+    fn next_packet(&mut self) -> Option<Result<Packet, Error>> {
+        self.next()
+            .map(|r| r.and_then(|(_, packet)| Packet::try_from(packet)))
+    }
+}
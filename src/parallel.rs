@@ -0,0 +1,404 @@
+//! read many snoop files in parallel with rayon, one [`Reader`] per file on the worker threads.
+//!
+//! [`par_packets`] returns a parallel iterator over the packets of all files in no particular order.
+//! [`OrderedPackets`] parses a window of files in parallel and returns the packets in file order,
+//! like the [`crate::multi::MultiReader`].
+//!
+//! [`ChunkedCapture`] splits a single large capture, a memory mapped slice or a seekable file,
+//! into chunks which are parsed concurrently. a chunk starts at the first offset where a chain of
+//! consecutive valid packet headers follows, so no index is needed. the packets are returned in
+//! capture order.
+use crate::format::{
+    PacketHeader, SnoopHeader, SnoopPacket, MAX_CAPTURE_LEN, MAX_CAPTURE_PADS, SNOOP_HEADER_SIZE,
+    SNOOP_PACKET_HEADER_SIZE,
+};
+use crate::parse::Parser;
+use crate::read::Reader;
+use crate::Error;
+use rayon::iter::Either;
+use rayon::prelude::*;
+use std::collections::VecDeque;
+use std::fs::File;
+use std::io::{BufReader, Read, Seek, SeekFrom};
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+
+/// packet with the index of the file it came from
+pub type FilePacket = (usize, SnoopPacket);
+
+fn open(path: &Path) -> Result<Reader<BufReader<File>>, Error> {
+    Reader::new(BufReader::new(File::open(path).map_err(Error::Io)?))
+}
+
+/// parallel iterator over the packets of all files with the index of their file.
+/// a file which can not be opened returns one error, a file stops at its first invalid packet.
+pub fn par_packets<P>(paths: &[P]) -> impl ParallelIterator<Item = Result<FilePacket, Error>> + '_
+where
+    P: AsRef<Path> + Sync,
+{
+    paths
+        .par_iter()
+        .enumerate()
+        .flat_map_iter(|(file, path)| match open(path.as_ref()) {
+            // the reader is not fused, stop after the first error like `read_file`
+            Ok(reader) => Either::Left(reader.scan(false, move |failed, p| {
+                if *failed {
+                    return None;
+                }
+                *failed = p.is_err();
+                Some(p.map(|p| (file, p)))
+            })),
+            Err(e) => Either::Right(std::iter::once(Err(e))),
+        })
+}
+
+/// read all packets of a file until the end or the first error.
+fn read_file(file: usize, path: &Path) -> Vec<Result<FilePacket, Error>> {
+    match open(path) {
+        Ok(reader) => {
+            let mut packets = Vec::new();
+            for p in reader {
+                let end = p.is_err();
+                packets.push(p.map(|p| (file, p)));
+                if end {
+                    break;
+                }
+            }
+            packets
+        }
+        Err(e) => vec![Err(e)],
+    }
+}
+
+/// iterator over the packets of all files in file order.
+/// a window of files is parsed in parallel and held in memory until its packets are returned.
+#[derive(Debug)]
+pub struct OrderedPackets {
+    paths: Vec<PathBuf>,
+    window: usize,
+    next_file: usize,
+    buffered: VecDeque<Result<FilePacket, Error>>,
+}
+
+impl OrderedPackets {
+    /// create a new ordered iterator which parses `window` files in parallel.
+    pub fn new<I, P>(paths: I, window: usize) -> Self
+    where
+        I: IntoIterator<Item = P>,
+        P: Into<PathBuf>,
+    {
+        Self {
+            paths: paths.into_iter().map(Into::into).collect(),
+            window: window.max(1),
+            next_file: 0,
+            buffered: VecDeque::new(),
+        }
+    }
+
+    /// paths of all files in read order.
+    #[must_use]
+    pub fn paths(&self) -> &[PathBuf] {
+        &self.paths
+    }
+}
+
+impl Iterator for OrderedPackets {
+    type Item = Result<FilePacket, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.buffered.is_empty() && self.next_file < self.paths.len() {
+            let end = (self.next_file + self.window).min(self.paths.len());
+            let first = self.next_file;
+            let files: Vec<Vec<Result<FilePacket, Error>>> = self.paths[first..end]
+                .par_iter()
+                .enumerate()
+                .map(|(i, path)| read_file(first + i, path))
+                .collect();
+            self.buffered.extend(files.into_iter().flatten());
+            self.next_file = end;
+        }
+        self.buffered.pop_front()
+    }
+}
+
+/// default size of a chunk, 64 MiB
+pub const DEFAULT_CHUNK_SIZE: u64 = 64 * 1024 * 1024;
+
+/// default number of consecutive packet headers which must be valid at a chunk boundary
+pub const DEFAULT_CHAIN: usize = 8;
+
+/// largest packet record with the maximum capture length and padding
+#[allow(clippy::cast_possible_truncation)]
+const MAX_RECORD_LEN: usize =
+    SNOOP_PACKET_HEADER_SIZE + (MAX_CAPTURE_LEN + MAX_CAPTURE_PADS) as usize;
+
+/// options to split a capture into chunks.
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub struct ChunkOptions {
+    /// approximate size of a chunk in bytes, rounded up to a multiple of 4
+    pub chunk_size: u64,
+    /// number of consecutive packet headers which must be valid to accept a chunk boundary,
+    /// fewer if the capture ends before
+    pub chain: usize,
+}
+
+impl Default for ChunkOptions {
+    fn default() -> Self {
+        Self {
+            chunk_size: DEFAULT_CHUNK_SIZE,
+            chain: DEFAULT_CHAIN,
+        }
+    }
+}
+
+/// reader over the packet records of one chunk.
+pub type ChunkReader<'a> = Reader<Box<dyn Read + Send + 'a>>;
+
+#[derive(Debug)]
+enum Input<'a> {
+    Slice(&'a [u8]),
+    File(PathBuf),
+}
+
+/// a capture split into chunks at packet record boundaries for parallel parsing.
+#[derive(Debug)]
+pub struct ChunkedCapture<'a> {
+    input: Input<'a>,
+    header: SnoopHeader,
+    chunks: Vec<Range<u64>>,
+}
+
+impl<'a> ChunkedCapture<'a> {
+    /// split a capture in memory, like a memory mapped file, into chunks.
+    /// # Errors
+    /// will return [`Error`] if the data is not in snoop format.
+    pub fn from_slice(data: &'a [u8], options: ChunkOptions) -> Result<Self, Error> {
+        let header = data
+            .get(..SNOOP_HEADER_SIZE)
+            .and_then(|h| h.try_into().ok())
+            .ok_or(Error::UnexpectedEof(data.len()))?;
+        let header = Parser::parse_header(header)?;
+        let len = data.len() as u64;
+        let starts: Vec<u64> = nominal_starts(len, options)
+            .into_par_iter()
+            .filter_map(|start| {
+                let start = usize::try_from(start).ok()?;
+                let end = data.len().min(start + probe_len(options.chain));
+                let offset = find_boundary(&data[start..end], end == data.len(), options.chain)?;
+                Some((start + offset) as u64)
+            })
+            .collect();
+        Ok(Self {
+            input: Input::Slice(data),
+            header,
+            chunks: chunks(starts, len),
+        })
+    }
+
+    /// return the chunk ranges as byte offsets into the capture.
+    #[must_use]
+    pub fn chunks(&self) -> &[Range<u64>] {
+        &self.chunks
+    }
+
+    /// snoop header of the capture.
+    #[must_use]
+    pub fn header(&self) -> &SnoopHeader {
+        &self.header
+    }
+
+    /// create a reader over the packet records of a chunk.
+    /// a record which crosses the end of the chunk returns [`Error::UnexpectedEof`].
+    /// # Errors
+    /// will return [`Error::Io`] if the file can not be opened
+    /// and [`Error::InvalidFormat`] if there is no chunk with this index.
+    pub fn reader(&self, chunk: usize) -> Result<ChunkReader<'a>, Error> {
+        let range = self
+            .chunks
+            .get(chunk)
+            .cloned()
+            .ok_or(Error::InvalidFormat("chunk index out of range"))?;
+        let r: Box<dyn Read + Send + 'a> = match &self.input {
+            Input::Slice(data) => {
+                let start = usize::try_from(range.start).unwrap_or(usize::MAX);
+                let end = usize::try_from(range.end).unwrap_or(usize::MAX);
+                Box::new(data.get(start..end).unwrap_or_default())
+            }
+            Input::File(path) => {
+                let mut fp = File::open(path).map_err(Error::Io)?;
+                fp.seek(SeekFrom::Start(range.start)).map_err(Error::Io)?;
+                Box::new(BufReader::new(fp).take(range.end - range.start))
+            }
+        };
+        Ok(Reader::with_header(r, self.header))
+    }
+
+    /// parallel iterator over one reader per chunk in capture order,
+    /// `collect` keeps the order of the chunks.
+    #[must_use]
+    pub fn par_readers(
+        &self,
+    ) -> impl IndexedParallelIterator<Item = Result<ChunkReader<'a>, Error>> + '_ {
+        (0..self.chunks.len())
+            .into_par_iter()
+            .map(|chunk| self.reader(chunk))
+    }
+
+    /// iterator over all packets in capture order.
+    /// as many chunks as rayon has threads are parsed in parallel and held in memory.
+    #[must_use]
+    pub fn packets(&self) -> ChunkedPackets<'_, 'a> {
+        ChunkedPackets {
+            capture: self,
+            next_chunk: 0,
+            buffered: VecDeque::new(),
+        }
+    }
+}
+
+impl ChunkedCapture<'static> {
+    /// split a capture file into chunks, each chunk is read with its own file handle.
+    /// # Errors
+    /// will return [`Error::Io`] if the file can not be read and [`Error`] if it is not in snoop format.
+    pub fn open<P: Into<PathBuf>>(path: P, options: ChunkOptions) -> Result<Self, Error> {
+        let path = path.into();
+        let mut fp = File::open(&path).map_err(Error::Io)?;
+        let len = fp.metadata().map_err(Error::Io)?.len();
+        let mut header = [0u8; SNOOP_HEADER_SIZE];
+        crate::read::read_full(&mut fp, &mut header)?;
+        let header = Parser::parse_header(&header)?;
+        let starts = nominal_starts(len, options)
+            .into_par_iter()
+            .map(|start| {
+                let mut fp = File::open(&path).map_err(Error::Io)?;
+                fp.seek(SeekFrom::Start(start)).map_err(Error::Io)?;
+                let mut probe = Vec::with_capacity(probe_len(options.chain));
+                fp.take(probe_len(options.chain) as u64)
+                    .read_to_end(&mut probe)
+                    .map_err(Error::Io)?;
+                let at_end = start + probe.len() as u64 >= len;
+                Ok(find_boundary(&probe, at_end, options.chain).map(|o| start + o as u64))
+            })
+            .collect::<Result<Vec<Option<u64>>, Error>>()?;
+        Ok(Self {
+            input: Input::File(path),
+            header,
+            chunks: chunks(starts.into_iter().flatten().collect(), len),
+        })
+    }
+}
+
+/// iterator over the packets of a [`ChunkedCapture`] in capture order.
+#[derive(Debug)]
+pub struct ChunkedPackets<'c, 'a> {
+    capture: &'c ChunkedCapture<'a>,
+    next_chunk: usize,
+    buffered: VecDeque<Result<SnoopPacket, Error>>,
+}
+
+impl Iterator for ChunkedPackets<'_, '_> {
+    type Item = Result<SnoopPacket, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let count = self.capture.chunks.len();
+        while self.buffered.is_empty() && self.next_chunk < count {
+            let end = (self.next_chunk + rayon::current_num_threads()).min(count);
+            let chunks: Vec<Vec<Result<SnoopPacket, Error>>> = (self.next_chunk..end)
+                .into_par_iter()
+                .map(|chunk| match self.capture.reader(chunk) {
+                    Ok(reader) => read_chunk(reader),
+                    Err(e) => vec![Err(e)],
+                })
+                .collect();
+            self.buffered.extend(chunks.into_iter().flatten());
+            self.next_chunk = end;
+        }
+        self.buffered.pop_front()
+    }
+}
+
+/// read all packets of a chunk until the end or the first error.
+fn read_chunk(reader: ChunkReader<'_>) -> Vec<Result<SnoopPacket, Error>> {
+    let mut packets = Vec::new();
+    for p in reader {
+        let end = p.is_err();
+        packets.push(p);
+        if end {
+            break;
+        }
+    }
+    packets
+}
+
+/// bytes which are needed to find a boundary followed by a chain of records.
+fn probe_len(chain: usize) -> usize {
+    MAX_RECORD_LEN * (chain + 1)
+}
+
+/// nominal start offsets of the chunks after the first chunk, the boundary is searched from there.
+fn nominal_starts(len: u64, options: ChunkOptions) -> Vec<u64> {
+    let size = options
+        .chunk_size
+        .max(MAX_RECORD_LEN as u64)
+        .next_multiple_of(4);
+    // every start is below the end of the capture
+    let records = len.saturating_sub(SNOOP_HEADER_SIZE as u64);
+    (1..records.div_ceil(size))
+        .map(|i| SNOOP_HEADER_SIZE as u64 + i * size)
+        .collect()
+}
+
+/// ranges from the first record to the end, split at the found boundaries.
+fn chunks(mut starts: Vec<u64>, len: u64) -> Vec<Range<u64>> {
+    starts.retain(|start| *start < len);
+    starts.sort_unstable();
+    starts.dedup();
+    let mut chunks = Vec::with_capacity(starts.len() + 1);
+    let mut start = SNOOP_HEADER_SIZE as u64;
+    for next in starts {
+        chunks.push(start..next);
+        start = next;
+    }
+    if start < len {
+        chunks.push(start..len);
+    }
+    chunks
+}
+
+/// first offset in the window where a chain of valid packet headers starts.
+/// every offset is tried, records are not 4 byte aligned if a writer used 4 pad bytes.
+/// `at_end` tells if the window ends with the capture, then a shorter chain up to the end is valid.
+fn find_boundary(window: &[u8], at_end: bool, chain: usize) -> Option<usize> {
+    (0..window.len().min(MAX_RECORD_LEN))
+        .find(|offset| valid_chain(&window[*offset..], at_end, chain))
+}
+
+/// check that a chain of consecutive packet headers is valid.
+/// records are padded with at most [`MAX_CAPTURE_PADS`] bytes like the reader accepts
+/// and have valid microseconds.
+fn valid_chain(data: &[u8], at_end: bool, chain: usize) -> bool {
+    let mut ph = PacketHeader::default();
+    let mut pos = 0;
+    for _ in 0..chain.max(1) {
+        if at_end && pos == data.len() {
+            return pos > 0;
+        }
+        let Some(Ok(buf)) = data
+            .get(pos..pos + SNOOP_PACKET_HEADER_SIZE)
+            .map(TryInto::try_into)
+        else {
+            return false;
+        };
+        if Parser::parse_packet_header(buf, &mut ph).is_err()
+            || Parser::pad(&ph) > MAX_CAPTURE_PADS as usize
+            || ph.timestamp_microseconds >= 1_000_000
+        {
+            return false;
+        }
+        pos += ph.packet_record_length as usize;
+        if pos > data.len() && at_end {
+            return false;
+        }
+    }
+    true
+}
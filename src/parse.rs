@@ -60,7 +60,7 @@ impl Parser {
             return Err(Error::CaptureLenExceeded);
         }
 
-        if ph.packet_record_length < (SNOOP_PACKET_HEADER_SIZE as u32 + ph.original_length) {
+        if ph.packet_record_length < (SNOOP_PACKET_HEADER_SIZE as u32 + ph.included_length) {
             return Err(Error::InvalidRecordLength);
         }
         Ok(())
@@ -73,6 +73,14 @@ impl Parser {
         (ph.packet_record_length - (SNOOP_PACKET_HEADER_SIZE as u32 + ph.included_length)) as usize
     }
 
+    /// calculate the packet record length of a packet with the given included length.
+    /// the record is padded to a multiple of 4 bytes.
+    #[must_use]
+    #[allow(clippy::cast_possible_truncation)]
+    pub fn record_len(included_length: u32) -> u32 {
+        (SNOOP_PACKET_HEADER_SIZE as u32 + included_length + 3) & !3
+    }
+
     /// calculate the data len with pads included.
     /// pads must be stripped at the end of data bytes.
     #[must_use]
@@ -40,11 +40,35 @@ where
         Ok(r)
     }
 
+    /// create a new reader for an underlying reader which is positioned at a packet record.
+    /// the snoop header is not read, the given header from a previous read is used instead.
+    /// can be used to continue reading after seeking the underlying reader.
+    pub fn with_header(r: R, header: SnoopHeader) -> Self {
+        Self {
+            r,
+            header,
+            ph: PacketHeader {
+                ..Default::default()
+            },
+            buf: vec![0u8; (MAX_CAPTURE_LEN + MAX_CAPTURE_PADS) as usize],
+        }
+    }
+
     /// get a reference to the snoop file format header
     pub fn header(&self) -> &SnoopHeader {
         &self.header
     }
 
+    /// get a mutable reference to the underlying reader.
+    pub fn get_mut(&mut self) -> &mut R {
+        &mut self.r
+    }
+
+    /// unwrap this reader and return the underlying reader.
+    pub fn into_inner(self) -> R {
+        self.r
+    }
+
     /// read and parse snoop file format header from the underlying reader
     fn read_header(&mut self) -> Result<(), Error> {
         self.read_exact(0, SNOOP_HEADER_SIZE)?;
@@ -103,7 +127,7 @@ where
     /// # Errors
     /// will return [`Error`] if something unexpected happen.
     #[allow(clippy::missing_panics_doc)]
-    pub fn read_ref(&mut self) -> Result<SnoopPacketRef, Error> {
+    pub fn read_ref(&mut self) -> Result<SnoopPacketRef<'_>, Error> {
         self.read_exact(0, SNOOP_PACKET_HEADER_SIZE)?;
         Parser::parse_packet_header(
             &self.buf[..SNOOP_PACKET_HEADER_SIZE].try_into().unwrap(),
@@ -133,7 +157,7 @@ where
     /// # Errors
     /// will return [`Error`] if something unexpected happen.
     #[allow(clippy::missing_panics_doc)]
-    pub fn read_stream(&mut self, time: time::Duration) -> Result<SnoopPacketRef, Error> {
+    pub fn read_stream(&mut self, time: time::Duration) -> Result<SnoopPacketRef<'_>, Error> {
         self.read_until(SNOOP_PACKET_HEADER_SIZE, time)?;
         Parser::parse_packet_header(
             &self.buf[..SNOOP_PACKET_HEADER_SIZE].try_into().unwrap(),
@@ -149,7 +173,7 @@ where
     }
 
     /// iterate over packets inside a snoop file until a valid eof or error occurs and return the packet data as a reference to the underlying buffer.
-    pub fn iter_ref(&mut self) -> Option<Result<SnoopPacketRef, Error>> {
+    pub fn iter_ref(&mut self) -> Option<Result<SnoopPacketRef<'_>, Error>> {
         match self.read_ref() {
             Ok(packet) => Some(Ok(packet)),
             Err(Error::Eof) => None,
@@ -173,3 +197,23 @@ where
         }
     }
 }
+
+/// read exactly `buf.len()` bytes.
+/// returns [`Error::Eof`] if no byte could be read and [`Error::UnexpectedEof`] if the data ends early.
+#[cfg(any(feature = "edit", feature = "convert", feature = "rayon"))]
+pub(crate) fn read_full<R: Read>(r: &mut R, buf: &mut [u8]) -> Result<(), Error> {
+    let mut bytes = 0;
+    while bytes < buf.len() {
+        match r.read(&mut buf[bytes..]) {
+            Ok(0) => break,
+            Ok(n) => bytes += n,
+            Err(ref e) if e.kind() == std::io::ErrorKind::Interrupted => {}
+            Err(e) => return Err(Error::Io(e)),
+        }
+    }
+    match bytes {
+        0 if !buf.is_empty() => Err(Error::Eof),
+        n if n < buf.len() => Err(Error::UnexpectedEof(n)),
+        _ => Ok(()),
+    }
+}
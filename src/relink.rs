@@ -0,0 +1,138 @@
+//! change the link type of a capture and re-encapsulate the frames.
+//!
+//! [`set_link_type`] fixes a wrong link type in the snoop header of an existing file in place.
+//! [`relink`] copies a capture to a writer with another link type and optionally prepends a
+//! synthetic Ethernet header to raw IP packets or strips Ethernet frames down to IP.
+//! the lengths of the packet records are adjusted to the new frames.
+use crate::ether::{ip_offset, ETHERTYPE_IPV4, ETHERTYPE_IPV6};
+use crate::format::{DataLinkType, PacketHeader, MAX_CAPTURE_LEN, SNOOP_MAGIC};
+use crate::parse::Parser;
+use crate::read::Reader;
+use crate::write::Writer;
+use crate::Error;
+use std::io::{Read, Seek, SeekFrom, Write};
+
+/// size of an Ethernet header without VLAN tags
+pub const ETHERNET_HEADER_SIZE: usize = 14;
+
+/// how the frames are changed.
+#[derive(Debug, Default, PartialEq, Eq, Copy, Clone)]
+pub enum Encapsulation {
+    /// keep the frames, only the link type changes
+    #[default]
+    Keep,
+    /// prepend an Ethernet header to raw IPv4 and IPv6 packets,
+    /// the ethertype is taken from the IP version
+    AddEthernet {
+        /// destination mac address
+        destination: [u8; 6],
+        /// source mac address
+        source: [u8; 6],
+    },
+    /// strip the Ethernet header and VLAN tags from frames with IPv4 or IPv6 payload
+    StripEthernet,
+}
+
+/// counters of a relink run.
+#[derive(Debug, Default, PartialEq, Eq, Copy, Clone)]
+pub struct RelinkStats {
+    /// number of written packets
+    pub packets: u64,
+    /// number of skipped packets which could not be re-encapsulated, like non IP frames
+    pub skipped: u64,
+}
+
+/// overwrite the link type in the snoop header of a file, the packets are not changed.
+/// # Errors
+/// will return [`Error::UnknownMagic`] if the file is not in snoop format
+/// and [`Error::Io`] if something unexpected happen.
+pub fn set_link_type<F: Read + Write + Seek>(
+    f: &mut F,
+    link_type: DataLinkType,
+) -> Result<(), Error> {
+    let mut magic = [0u8; 8];
+    f.seek(SeekFrom::Start(0)).map_err(Error::Io)?;
+    f.read_exact(&mut magic).map_err(Error::Io)?;
+    if magic != SNOOP_MAGIC {
+        return Err(Error::UnknownMagic);
+    }
+    f.seek(SeekFrom::Start(12)).map_err(Error::Io)?;
+    f.write_all(&(link_type as u32).to_be_bytes())
+        .map_err(Error::Io)?;
+    f.flush().map_err(Error::Io)
+}
+
+/// re-encapsulate a frame, `None` if the frame is not supported by the encapsulation.
+/// the returned header has the adjusted original, included and record length.
+#[must_use]
+pub fn encapsulate(
+    header: &PacketHeader,
+    data: &[u8],
+    encapsulation: Encapsulation,
+) -> Option<(PacketHeader, Vec<u8>)> {
+    let (original_length, mut frame) = match encapsulation {
+        Encapsulation::Keep => (header.original_length, data.to_vec()),
+        Encapsulation::AddEthernet {
+            destination,
+            source,
+        } => {
+            let ethertype = match data.first()? >> 4 {
+                4 => ETHERTYPE_IPV4,
+                6 => ETHERTYPE_IPV6,
+                _ => return None,
+            };
+            let mut frame = Vec::with_capacity(ETHERNET_HEADER_SIZE + data.len());
+            frame.extend_from_slice(&destination);
+            frame.extend_from_slice(&source);
+            frame.extend_from_slice(&ethertype.to_be_bytes());
+            frame.extend_from_slice(data);
+            #[allow(clippy::cast_possible_truncation)]
+            let len = header
+                .original_length
+                .saturating_add(ETHERNET_HEADER_SIZE as u32);
+            (len, frame)
+        }
+        Encapsulation::StripEthernet => {
+            let (_, offset) = ip_offset(data)?;
+            #[allow(clippy::cast_possible_truncation)]
+            let len = header.original_length.saturating_sub(offset as u32);
+            (len, data[offset..].to_vec())
+        }
+    };
+    frame.truncate(MAX_CAPTURE_LEN as usize);
+    #[allow(clippy::cast_possible_truncation)]
+    let included_length = frame.len() as u32;
+    Some((
+        PacketHeader {
+            original_length: original_length.max(included_length),
+            included_length,
+            packet_record_length: Parser::record_len(included_length),
+            ..header.clone()
+        },
+        frame,
+    ))
+}
+
+/// copy all packets to the writer, which has the new link type, and re-encapsulate the frames.
+/// packets which can not be re-encapsulated are skipped.
+/// # Errors
+/// will return [`Error`] if a packet can not be read or written.
+pub fn relink<R: Read, W: Write>(
+    reader: &mut Reader<R>,
+    writer: &mut Writer<W>,
+    encapsulation: Encapsulation,
+) -> Result<RelinkStats, Error> {
+    let mut stats = RelinkStats::default();
+    while let Some(packet) = reader.iter_ref() {
+        let packet = packet?;
+        match encapsulate(packet.header, packet.data, encapsulation) {
+            Some((header, data)) => {
+                writer.write_record(&header, &data)?;
+                stats.packets += 1;
+            }
+            None => stats.skipped += 1,
+        }
+    }
+    writer.flush()?;
+    Ok(stats)
+}
@@ -0,0 +1,190 @@
+//! sort captures with out of order timestamps, like `reordercap`.
+//!
+//! small captures are sorted in memory. if the packets exceed the memory limit,
+//! sorted runs are spilled to temporary snoop files and merged with [`crate::merge`].
+//! at most `fan_in` runs are open at once, more runs are merged in several passes.
+//! packets with the same timestamp keep their order and the cumulative drops are not changed.
+use crate::format::{DataLinkType, SnoopPacket};
+use crate::merge::{MergeOptions, Merger};
+use crate::read::Reader;
+use crate::write::Writer;
+use crate::Error;
+use std::fs::{self, File};
+use std::io::{BufReader, BufWriter, Read, Write};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// default memory limit for packets sorted in memory, 256 MiB
+pub const DEFAULT_MAX_MEMORY: usize = 256 * 1024 * 1024;
+/// smallest memory limit, lower limits are raised to it, 64 KiB
+pub const MIN_MAX_MEMORY: usize = 64 * 1024;
+/// default number of runs which are merged at once
+pub const DEFAULT_FAN_IN: usize = 64;
+
+static RUN_ID: AtomicUsize = AtomicUsize::new(0);
+
+/// options of the reorder.
+#[derive(Debug, Clone)]
+pub struct ReorderOptions {
+    /// maximum size of the packet records held in memory before a sorted run is spilled,
+    /// at least [`MIN_MAX_MEMORY`]
+    pub max_memory: usize,
+    /// maximum number of runs which are merged at once, at least 2
+    pub fan_in: usize,
+    /// directory for the temporary run files, the system temp dir if not set
+    pub temp_dir: Option<PathBuf>,
+}
+
+impl Default for ReorderOptions {
+    fn default() -> Self {
+        Self {
+            max_memory: DEFAULT_MAX_MEMORY,
+            fan_in: DEFAULT_FAN_IN,
+            temp_dir: None,
+        }
+    }
+}
+
+/// temporary run files which are removed on drop.
+struct Runs {
+    dir: PathBuf,
+    files: Vec<PathBuf>,
+}
+
+impl Runs {
+    /// create a new run file, it is removed on drop.
+    fn create(&mut self, link_type: DataLinkType) -> Result<RunWriter, Error> {
+        let path = self.dir.join(format!(
+            "snoop-reorder-{}-{}.snoop",
+            std::process::id(),
+            RUN_ID.fetch_add(1, Ordering::Relaxed)
+        ));
+        self.files.push(path.clone());
+        let fp = File::create(&path).map_err(Error::Io)?;
+        Ok((path, Writer::new(BufWriter::new(fp), link_type)?))
+    }
+
+    fn spill(
+        &mut self,
+        packets: &mut Vec<SnoopPacket>,
+        link_type: DataLinkType,
+    ) -> Result<PathBuf, Error> {
+        let (path, mut w) = self.create(link_type)?;
+        sort(packets);
+        for packet in packets.drain(..) {
+            w.write_packet(&packet)?;
+        }
+        w.flush()?;
+        Ok(path)
+    }
+
+    /// merge groups of `fan_in` runs into new runs, the merged runs are removed.
+    fn merge_pass(
+        &mut self,
+        current: &[PathBuf],
+        fan_in: usize,
+        link_type: DataLinkType,
+    ) -> Result<Vec<PathBuf>, Error> {
+        let mut next = Vec::with_capacity(current.len().div_ceil(fan_in));
+        for group in current.chunks(fan_in) {
+            if let [path] = group {
+                next.push(path.clone());
+                continue;
+            }
+            let (path, mut w) = self.create(link_type)?;
+            for packet in merger(group)? {
+                w.write_packet(&packet?)?;
+            }
+            w.flush()?;
+            for old in group {
+                fs::remove_file(old).map_err(Error::Io)?;
+            }
+            self.files.retain(|f| !group.contains(f));
+            next.push(path);
+        }
+        Ok(next)
+    }
+}
+
+type RunWriter = (PathBuf, Writer<BufWriter<File>>);
+
+/// merge the sorted runs, packets with the same timestamp keep the order of the runs.
+fn merger(runs: &[PathBuf]) -> Result<Merger<BufReader<File>>, Error> {
+    let readers = runs
+        .iter()
+        .map(|path| Reader::new(BufReader::new(File::open(path).map_err(Error::Io)?)))
+        .collect::<Result<Vec<_>, Error>>()?;
+    Merger::new(
+        readers,
+        &MergeOptions {
+            keep_drops: true,
+            ..Default::default()
+        },
+    )
+}
+
+impl Drop for Runs {
+    fn drop(&mut self) {
+        for path in &self.files {
+            let _ = fs::remove_file(path);
+        }
+    }
+}
+
+/// read all packets, write them sorted by timestamp and return the number of written packets.
+/// # Errors
+/// will return [`Error`] if a packet can not be read or written or a temporary file fails.
+pub fn reorder<R: Read, W: Write>(
+    reader: &mut Reader<R>,
+    writer: &mut Writer<W>,
+    options: &ReorderOptions,
+) -> Result<u64, Error> {
+    let link_type = reader.header().link_type;
+    let mut runs = Runs {
+        dir: options.temp_dir.clone().unwrap_or_else(std::env::temp_dir),
+        files: Vec::new(),
+    };
+    let max_memory = options.max_memory.max(MIN_MAX_MEMORY);
+    let fan_in = options.fan_in.max(2);
+    let mut current = Vec::new();
+    let mut packets = Vec::new();
+    let mut memory = 0;
+    for packet in reader {
+        let packet = packet?;
+        memory += packet.header.packet_record_length as usize;
+        packets.push(packet);
+        if memory >= max_memory {
+            current.push(runs.spill(&mut packets, link_type)?);
+            memory = 0;
+        }
+    }
+
+    let mut cnt = 0;
+    if current.is_empty() {
+        sort(&mut packets);
+        for packet in &packets {
+            writer.write_packet(packet)?;
+            cnt += 1;
+        }
+    } else {
+        if !packets.is_empty() {
+            current.push(runs.spill(&mut packets, link_type)?);
+        }
+        // release the buffer of the in memory packets before merging
+        drop(packets);
+        while current.len() > fan_in {
+            current = runs.merge_pass(&current, fan_in, link_type)?;
+        }
+        for packet in merger(&current)? {
+            writer.write_packet(&packet?)?;
+            cnt += 1;
+        }
+    }
+    writer.flush()?;
+    Ok(cnt)
+}
+
+/// stable sort by timestamp.
+fn sort(packets: &mut [SnoopPacket]) {
+    packets.sort_by_key(|p| (p.header.timestamp_seconds, p.header.timestamp_microseconds));
+}
@@ -0,0 +1,175 @@
+//! write to a set of rotating snoop files like `tcpdump -C/-G/-W`.
+use crate::civil::strftime;
+use crate::format::{DataLinkType, SnoopPacket, SNOOP_HEADER_SIZE};
+use crate::write::{packet_now, Writer};
+use crate::Error;
+use std::collections::{HashSet, VecDeque};
+use std::fs::{self, File};
+use std::io::BufWriter;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// limits when the [`RotatingWriter`] starts a new file.
+/// all limits are optional and the first reached limit starts a new file.
+#[derive(Debug, Default, Clone)]
+pub struct Rotation {
+    /// start a new file if the file would grow beyond this size in bytes
+    pub max_bytes: Option<u64>,
+    /// start a new file after this number of packets
+    pub max_packets: Option<u64>,
+    /// start a new file after this time span, measured on the packet timestamps and not on the
+    /// wall clock. the file is only closed when a packet at or after the end of the span is written,
+    /// so a file stays open while no packets arrive. [`RotatingWriter::write`] timestamps the
+    /// packets with the current time, which makes the span follow the wall clock.
+    pub max_duration: Option<Duration>,
+    /// ring buffer mode, keep only the newest n files and remove older ones
+    pub max_files: Option<usize>,
+    /// align the time span to wall clock boundaries, e.g. full hours for one hour.
+    /// the first file ends at the next boundary and the file names use the boundary time.
+    pub align: bool,
+}
+
+/// writer that splits the written packets into multiple snoop files.
+/// each file starts with a fresh snoop header.
+///
+/// file names are created from a `strftime` style template with the timestamp
+/// of the first packet in the file, see [`RotatingWriter::new`].
+#[derive(Debug)]
+pub struct RotatingWriter {
+    template: String,
+    link_type: DataLinkType,
+    rotation: Rotation,
+    writer: Option<Writer<BufWriter<File>>>,
+    files: VecDeque<PathBuf>,
+    names: HashSet<String>,
+    seq: u64,
+    bytes: u64,
+    packets: u64,
+    started: Duration,
+}
+
+impl RotatingWriter {
+    /// create a new rotating writer. no file is created until the first packet is written.
+    ///
+    /// the template supports `%Y`, `%y`, `%m`, `%d`, `%H`, `%M`, `%S`, `%j`, `%s` and `%%`
+    /// in utc and `%n` as the sequence number of the file, starting at 0.
+    /// if an expanded template equals the one of any previous file, the sequence number is appended,
+    /// so no file of this writer is overwritten.
+    pub fn new(template: impl Into<String>, link_type: DataLinkType, rotation: Rotation) -> Self {
+        Self {
+            template: template.into(),
+            link_type,
+            rotation,
+            writer: None,
+            files: VecDeque::new(),
+            names: HashSet::new(),
+            seq: 0,
+            bytes: 0,
+            packets: 0,
+            started: Duration::ZERO,
+        }
+    }
+
+    /// paths of the written files which are not removed by the ring buffer, oldest first.
+    pub fn files(&self) -> impl Iterator<Item = &Path> {
+        self.files.iter().map(PathBuf::as_path)
+    }
+
+    /// write packet header and data to the current file and start a new file if a limit is reached.
+    /// # Errors
+    /// will return [`Error`] if something unexpected happen.
+    pub fn write_packet(&mut self, packet: &SnoopPacket) -> Result<(), Error> {
+        let time = packet.header.timestamp()?;
+        let size = u64::from(packet.header.packet_record_length);
+        if self.writer.is_none() || self.limit_reached(time, size) {
+            self.rotate(time)?;
+        }
+        if let Some(w) = self.writer.as_mut() {
+            w.write_packet(packet)?;
+        }
+        self.bytes += size;
+        self.packets += 1;
+        Ok(())
+    }
+
+    /// write calculated header and the data as snoop packet data to the current file.
+    /// # Errors
+    /// will return [`Error`] if something unexpected happen.
+    pub fn write(&mut self, data: Vec<u8>) -> Result<(), Error> {
+        let packet = packet_now(data)?;
+        self.write_packet(&packet)
+    }
+
+    /// flush the current file.
+    /// # Errors
+    /// will return [`Error::Io`] if something unexpected happen.
+    pub fn flush(&mut self) -> Result<(), Error> {
+        match self.writer.as_mut() {
+            Some(w) => w.flush(),
+            None => Ok(()),
+        }
+    }
+
+    /// check if the packet does not fit into the current file anymore.
+    fn limit_reached(&self, time: Duration, size: u64) -> bool {
+        if self.packets == 0 {
+            return false;
+        }
+        if let Some(max) = self.rotation.max_packets {
+            if self.packets >= max {
+                return true;
+            }
+        }
+        if let Some(max) = self.rotation.max_bytes {
+            if self.bytes + size > max {
+                return true;
+            }
+        }
+        if let Some(max) = self.rotation.max_duration {
+            if time.saturating_sub(self.started) >= max {
+                return true;
+            }
+        }
+        false
+    }
+
+    /// close the current file, open the next one and remove old files in ring buffer mode.
+    fn rotate(&mut self, time: Duration) -> Result<(), Error> {
+        if let Some(mut w) = self.writer.take() {
+            w.flush()?;
+        }
+
+        let started = match self.rotation.max_duration {
+            Some(d) if self.rotation.align && !d.is_zero() => {
+                // the remainder is below the time, which fits into u64 nanoseconds
+                #[allow(clippy::cast_possible_truncation)]
+                let rem = (time.as_nanos() % d.as_nanos()) as u64;
+                time.saturating_sub(Duration::from_nanos(rem))
+            }
+            _ => time,
+        };
+        let name = strftime(&self.template, started.as_secs(), self.seq);
+        let path = if self.names.contains(&name) {
+            PathBuf::from(format!("{name}.{}", self.seq))
+        } else {
+            PathBuf::from(&name)
+        };
+        self.names.insert(name);
+        let fp = File::create(&path).map_err(Error::Io)?;
+        self.writer = Some(Writer::new(BufWriter::new(fp), self.link_type)?);
+        self.files.push_back(path);
+        self.seq += 1;
+        self.bytes = SNOOP_HEADER_SIZE as u64;
+        self.packets = 0;
+        self.started = started;
+
+        if let Some(max) = self.rotation.max_files {
+            while self.files.len() > max.max(1) {
+                if let Some(old) = self.files.pop_front() {
+                    fs::remove_file(old).map_err(Error::Io)?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,204 @@
+//! sample packets from a capture: every n-th packet, random with a probability or a reservoir of k packets.
+//!
+//! the samplers are adapters over an iterator of packets like the [`Reader`].
+//! random sampling is seeded and returns the same packets for the same seed.
+//! skipped packets are counted into the cumulative drops of the following packets,
+//! so the sample shows how many packets are missing.
+use crate::format::SnoopPacket;
+use crate::read::Reader;
+use crate::write::Writer;
+use crate::Error;
+use std::io::{Read, Write};
+
+/// sampling method.
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub enum Sampling {
+    /// keep the first of every n packets
+    Every(u64),
+    /// keep each packet with the probability between 0 and 1
+    Bernoulli {
+        /// probability to keep a packet
+        probability: f64,
+        /// seed of the random generator
+        seed: u64,
+    },
+    /// keep k random packets in capture order, all packets are read before the sample is written
+    Reservoir {
+        /// number of packets to keep
+        size: usize,
+        /// seed of the random generator
+        seed: u64,
+    },
+}
+
+/// counters of a sample run.
+#[derive(Debug, Default, PartialEq, Eq, Copy, Clone)]
+pub struct SampleStats {
+    /// number of read packets
+    pub packets: u64,
+    /// number of written packets
+    pub written: u64,
+}
+
+/// small seeded random generator, splitmix64.
+#[derive(Debug, Clone)]
+struct Rng(u64);
+
+impl Rng {
+    fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// uniform float in `[0, 1)`
+    #[allow(clippy::cast_precision_loss)]
+    fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    /// uniform integer in `[0, n)`
+    #[allow(clippy::cast_possible_truncation)]
+    fn below(&mut self, n: u64) -> u64 {
+        ((u128::from(self.next_u64()) * u128::from(n)) >> 64) as u64
+    }
+}
+
+#[derive(Debug, Clone)]
+enum Method {
+    Every(u64),
+    Bernoulli(f64, Rng),
+}
+
+/// iterator adapter which keeps a sample of the packets.
+#[derive(Debug)]
+pub struct Sampler<I> {
+    iter: I,
+    method: Method,
+    index: u64,
+    skipped: u32,
+}
+
+impl<I> Sampler<I> {
+    /// keep the first of every n packets.
+    pub fn every(iter: I, n: u64) -> Self {
+        Self {
+            iter,
+            method: Method::Every(n.max(1)),
+            index: 0,
+            skipped: 0,
+        }
+    }
+
+    /// keep each packet with the probability, seeded for reproducible samples.
+    pub fn bernoulli(iter: I, probability: f64, seed: u64) -> Self {
+        Self {
+            iter,
+            method: Method::Bernoulli(probability, Rng(seed)),
+            index: 0,
+            skipped: 0,
+        }
+    }
+}
+
+impl<I> Iterator for Sampler<I>
+where
+    I: Iterator<Item = Result<SnoopPacket, Error>>,
+{
+    type Item = Result<SnoopPacket, Error>;
+
+    /// return the next kept packet, the cumulative drops include the skipped packets.
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let mut packet = match self.iter.next()? {
+                Ok(packet) => packet,
+                Err(e) => return Some(Err(e)),
+            };
+            let keep = match &mut self.method {
+                Method::Every(n) => self.index.checked_rem(*n) == Some(0),
+                Method::Bernoulli(p, rng) => rng.next_f64() < *p,
+            };
+            self.index += 1;
+            if keep {
+                packet.header.cumulative_drops =
+                    packet.header.cumulative_drops.saturating_add(self.skipped);
+                return Some(Ok(packet));
+            }
+            self.skipped = self.skipped.saturating_add(1);
+        }
+    }
+}
+
+/// keep `size` random packets with reservoir sampling and return them in capture order.
+/// the cumulative drops include the skipped packets.
+/// # Errors
+/// will return [`Error`] if a packet can not be read.
+pub fn reservoir<I>(iter: I, size: usize, seed: u64) -> Result<Vec<SnoopPacket>, Error>
+where
+    I: Iterator<Item = Result<SnoopPacket, Error>>,
+{
+    let mut rng = Rng(seed);
+    let mut sample: Vec<(u64, SnoopPacket)> = Vec::with_capacity(size);
+    for (index, packet) in (0u64..).zip(iter) {
+        let packet = packet?;
+        if sample.len() < size {
+            sample.push((index, packet));
+        } else {
+            let j = rng.below(index + 1);
+            if let Some(slot) = usize::try_from(j).ok().and_then(|j| sample.get_mut(j)) {
+                *slot = (index, packet);
+            }
+        }
+    }
+    sample.sort_by_key(|(index, _)| *index);
+    Ok(sample
+        .into_iter()
+        .zip(0u64..)
+        .map(|((index, mut packet), pos)| {
+            let skipped = u32::try_from(index - pos).unwrap_or(u32::MAX);
+            packet.header.cumulative_drops = packet.header.cumulative_drops.saturating_add(skipped);
+            packet
+        })
+        .collect())
+}
+
+/// write a sample of the packets and return the counters.
+/// # Errors
+/// will return [`Error`] if a packet can not be read or written.
+pub fn sample<R: Read, W: Write>(
+    reader: &mut Reader<R>,
+    writer: &mut Writer<W>,
+    sampling: Sampling,
+) -> Result<SampleStats, Error> {
+    let (mut read, mut written) = (0, 0);
+    let packets = reader.inspect(|_| read += 1);
+    let mut write = |packet: &SnoopPacket| -> Result<(), Error> {
+        writer.write_packet(packet)?;
+        written += 1;
+        Ok(())
+    };
+    match sampling {
+        Sampling::Every(n) => {
+            for packet in Sampler::every(packets, n) {
+                write(&packet?)?;
+            }
+        }
+        Sampling::Bernoulli { probability, seed } => {
+            for packet in Sampler::bernoulli(packets, probability, seed) {
+                write(&packet?)?;
+            }
+        }
+        Sampling::Reservoir { size, seed } => {
+            for packet in reservoir(packets, size, seed)? {
+                write(&packet)?;
+            }
+        }
+    }
+    writer.flush()?;
+    Ok(SampleStats {
+        packets: read,
+        written,
+    })
+}
@@ -0,0 +1,513 @@
+//! zstd compressed snoop files with random access.
+//!
+//! the [`SeekableWriter`] compresses the snoop header and every `N` packet records into
+//! independent zstd frames and appends a frame table in the
+//! [zstd seekable format](https://github.com/facebook/zstd/blob/dev/contrib/seekable_format/zstd_seekable_compression_format.md).
+//! the frame table is a skippable frame, so the files are also valid zstd streams and can be read
+//! with [`crate::decompress`] or the zstd command line tool.
+//!
+//! the number of packet records in every frame is written in a second skippable frame
+//! just before the frame table, so the [`SeekableReader`] can find the frame of a packet index
+//! without decompressing other frames. the [`SeekableReader`] uses the frame table to decompress
+//! only the frames needed for a position in the uncompressed snoop file.
+use crate::format::{
+    DataLinkType, PacketHeader, SnoopHeader, SnoopPacket, SnoopPacketRef, MAX_CAPTURE_LEN,
+    MAX_CAPTURE_PADS, SNOOP_HEADER_SIZE, SNOOP_PACKET_HEADER_SIZE,
+};
+use crate::parse::Parser;
+use crate::read::Reader;
+use crate::write::Writer;
+use crate::Error;
+use std::io::{self, Read, Seek, SeekFrom, Write};
+
+/// magic of the skippable frame which holds the frame table
+pub const SEEKABLE_SKIPPABLE_MAGIC: u32 = 0x184D_2A5E;
+/// magic at the end of the frame table
+pub const SEEKABLE_MAGIC: u32 = 0x8F92_EAB1;
+/// magic of the skippable frame which holds the number of packet records per frame
+pub const RECORD_INDEX_MAGIC: u32 = 0x184D_2A5D;
+/// default number of packet records in one frame
+pub const DEFAULT_FRAME_RECORDS: usize = 1024;
+/// largest uncompressed frame written by the writer and accepted by the reader.
+/// the writer starts a new frame before a frame would grow beyond it
+pub const MAX_FRAME_SIZE: usize = 8 * 1024 * 1024;
+
+const SKIPPABLE_HEADER_SIZE: usize = 8;
+const SEEK_TABLE_FOOTER_SIZE: usize = 9;
+const CHECKSUM_FLAG: u8 = 0x80;
+const MAX_RECORD_LEN: usize =
+    SNOOP_PACKET_HEADER_SIZE + (MAX_CAPTURE_LEN + MAX_CAPTURE_PADS) as usize;
+
+/// entry of the frame table
+#[derive(Debug, Default, PartialEq, Eq, Copy, Clone)]
+pub struct FrameEntry {
+    /// size of the compressed frame
+    pub compressed_size: u32,
+    /// size of the uncompressed data in this frame
+    pub decompressed_size: u32,
+}
+
+/// writer which compresses snoop files in independent zstd frames and writes a frame table.
+/// [`SeekableWriter::finish`] must be called to write the last frame and the frame table.
+#[derive(Debug)]
+pub struct SeekableWriter<W: Write> {
+    w: W,
+    snoop: Writer<Vec<u8>>,
+    level: i32,
+    frame_records: usize,
+    records: usize,
+    frames: Vec<FrameEntry>,
+    frame_counts: Vec<u32>,
+}
+
+impl<W: Write> SeekableWriter<W> {
+    /// create a new seekable writer which writes a new frame every `frame_records` packets,
+    /// or earlier if the frame would exceed [`MAX_FRAME_SIZE`].
+    /// the snoop header is written in its own frame, so every other frame starts with a packet record.
+    /// # Errors
+    /// will return [`Error::Io`] if the header frame can not be written
+    pub fn new(w: W, link_type: DataLinkType, frame_records: usize) -> Result<Self, Error> {
+        Self::with_level(w, link_type, frame_records, zstd::DEFAULT_COMPRESSION_LEVEL)
+    }
+
+    /// create a new seekable writer with a zstd compression level.
+    /// # Errors
+    /// will return [`Error::Io`] if the header frame can not be written
+    pub fn with_level(
+        w: W,
+        link_type: DataLinkType,
+        frame_records: usize,
+        level: i32,
+    ) -> Result<Self, Error> {
+        let mut s = Self {
+            w,
+            snoop: Writer::new(Vec::with_capacity(SNOOP_HEADER_SIZE), link_type)?,
+            level,
+            frame_records: frame_records.max(1),
+            records: 0,
+            frames: Vec::new(),
+            frame_counts: Vec::new(),
+        };
+        s.write_frame()?;
+        Ok(s)
+    }
+
+    /// get a reference to the snoop file format header
+    pub fn header(&self) -> &SnoopHeader {
+        self.snoop.header()
+    }
+
+    /// get the frame table of the already written frames
+    pub fn frames(&self) -> &[FrameEntry] {
+        &self.frames
+    }
+
+    /// write a packet to the current frame.
+    /// # Errors
+    /// will return [`Error`] if the packet is invalid or the frame can not be written
+    pub fn write_packet(&mut self, packet: &SnoopPacket) -> Result<(), Error> {
+        self.snoop.write_packet(packet)?;
+        self.next_record()
+    }
+
+    /// write a packet reference to the current frame.
+    /// # Errors
+    /// will return [`Error`] if the packet is invalid or the frame can not be written
+    pub fn write_packet_ref(&mut self, packet: &SnoopPacketRef) -> Result<(), Error> {
+        self.snoop.write_packet_ref(packet)?;
+        self.next_record()
+    }
+
+    /// write the data as a packet with the current time to the current frame.
+    /// # Errors
+    /// will return [`Error`] if the packet is invalid or the frame can not be written
+    pub fn write(&mut self, data: Vec<u8>) -> Result<(), Error> {
+        self.snoop.write(data)?;
+        self.next_record()
+    }
+
+    /// compress the current frame and flush the underlying writer.
+    /// the following packets start a new frame.
+    /// # Errors
+    /// will return [`Error::Io`] if something unexpected happen.
+    pub fn flush(&mut self) -> Result<(), Error> {
+        if self.records > 0 {
+            self.write_frame()?;
+        }
+        self.w.flush().map_err(Error::Io)
+    }
+
+    /// write the last frame, the record index and the frame table and return the underlying writer.
+    /// # Errors
+    /// will return [`Error::Io`] if something unexpected happen.
+    pub fn finish(mut self) -> Result<W, Error> {
+        if self.records > 0 {
+            self.write_frame()?;
+        }
+        let size = u32::try_from(self.frame_counts.len() * 4)
+            .map_err(|_| Error::InvalidFormat("too many frames for seek table"))?;
+        let mut index = Vec::with_capacity(SKIPPABLE_HEADER_SIZE + size as usize);
+        index.extend_from_slice(&RECORD_INDEX_MAGIC.to_le_bytes());
+        index.extend_from_slice(&size.to_le_bytes());
+        for c in &self.frame_counts {
+            index.extend_from_slice(&c.to_le_bytes());
+        }
+        self.w.write_all(&index).map_err(Error::Io)?;
+        #[allow(clippy::cast_possible_truncation)]
+        self.frames.push(FrameEntry {
+            compressed_size: index.len() as u32,
+            decompressed_size: 0,
+        });
+        let mut table = Vec::with_capacity(
+            SKIPPABLE_HEADER_SIZE + self.frames.len() * 8 + SEEK_TABLE_FOOTER_SIZE,
+        );
+        let size = u32::try_from(self.frames.len() * 8 + SEEK_TABLE_FOOTER_SIZE)
+            .map_err(|_| Error::InvalidFormat("too many frames for seek table"))?;
+        table.extend_from_slice(&SEEKABLE_SKIPPABLE_MAGIC.to_le_bytes());
+        table.extend_from_slice(&size.to_le_bytes());
+        for f in &self.frames {
+            table.extend_from_slice(&f.compressed_size.to_le_bytes());
+            table.extend_from_slice(&f.decompressed_size.to_le_bytes());
+        }
+        #[allow(clippy::cast_possible_truncation)]
+        table.extend_from_slice(&(self.frames.len() as u32).to_le_bytes());
+        table.push(0); // no checksums
+        table.extend_from_slice(&SEEKABLE_MAGIC.to_le_bytes());
+        self.w.write_all(&table).map_err(Error::Io)?;
+        self.w.flush().map_err(Error::Io)?;
+        Ok(self.w)
+    }
+
+    fn next_record(&mut self) -> Result<(), Error> {
+        self.records += 1;
+        if self.records >= self.frame_records
+            || self.snoop.get_mut().len() + MAX_RECORD_LEN > MAX_FRAME_SIZE
+        {
+            self.write_frame()?;
+        }
+        Ok(())
+    }
+
+    fn write_frame(&mut self) -> Result<(), Error> {
+        let data = self.snoop.get_mut();
+        let frame = zstd::bulk::compress(data, self.level).map_err(Error::Io)?;
+        self.w.write_all(&frame).map_err(Error::Io)?;
+        #[allow(clippy::cast_possible_truncation)]
+        self.frames.push(FrameEntry {
+            compressed_size: frame.len() as u32,
+            decompressed_size: data.len() as u32,
+        });
+        #[allow(clippy::cast_possible_truncation)]
+        self.frame_counts.push(self.records as u32);
+        data.clear();
+        self.records = 0;
+        Ok(())
+    }
+}
+
+/// reader for zstd seekable files which implements [`Read`] and [`Seek`]
+/// on the uncompressed data. only the frame of the current position is decompressed.
+#[derive(Debug)]
+pub struct SeekableReader<R: Read + Seek> {
+    r: R,
+    header: SnoopHeader,
+    frames: Vec<FrameEntry>,
+    compressed: Vec<u64>,
+    decompressed: Vec<u64>,
+    first_records: Option<Vec<u64>>,
+    pos: u64,
+    current: Option<usize>,
+    buf: Vec<u8>,
+}
+
+impl<R: Read + Seek> SeekableReader<R> {
+    /// create a new seekable reader from the frame table at the end of the underlying reader
+    /// and read the snoop header.
+    /// # Errors
+    /// will return [`Error::InvalidFormat`] if there is no valid frame table
+    /// and [`Error::UnknownMagic`] if the data is not in snoop format.
+    pub fn new(mut r: R) -> Result<Self, Error> {
+        let end = r.seek(SeekFrom::End(0)).map_err(Error::Io)?;
+        if end < (SKIPPABLE_HEADER_SIZE + SEEK_TABLE_FOOTER_SIZE) as u64 {
+            return Err(Error::InvalidFormat("seek table missing"));
+        }
+        let mut footer = [0u8; SEEK_TABLE_FOOTER_SIZE];
+        r.seek(SeekFrom::Start(end - SEEK_TABLE_FOOTER_SIZE as u64))
+            .map_err(Error::Io)?;
+        r.read_exact(&mut footer).map_err(Error::Io)?;
+        if u32::from_le_bytes([footer[5], footer[6], footer[7], footer[8]]) != SEEKABLE_MAGIC {
+            return Err(Error::InvalidFormat("seek table missing"));
+        }
+        let count = u32::from_le_bytes([footer[0], footer[1], footer[2], footer[3]]);
+        let entry_size: usize = if footer[4] & CHECKSUM_FLAG == 0 {
+            8
+        } else {
+            12
+        };
+        let table_size = u64::from(count) * entry_size as u64
+            + (SEEK_TABLE_FOOTER_SIZE + SKIPPABLE_HEADER_SIZE) as u64;
+        // the table must fit into the file before it is allocated
+        if table_size > end {
+            return Err(Error::InvalidFormat("invalid seek table"));
+        }
+        let mut table = vec![
+            0u8;
+            usize::try_from(table_size)
+                .map_err(|_| Error::InvalidFormat("invalid seek table"))?
+        ];
+        r.seek(SeekFrom::Start(end - table_size))
+            .map_err(Error::Io)?;
+        r.read_exact(&mut table).map_err(Error::Io)?;
+        let le = |b: &[u8]| u32::from_le_bytes([b[0], b[1], b[2], b[3]]);
+        if le(&table[..4]) != SEEKABLE_SKIPPABLE_MAGIC {
+            return Err(Error::InvalidFormat("invalid seek table"));
+        }
+        let mut frames: Vec<FrameEntry> = table[SKIPPABLE_HEADER_SIZE..]
+            .chunks_exact(entry_size)
+            .take(count as usize)
+            .map(|e| FrameEntry {
+                compressed_size: le(&e[..4]),
+                decompressed_size: le(&e[4..8]),
+            })
+            .collect();
+        // the frames must fit into the file in front of the table and decompress
+        // to at most the frame size of the writer, before any frame is allocated
+        if frames
+            .iter()
+            .any(|f| f.decompressed_size as usize > MAX_FRAME_SIZE)
+        {
+            return Err(Error::InvalidFormat("seek table frame too large"));
+        }
+        let (mut compressed, mut decompressed) = (vec![0u64], vec![0u64]);
+        for f in &frames {
+            compressed.push(compressed[compressed.len() - 1] + u64::from(f.compressed_size));
+            decompressed
+                .push(decompressed[decompressed.len() - 1] + u64::from(f.decompressed_size));
+        }
+        if compressed[frames.len()] + table_size > end {
+            return Err(Error::InvalidFormat("invalid seek table"));
+        }
+        let first_records = read_record_index(&mut r, &frames, &compressed)?;
+        if first_records.is_some() {
+            frames.pop();
+            compressed.pop();
+            decompressed.pop();
+        }
+        let mut s = Self {
+            r,
+            header: SnoopHeader::default(),
+            frames,
+            compressed,
+            decompressed,
+            first_records,
+            pos: 0,
+            current: None,
+            buf: Vec::new(),
+        };
+        let mut buf = [0u8; SNOOP_HEADER_SIZE];
+        s.read_exact(&mut buf).map_err(|e| match e.kind() {
+            io::ErrorKind::UnexpectedEof => Error::UnexpectedEof(0),
+            _ => Error::Io(e),
+        })?;
+        s.header = Parser::parse_header(&buf)?;
+        Ok(s)
+    }
+
+    /// get a reference to the snoop file format header
+    pub fn header(&self) -> &SnoopHeader {
+        &self.header
+    }
+
+    /// get the frame table
+    pub fn frames(&self) -> &[FrameEntry] {
+        &self.frames
+    }
+
+    /// size of the uncompressed snoop file
+    pub fn len(&self) -> u64 {
+        self.decompressed[self.frames.len()]
+    }
+
+    /// true if the uncompressed snoop file is empty
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// seek to the packet with the given index, counted from 0.
+    /// the frame is found with the record index and only the target frame is decompressed.
+    /// files without a record index are walked frame by frame.
+    /// # Errors
+    /// will return [`Error::Eof`] if the capture has fewer packets
+    /// and [`Error`] if a frame can not be read.
+    pub fn seek_packet(&mut self, index: u64) -> Result<(), Error> {
+        let (frame, remaining) = match &self.first_records {
+            Some(first) => {
+                if index >= first[first.len() - 1] {
+                    return Err(Error::Eof);
+                }
+                // last frame whose first record is at or before the index
+                let frame = first.partition_point(|&f| f <= index) - 1;
+                (frame, index - first[frame])
+            }
+            None => self.scan_frames(index)?,
+        };
+        self.load(frame).map_err(Error::Io)?;
+        let mut offset = 0usize;
+        for _ in 0..remaining {
+            offset = self.next_record(offset)?;
+        }
+        // the record index may claim more records than the frame holds
+        if offset >= self.buf.len() {
+            return Err(Error::UnexpectedEof(offset));
+        }
+        self.pos = self.decompressed[frame] + offset as u64;
+        Ok(())
+    }
+
+    /// create a snoop reader which starts at the packet with the given index.
+    /// # Errors
+    /// will return [`Error::Eof`] if the capture has fewer packets
+    /// and [`Error`] if a frame can not be read.
+    pub fn reader_at(mut self, index: u64) -> Result<Reader<Self>, Error> {
+        self.seek_packet(index)?;
+        let header = self.header;
+        Ok(Reader::with_header(self, header))
+    }
+
+    /// unwrap this reader and return the underlying reader.
+    pub fn into_inner(self) -> R {
+        self.r
+    }
+
+    /// find the frame and the record in the frame of a packet index
+    /// by decompressing the frames in order.
+    fn scan_frames(&mut self, index: u64) -> Result<(usize, u64), Error> {
+        let mut remaining = index;
+        // frame 0 holds the snoop header, the data frames hold whole records
+        let mut frame = 1;
+        loop {
+            if frame >= self.frames.len() {
+                return Err(Error::Eof);
+            }
+            let records = self.frame_records(frame)?;
+            if remaining < records {
+                return Ok((frame, remaining));
+            }
+            remaining -= records;
+            frame += 1;
+        }
+    }
+
+    /// number of packet records in a frame.
+    fn frame_records(&mut self, frame: usize) -> Result<u64, Error> {
+        self.load(frame).map_err(Error::Io)?;
+        let mut offset = 0;
+        let mut records = 0;
+        while offset < self.buf.len() {
+            offset = self.next_record(offset)?;
+            records += 1;
+        }
+        Ok(records)
+    }
+
+    /// offset of the record after the record at the offset in the current frame.
+    /// # Errors
+    /// will return [`Error::UnexpectedEof`] if the record not ends inside the frame.
+    fn next_record(&self, offset: usize) -> Result<usize, Error> {
+        let rest = self.buf.get(offset..).ok_or(Error::UnexpectedEof(offset))?;
+        let next = offset + record_len(rest)?;
+        if next > self.buf.len() {
+            return Err(Error::UnexpectedEof(self.buf.len()));
+        }
+        Ok(next)
+    }
+
+    fn load(&mut self, frame: usize) -> io::Result<()> {
+        if self.current == Some(frame) {
+            return Ok(());
+        }
+        let entry = self.frames[frame];
+        let mut compressed = vec![0u8; entry.compressed_size as usize];
+        self.r.seek(SeekFrom::Start(self.compressed[frame]))?;
+        self.r.read_exact(&mut compressed)?;
+        self.buf = zstd::bulk::decompress(&compressed, entry.decompressed_size as usize)?;
+        if self.buf.len() != entry.decompressed_size as usize {
+            return Err(io::Error::other("frame size does not match the seek table"));
+        }
+        self.current = Some(frame);
+        Ok(())
+    }
+}
+
+/// read the record index if the last frame of the frame table is a record index frame
+/// and return the index of the first record in every frame, followed by the number of records.
+fn read_record_index<R: Read + Seek>(
+    r: &mut R,
+    frames: &[FrameEntry],
+    compressed: &[u64],
+) -> Result<Option<Vec<u64>>, Error> {
+    let Some((last, data)) = frames.split_last() else {
+        return Ok(None);
+    };
+    if last.decompressed_size != 0
+        || last.compressed_size as usize != SKIPPABLE_HEADER_SIZE + data.len() * 4
+    {
+        return Ok(None);
+    }
+    let mut index = vec![0u8; last.compressed_size as usize];
+    r.seek(SeekFrom::Start(compressed[data.len()]))
+        .map_err(Error::Io)?;
+    r.read_exact(&mut index).map_err(Error::Io)?;
+    let le = |b: &[u8]| u32::from_le_bytes([b[0], b[1], b[2], b[3]]);
+    if le(&index[..4]) != RECORD_INDEX_MAGIC || le(&index[4..8]) as usize != data.len() * 4 {
+        return Ok(None);
+    }
+    let mut first = Vec::with_capacity(data.len() + 1);
+    first.push(0u64);
+    for c in index[SKIPPABLE_HEADER_SIZE..].chunks_exact(4) {
+        first.push(first[first.len() - 1] + u64::from(le(c)));
+    }
+    Ok(Some(first))
+}
+
+/// length of the packet record at the start of the buffer.
+fn record_len(buf: &[u8]) -> Result<usize, Error> {
+    let buf = buf
+        .get(..SNOOP_PACKET_HEADER_SIZE)
+        .and_then(|b| b.try_into().ok())
+        .ok_or(Error::UnexpectedEof(buf.len()))?;
+    let mut ph = PacketHeader::default();
+    Parser::parse_packet_header(buf, &mut ph)?;
+    Ok(ph.packet_record_length as usize)
+}
+
+impl<R: Read + Seek> Read for SeekableReader<R> {
+    fn read(&mut self, out: &mut [u8]) -> io::Result<usize> {
+        if self.pos >= self.len() || out.is_empty() {
+            return Ok(0);
+        }
+        // last frame starting at or before the position
+        let frame = self.decompressed.partition_point(|&o| o <= self.pos) - 1;
+        self.load(frame)?;
+        #[allow(clippy::cast_possible_truncation)]
+        let offset = (self.pos - self.decompressed[frame]) as usize;
+        let n = out.len().min(self.buf.len() - offset);
+        out[..n].copy_from_slice(&self.buf[offset..offset + n]);
+        self.pos += n as u64;
+        Ok(n)
+    }
+}
+
+impl<R: Read + Seek> Seek for SeekableReader<R> {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        let pos = match pos {
+            SeekFrom::Start(p) => Some(p),
+            SeekFrom::End(d) => self.len().checked_add_signed(d),
+            SeekFrom::Current(d) => self.pos.checked_add_signed(d),
+        };
+        self.pos = pos.ok_or_else(|| {
+            io::Error::new(io::ErrorKind::InvalidInput, "seek to a negative position")
+        })?;
+        Ok(self.pos)
+    }
+}
@@ -0,0 +1,171 @@
+//! write the packets between two timestamps or packet indices, like `editcap -A/-B` or `editcap -r`.
+//!
+//! the cumulative drops are written relative to the first packet of the slice.
+//! time ranges expect the capture in time order, the slice ends at the first packet after the range.
+//!
+//! a [`SeekIndex`] over a seekable reader skips the packets before the slice without reading them.
+//! it also works on zstd seekable files with `seekable::SeekableReader`.
+use crate::format::{PacketHeader, SNOOP_HEADER_SIZE, SNOOP_PACKET_HEADER_SIZE};
+use crate::parse::Parser;
+use crate::read::{read_full, Reader};
+use crate::write::Writer;
+use crate::Error;
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::time::Duration;
+
+/// range of packets to write, the start is inclusive and the end exclusive.
+#[derive(Debug, PartialEq, Copy, Clone)]
+pub enum Range {
+    /// packet indices, counted from 0
+    Index(u64, u64),
+    /// packet timestamps as time since the unix epoch
+    Time(Duration, Duration),
+}
+
+/// position of a packet record in the underlying reader.
+#[derive(Debug, Default, PartialEq, Copy, Clone)]
+pub struct IndexEntry {
+    /// index of the packet, counted from 0
+    pub index: u64,
+    /// timestamp of the packet
+    pub timestamp: Duration,
+    /// offset of the packet record from the start of the file
+    pub offset: u64,
+}
+
+/// index of every n-th packet record of a capture to seek to packet indices and timestamps.
+#[derive(Debug, Default, Clone)]
+pub struct SeekIndex {
+    entries: Vec<IndexEntry>,
+}
+
+impl SeekIndex {
+    /// build an index with every `every` packet record of the underlying reader.
+    /// only the packet headers are read, the packet data is skipped by seeking.
+    /// # Errors
+    /// will return [`Error`] if a packet header is invalid or the reader can not seek.
+    pub fn build<R: Read + Seek>(r: &mut R, every: u64) -> Result<Self, Error> {
+        let every = every.max(1);
+        let mut entries = Vec::new();
+        let mut offset = r
+            .seek(SeekFrom::Start(SNOOP_HEADER_SIZE as u64))
+            .map_err(Error::Io)?;
+        let mut buf = [0u8; SNOOP_PACKET_HEADER_SIZE];
+        let mut ph = PacketHeader::default();
+        let mut index = 0;
+        loop {
+            match read_full(r, &mut buf) {
+                Ok(()) => {}
+                Err(Error::Eof) => break,
+                Err(e) => return Err(e),
+            }
+            Parser::parse_packet_header(&buf, &mut ph)?;
+            if index % every == 0 {
+                entries.push(IndexEntry {
+                    index,
+                    timestamp: ph.timestamp()?,
+                    offset,
+                });
+            }
+            // the header size fits into u32 and the parser checked that the record holds it
+            #[allow(clippy::cast_possible_truncation)]
+            let skip = ph.packet_record_length - SNOOP_PACKET_HEADER_SIZE as u32;
+            offset = r
+                .seek(SeekFrom::Current(i64::from(skip)))
+                .map_err(Error::Io)?;
+            index += 1;
+        }
+        Ok(Self { entries })
+    }
+
+    /// the index entries ordered by packet index
+    #[must_use]
+    pub fn entries(&self) -> &[IndexEntry] {
+        &self.entries
+    }
+
+    /// last entry at or before the packet index
+    #[must_use]
+    pub fn lookup_index(&self, index: u64) -> Option<&IndexEntry> {
+        let n = self.entries.partition_point(|e| e.index <= index);
+        n.checked_sub(1).map(|n| &self.entries[n])
+    }
+
+    /// last entry before the timestamp, the packets with this timestamp start after it
+    #[must_use]
+    pub fn lookup_time(&self, time: Duration) -> Option<&IndexEntry> {
+        let n = self.entries.partition_point(|e| e.timestamp < time);
+        n.checked_sub(1).map(|n| &self.entries[n])
+    }
+}
+
+/// write the packets of the range from the reader to the writer and return the number of written packets.
+/// the reader must be at the first packet record.
+/// # Errors
+/// will return [`Error`] if a packet can not be read or written.
+pub fn slice<R: Read, W: Write>(
+    reader: &mut Reader<R>,
+    writer: &mut Writer<W>,
+    range: Range,
+) -> Result<u64, Error> {
+    write_range(reader, writer, range, 0)
+}
+
+/// write the packets of the range like [`slice()`], but seek to the nearest indexed packet before the range first.
+/// # Errors
+/// will return [`Error`] if a packet can not be read or written or the reader can not seek.
+pub fn slice_indexed<R: Read + Seek, W: Write>(
+    reader: &mut Reader<R>,
+    index: &SeekIndex,
+    writer: &mut Writer<W>,
+    range: Range,
+) -> Result<u64, Error> {
+    let entry = match range {
+        Range::Index(start, _) => index.lookup_index(start),
+        Range::Time(start, _) => index.lookup_time(start),
+    };
+    let (offset, first) = entry.map_or((SNOOP_HEADER_SIZE as u64, 0), |e| (e.offset, e.index));
+    reader
+        .get_mut()
+        .seek(SeekFrom::Start(offset))
+        .map_err(Error::Io)?;
+    write_range(reader, writer, range, first)
+}
+
+/// write the packets in range, `index` is the index of the next packet of the reader.
+fn write_range<R: Read, W: Write>(
+    reader: &mut Reader<R>,
+    writer: &mut Writer<W>,
+    range: Range,
+    mut index: u64,
+) -> Result<u64, Error> {
+    let mut base = None;
+    let mut cnt = 0;
+    while let Some(packet) = reader.iter_ref() {
+        let packet = packet?;
+        let (before, after) = match range {
+            Range::Index(start, end) => (index < start, index >= end),
+            Range::Time(start, end) => {
+                let t = packet.header.timestamp()?;
+                (t < start, t >= end)
+            }
+        };
+        index += 1;
+        if after {
+            break;
+        }
+        if before {
+            continue;
+        }
+        let drops = packet.header.cumulative_drops;
+        let base = *base.get_or_insert(drops);
+        let header = PacketHeader {
+            cumulative_drops: drops.saturating_sub(base),
+            ..packet.header.clone()
+        };
+        writer.write_record(&header, packet.data)?;
+        cnt += 1;
+    }
+    writer.flush()?;
+    Ok(cnt)
+}
@@ -0,0 +1,55 @@
+//! rewrite a capture with a smaller snaplen, like `editcap -s`.
+//!
+//! the packet data is cut to the snaplen, the original length is kept and
+//! the record length is recalculated with the padding to 4 bytes.
+use crate::format::{PacketHeader, SnoopPacket};
+use crate::parse::Parser;
+use crate::read::Reader;
+use crate::write::Writer;
+use crate::Error;
+use std::io::{Read, Write};
+
+/// header and data of a packet cut to the snaplen.
+#[must_use]
+pub fn truncate<'a>(
+    header: &PacketHeader,
+    data: &'a [u8],
+    snaplen: u32,
+) -> (PacketHeader, &'a [u8]) {
+    let included_length = header.included_length.min(snaplen);
+    (
+        PacketHeader {
+            included_length,
+            packet_record_length: Parser::record_len(included_length),
+            ..header.clone()
+        },
+        &data[..data.len().min(included_length as usize)],
+    )
+}
+
+/// cut the packet to the snaplen.
+pub fn truncate_packet(packet: &mut SnoopPacket, snaplen: u32) {
+    let (header, data) = truncate(&packet.header, &packet.data, snaplen);
+    let len = data.len();
+    packet.header = header;
+    packet.data.truncate(len);
+}
+
+/// write all packets cut to the snaplen and return the number of written packets.
+/// # Errors
+/// will return [`Error`] if a packet can not be read or written.
+pub fn snaplen<R: Read, W: Write>(
+    reader: &mut Reader<R>,
+    writer: &mut Writer<W>,
+    snaplen: u32,
+) -> Result<u64, Error> {
+    let mut cnt = 0;
+    while let Some(packet) = reader.iter_ref() {
+        let packet = packet?;
+        let (header, data) = truncate(packet.header, packet.data, snaplen);
+        writer.write_record(&header, data)?;
+        cnt += 1;
+    }
+    writer.flush()?;
+    Ok(cnt)
+}
@@ -0,0 +1,84 @@
+//! split a capture into multiple snoop files, like `editcap -c/-i`.
+//!
+//! the files are written with a [`RotatingWriter`], so they are cut by packet count,
+//! by time span, optionally aligned to wall clock boundaries, or by byte size.
+use crate::read::Reader;
+use crate::rotate::{RotatingWriter, Rotation};
+use crate::Error;
+use std::io::Read;
+use std::path::PathBuf;
+use std::time::Duration;
+
+/// split all remaining packets of the reader into files created from the template
+/// and return the paths of the written files.
+/// each file starts with the snoop header of the reader.
+/// see [`RotatingWriter::new`] for the supported template patterns, e.g. `part-%n.snoop`.
+/// # Errors
+/// will return [`Error`] if a packet can not be read or a file can not be written.
+pub fn split<R: Read>(
+    reader: &mut Reader<R>,
+    template: impl Into<String>,
+    rotation: Rotation,
+) -> Result<Vec<PathBuf>, Error> {
+    let mut w = RotatingWriter::new(template, reader.header().link_type, rotation);
+    for packet in reader {
+        w.write_packet(&packet?)?;
+    }
+    w.flush()?;
+    Ok(w.files().map(PathBuf::from).collect())
+}
+
+/// split into files with at most `packets` packets each.
+/// # Errors
+/// will return [`Error`] if a packet can not be read or a file can not be written.
+pub fn split_packets<R: Read>(
+    reader: &mut Reader<R>,
+    template: impl Into<String>,
+    packets: u64,
+) -> Result<Vec<PathBuf>, Error> {
+    split(
+        reader,
+        template,
+        Rotation {
+            max_packets: Some(packets),
+            ..Default::default()
+        },
+    )
+}
+
+/// split into files covering `interval` each, aligned to wall clock boundaries.
+/// # Errors
+/// will return [`Error`] if a packet can not be read or a file can not be written.
+pub fn split_interval<R: Read>(
+    reader: &mut Reader<R>,
+    template: impl Into<String>,
+    interval: Duration,
+) -> Result<Vec<PathBuf>, Error> {
+    split(
+        reader,
+        template,
+        Rotation {
+            max_duration: Some(interval),
+            align: true,
+            ..Default::default()
+        },
+    )
+}
+
+/// split into files of at most `bytes` bytes each, a file holds at least one packet.
+/// # Errors
+/// will return [`Error`] if a packet can not be read or a file can not be written.
+pub fn split_bytes<R: Read>(
+    reader: &mut Reader<R>,
+    template: impl Into<String>,
+    bytes: u64,
+) -> Result<Vec<PathBuf>, Error> {
+    split(
+        reader,
+        template,
+        Rotation {
+            max_bytes: Some(bytes),
+            ..Default::default()
+        },
+    )
+}
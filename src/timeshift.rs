@@ -0,0 +1,113 @@
+//! adjust the packet timestamps, like `editcap -t` and `editcap -S`.
+//!
+//! the timestamps are shifted by a fixed offset, moved so the first packet starts at a given time
+//! or corrected by a linear clock drift between two reference points. microseconds carry into
+//! seconds and results outside of the `u32` seconds range return [`Error::TimeEpoch`].
+use crate::format::PacketHeader;
+use crate::read::Reader;
+use crate::write::Writer;
+use crate::Error;
+use std::io::{Read, Write};
+use std::time::Duration;
+
+/// a capture time of the clock to correct and the actual time at this moment.
+#[derive(Debug, Default, PartialEq, Eq, Copy, Clone)]
+pub struct ReferencePoint {
+    /// time since the unix epoch in the capture
+    pub captured: Duration,
+    /// actual time since the unix epoch
+    pub actual: Duration,
+}
+
+/// how to change the timestamps.
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum Shift {
+    /// add the offset in microseconds, negative values move the packets back in time
+    Offset(i64),
+    /// move all packets so the first packet starts at this time since the unix epoch
+    StartAt(Duration),
+    /// map the capture time linear through both reference points to correct a drifting clock.
+    /// if both points have the same capture time, only the offset of the first point is applied
+    Drift(ReferencePoint, ReferencePoint),
+}
+
+/// timestamp transformation over a stream of packets.
+#[derive(Debug)]
+pub struct TimeShift {
+    shift: Shift,
+    offset: Option<i128>,
+}
+
+impl TimeShift {
+    /// create a new timestamp transformation.
+    #[must_use]
+    pub fn new(shift: Shift) -> Self {
+        Self {
+            shift,
+            offset: match shift {
+                Shift::Offset(offset) => Some(i128::from(offset)),
+                _ => None,
+            },
+        }
+    }
+
+    /// change the timestamp of the packet header.
+    /// # Errors
+    /// will return [`Error::TimeEpoch`] if the new timestamp is before the epoch or does not fit into u32 seconds.
+    pub fn apply(&mut self, ph: &mut PacketHeader) -> Result<(), Error> {
+        let time =
+            i128::from(ph.timestamp_seconds) * 1_000_000 + i128::from(ph.timestamp_microseconds);
+        let time = match self.shift {
+            Shift::Offset(_) | Shift::StartAt(_) => {
+                let offset = *self.offset.get_or_insert_with(|| match self.shift {
+                    Shift::StartAt(start) => micros(start) - time,
+                    _ => 0,
+                });
+                time + offset
+            }
+            Shift::Drift(a, b) => {
+                let (ca, aa) = (micros(a.captured), micros(a.actual));
+                let (cb, ab) = (micros(b.captured), micros(b.actual));
+                if ca == cb {
+                    time + aa - ca
+                } else {
+                    aa + (time - ca) * (ab - aa) / (cb - ca)
+                }
+            }
+        };
+        if time < 0 {
+            return Err(Error::TimeEpoch);
+        }
+        ph.timestamp_seconds = u32::try_from(time / 1_000_000).map_err(|_| Error::TimeEpoch)?;
+        #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+        let micros = (time % 1_000_000) as u32;
+        ph.timestamp_microseconds = micros;
+        Ok(())
+    }
+}
+
+/// write all packets with changed timestamps and return the number of written packets.
+/// # Errors
+/// will return [`Error::TimeEpoch`] if a new timestamp is out of range
+/// and [`Error`] if a packet can not be read or written.
+pub fn timeshift<R: Read, W: Write>(
+    reader: &mut Reader<R>,
+    writer: &mut Writer<W>,
+    shift: Shift,
+) -> Result<u64, Error> {
+    let mut ts = TimeShift::new(shift);
+    let mut cnt = 0;
+    while let Some(packet) = reader.iter_ref() {
+        let packet = packet?;
+        let mut header = packet.header.clone();
+        ts.apply(&mut header)?;
+        writer.write_record(&header, packet.data)?;
+        cnt += 1;
+    }
+    writer.flush()?;
+    Ok(cnt)
+}
+
+fn micros(d: Duration) -> i128 {
+    i128::from(d.as_secs()) * 1_000_000 + i128::from(d.subsec_micros())
+}
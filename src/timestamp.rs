@@ -0,0 +1,155 @@
+//! conversions of the packet timestamps to and from [`Duration`] and [`SystemTime`].
+//!
+//! the **chrono** and **time** features add conversions to and from their date time types.
+//! a timestamp with microseconds of one second or more is invalid and returns
+//! [`Error::InvalidMicroseconds`], seconds beyond `u32` return [`Error::TimeEpoch`].
+use crate::civil::DateTime;
+use crate::format::PacketHeader;
+use crate::Error;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// styles to format a packet timestamp.
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum TimeFormat {
+    /// utc date and time in RFC 3339, e.g. `2019-04-23T07:18:36.831815Z`
+    Rfc3339,
+    /// seconds since the unix epoch, e.g. `1556003916.831815`
+    Epoch,
+    /// seconds relative to the given timestamp, usually of the first packet, e.g. `1.500000`
+    Relative(Duration),
+}
+
+impl PacketHeader {
+    /// timestamp as duration since the unix epoch.
+    /// # Errors
+    /// will return [`Error::InvalidMicroseconds`] if the microseconds are not below one second.
+    pub fn timestamp(&self) -> Result<Duration, Error> {
+        if self.timestamp_microseconds >= 1_000_000 {
+            return Err(Error::InvalidMicroseconds(self.timestamp_microseconds));
+        }
+        Ok(Duration::new(
+            u64::from(self.timestamp_seconds),
+            self.timestamp_microseconds * 1000,
+        ))
+    }
+
+    /// set the timestamp from a duration since the unix epoch, nanoseconds are truncated.
+    /// # Errors
+    /// will return [`Error::TimeEpoch`] if the seconds do not fit into u32.
+    pub fn set_timestamp(&mut self, timestamp: Duration) -> Result<(), Error> {
+        self.timestamp_seconds =
+            u32::try_from(timestamp.as_secs()).map_err(|_| Error::TimeEpoch)?;
+        self.timestamp_microseconds = timestamp.subsec_micros();
+        Ok(())
+    }
+
+    /// timestamp as system time.
+    /// # Errors
+    /// will return [`Error::InvalidMicroseconds`] if the microseconds are not below one second.
+    pub fn system_time(&self) -> Result<SystemTime, Error> {
+        Ok(UNIX_EPOCH + self.timestamp()?)
+    }
+
+    /// set the timestamp from a system time, nanoseconds are truncated.
+    /// # Errors
+    /// will return [`Error::Time`] if the time is before the unix epoch
+    /// and [`Error::TimeEpoch`] if the seconds do not fit into u32.
+    pub fn set_system_time(&mut self, time: SystemTime) -> Result<(), Error> {
+        self.set_timestamp(time.duration_since(UNIX_EPOCH).map_err(Error::Time)?)
+    }
+
+    /// format the timestamp in the given style.
+    /// # Errors
+    /// will return [`Error::InvalidMicroseconds`] if the microseconds are not below one second.
+    pub fn format_timestamp(&self, format: TimeFormat) -> Result<String, Error> {
+        let timestamp = self.timestamp()?;
+        Ok(match format {
+            TimeFormat::Rfc3339 => {
+                let dt = DateTime::from_unix(timestamp.as_secs());
+                format!(
+                    "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}.{:06}Z",
+                    dt.year,
+                    dt.month,
+                    dt.day,
+                    dt.hour,
+                    dt.minute,
+                    dt.second,
+                    timestamp.subsec_micros()
+                )
+            }
+            TimeFormat::Epoch => {
+                format!("{}.{:06}", timestamp.as_secs(), timestamp.subsec_micros())
+            }
+            TimeFormat::Relative(first) => {
+                let (sign, d) = match timestamp.checked_sub(first) {
+                    Some(d) => ("", d),
+                    None => ("-", first.saturating_sub(timestamp)),
+                };
+                format!("{sign}{}.{:06}", d.as_secs(), d.subsec_micros())
+            }
+        })
+    }
+
+    /// timestamp as chrono utc date time.
+    /// # Errors
+    /// will return [`Error::InvalidMicroseconds`] if the microseconds are not below one second.
+    #[cfg(feature = "chrono")]
+    pub fn datetime(&self) -> Result<chrono::DateTime<chrono::Utc>, Error> {
+        let timestamp = self.timestamp()?;
+        chrono::DateTime::from_timestamp(
+            i64::from(self.timestamp_seconds),
+            timestamp.subsec_nanos(),
+        )
+        .ok_or(Error::TimeEpoch)
+    }
+
+    /// set the timestamp from a chrono date time, nanoseconds are truncated.
+    /// # Errors
+    /// will return [`Error::TimeEpoch`] if the time is before the unix epoch or the seconds do not fit into u32.
+    #[cfg(feature = "chrono")]
+    pub fn set_datetime<Tz: chrono::TimeZone>(
+        &mut self,
+        time: &chrono::DateTime<Tz>,
+    ) -> Result<(), Error> {
+        self.timestamp_seconds = u32::try_from(time.timestamp()).map_err(|_| Error::TimeEpoch)?;
+        self.timestamp_microseconds = time.timestamp_subsec_micros().min(999_999);
+        Ok(())
+    }
+
+    /// timestamp as time offset date time in utc.
+    /// # Errors
+    /// will return [`Error::InvalidMicroseconds`] if the microseconds are not below one second.
+    #[cfg(feature = "time")]
+    pub fn offset_datetime(&self) -> Result<time::OffsetDateTime, Error> {
+        let timestamp = self.timestamp()?;
+        let nanos = i128::try_from(timestamp.as_nanos()).map_err(|_| Error::TimeEpoch)?;
+        time::OffsetDateTime::from_unix_timestamp_nanos(nanos).map_err(|_| Error::TimeEpoch)
+    }
+
+    /// set the timestamp from a time offset date time, nanoseconds are truncated.
+    /// # Errors
+    /// will return [`Error::TimeEpoch`] if the time is before the unix epoch or the seconds do not fit into u32.
+    #[cfg(feature = "time")]
+    pub fn set_offset_datetime(&mut self, time: time::OffsetDateTime) -> Result<(), Error> {
+        self.timestamp_seconds =
+            u32::try_from(time.unix_timestamp()).map_err(|_| Error::TimeEpoch)?;
+        self.timestamp_microseconds = time.microsecond();
+        Ok(())
+    }
+}
+
+impl TryFrom<&PacketHeader> for Duration {
+    type Error = Error;
+
+    fn try_from(ph: &PacketHeader) -> Result<Self, Error> {
+        ph.timestamp()
+    }
+}
+
+impl TryFrom<&PacketHeader> for SystemTime {
+    type Error = Error;
+
+    fn try_from(ph: &PacketHeader) -> Result<Self, Error> {
+        ph.system_time()
+    }
+}
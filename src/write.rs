@@ -1,7 +1,7 @@
 //! write to a underlying writer like a file or a buffer.
 use crate::format::{
-    DataLinkType, PacketHeader, SnoopHeader, SnoopPacket, MAX_CAPTURE_LEN, MAX_CAPTURE_PADS,
-    SNOOP_MAGIC, SNOOP_VERSION,
+    DataLinkType, PacketHeader, SnoopHeader, SnoopPacket, SnoopPacketRef, MAX_CAPTURE_LEN,
+    MAX_CAPTURE_PADS, SNOOP_MAGIC, SNOOP_VERSION,
 };
 use crate::parse::Parser;
 use crate::Error;
@@ -58,7 +58,7 @@ where
             return Err(Error::CaptureLenExceeded);
         }
 
-        if ph.packet_record_length < (24 + ph.original_length) {
+        if ph.packet_record_length < (24 + ph.included_length) {
             return Err(Error::InvalidRecordLength);
         }
 
@@ -95,11 +95,24 @@ where
     /// use this function if you want to create the packet header yourself
     /// # Errors
     /// will return [`Error`] if something unexpected happen.
-    #[allow(clippy::cast_possible_truncation)]
     pub fn write_packet(&mut self, packet: &SnoopPacket) -> Result<(), Error> {
-        self.write_packet_header(&packet.header)?;
-        self.write_data(&packet.data)?;
-        self.pad = Parser::pad(&packet.header) as u32;
+        self.write_record(&packet.header, &packet.data)
+    }
+
+    /// write packet header and data to writer from a [`SnoopPacketRef`], like [`Writer::write_packet`].
+    /// use this function to copy packets from [`crate::read::Reader::read_ref`] without allocation.
+    /// # Errors
+    /// will return [`Error`] if something unexpected happen.
+    pub fn write_packet_ref(&mut self, packet: &SnoopPacketRef) -> Result<(), Error> {
+        self.write_record(packet.header, packet.data)
+    }
+
+    /// write packet header, data and pads
+    #[allow(clippy::cast_possible_truncation)]
+    pub(crate) fn write_record(&mut self, ph: &PacketHeader, data: &[u8]) -> Result<(), Error> {
+        self.write_packet_header(ph)?;
+        self.write_data(data)?;
+        self.pad = Parser::pad(ph) as u32;
         /* add pads, only 4 supported */
         match self.pad {
             0 => (),
@@ -118,31 +131,58 @@ where
     /// use this function if you want to auto generate the packet header.
     /// # Errors
     /// will return [`Error`] if something unexpected happen.
-
     pub fn write(&mut self, data: Vec<u8>) -> Result<(), Error> {
-        let time = SystemTime::now()
-            .duration_since(UNIX_EPOCH)
-            .map_err(Error::Time)?;
-        let mut packet = SnoopPacket {
-            header: PacketHeader {
-                ..Default::default()
-            },
-            data,
-        };
-
-        packet.header.original_length = match packet.data.len().try_into() {
-            Ok(s) => s,
-            Err(_) => return Err(Error::OriginalLenExceeded),
-        };
-        packet.header.included_length = packet.header.original_length; // not truncated
-        packet.header.packet_record_length = packet.header.original_length + 24; // no pads
-        packet.header.cumulative_drops = 0;
-        // will be supported to 2038 :-)
-        packet.header.timestamp_seconds = match time.as_secs().try_into() {
-            Ok(t) => t,
-            Err(_) => return Err(Error::TimeEpoch),
-        };
-        packet.header.timestamp_microseconds = time.subsec_micros();
+        let packet = packet_now(data)?;
         self.write_packet(&packet)
     }
+
+    /// get a reference to the snoop file format header
+    pub fn header(&self) -> &SnoopHeader {
+        &self.header
+    }
+
+    /// flush the underlying writer.
+    /// # Errors
+    /// will return [`Error::Io`] if something unexpected happen.
+    pub fn flush(&mut self) -> Result<(), Error> {
+        self.w.flush().map_err(Error::Io)
+    }
+
+    /// get a mutable reference to the underlying writer.
+    pub fn get_mut(&mut self) -> &mut W {
+        &mut self.w
+    }
+
+    /// unwrap this writer and return the underlying writer.
+    pub fn into_inner(self) -> W {
+        self.w
+    }
+}
+
+/// create a [`SnoopPacket`] from data with a generated packet header using the current time.
+pub(crate) fn packet_now(data: Vec<u8>) -> Result<SnoopPacket, Error> {
+    let time = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_err(Error::Time)?;
+    let mut packet = SnoopPacket {
+        header: PacketHeader {
+            ..Default::default()
+        },
+        data,
+    };
+
+    packet.header.original_length = match packet.data.len().try_into() {
+        Ok(s) => s,
+        Err(_) => return Err(Error::OriginalLenExceeded),
+    };
+    packet.header.included_length = packet.header.original_length; // not truncated
+    packet.header.packet_record_length = packet.header.original_length + 24; // no pads
+    packet.header.cumulative_drops = 0;
+    // will be supported to 2038 :-)
+    packet.header.timestamp_seconds = match time.as_secs().try_into() {
+        Ok(t) => t,
+        Err(_) => return Err(Error::TimeEpoch),
+    };
+    packet.header.timestamp_microseconds = time.subsec_micros();
+    Ok(packet)
 }
@@ -0,0 +1,89 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use crate::common::HEADER;
+    use snoop::capture::{copy, CaptureSink, CaptureSource, Packet};
+    use snoop::convert::pcap::{PcapReader, PcapWriter};
+    use snoop::convert::LINKTYPE_ETHERNET;
+    use snoop::error::Error;
+    use snoop::format::{DataLinkType, SnoopPacket};
+    use snoop::read::Reader;
+    use snoop::write::Writer;
+    use std::time::Duration;
+
+    fn lengths<S: CaptureSource>(source: &mut S) -> Vec<usize> {
+        source.packets().map(|p| p.unwrap().data.len()).collect()
+    }
+
+    #[test]
+    fn capture_source() {
+        let mut reader = Reader::new(HEADER).unwrap();
+        assert_eq!(CaptureSource::link_type(&reader), DataLinkType::Ethernet);
+        let packet = reader.next_packet().unwrap().unwrap();
+        assert_eq!(packet.timestamp, Duration::new(1556002892, 831815000));
+        assert_eq!(packet.original_length, 42);
+        assert_eq!(packet.data, &HEADER[40..82]);
+        assert!(reader.next_packet().is_none());
+    }
+
+    #[test]
+    fn capture_copy_formats() {
+        let mut pcap = PcapWriter::new(Vec::new(), LINKTYPE_ETHERNET).unwrap();
+        assert_eq!(
+            copy(&mut Reader::new(HEADER).unwrap(), &mut pcap).unwrap(),
+            1
+        );
+        let pcap = pcap.into_inner();
+
+        let mut source: Box<dyn CaptureSource> = Box::new(PcapReader::new(&pcap[..]).unwrap());
+        let mut sink: Box<dyn CaptureSink> =
+            Box::new(Writer::new(Vec::new(), source.link_type()).unwrap());
+        assert_eq!(copy(&mut source, &mut sink).unwrap(), 1);
+        assert_eq!(sink.link_type(), DataLinkType::Ethernet);
+
+        let mut snoop = Vec::new();
+        let mut writer = Writer::new(&mut snoop, DataLinkType::Ethernet).unwrap();
+        copy(&mut PcapReader::new(&pcap[..]).unwrap(), &mut writer).unwrap();
+        assert_eq!(HEADER, &snoop[..]);
+        assert_eq!(lengths(&mut Reader::new(&snoop[..]).unwrap()), vec![42]);
+    }
+
+    #[test]
+    fn capture_packet_truncate() {
+        let packet = Packet {
+            timestamp: Duration::new(10, 5_000),
+            original_length: 5000,
+            drops: 2,
+            data: vec![0u8; 5000],
+        };
+        let snoop = SnoopPacket::try_from(packet.clone()).unwrap();
+        assert_eq!(snoop.header.included_length, 4096);
+        assert_eq!(snoop.header.packet_record_length, 4120);
+        assert_eq!(snoop.header.timestamp_microseconds, 5);
+        assert_eq!(snoop.data.len(), 4096);
+
+        let mut writer = Writer::new(Vec::new(), DataLinkType::Ethernet).unwrap();
+        writer.write_capture(&packet).unwrap();
+        let buf = writer.into_inner();
+        let packet = Reader::new(&buf[..])
+            .unwrap()
+            .next_packet()
+            .unwrap()
+            .unwrap();
+        assert_eq!(packet.original_length, 5000);
+        assert_eq!(packet.drops, 2);
+        assert_eq!(packet.data.len(), 4096);
+    }
+
+    #[test]
+    fn capture_source_invalid_timestamp() {
+        let mut buf = HEADER.to_vec();
+        buf[36..40].copy_from_slice(&1_000_000u32.to_be_bytes());
+        let mut reader = Reader::new(&buf[..]).unwrap();
+        assert!(matches!(
+            reader.next_packet(),
+            Some(Err(Error::InvalidMicroseconds(1_000_000)))
+        ));
+    }
+}
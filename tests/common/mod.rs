@@ -1,3 +1,5 @@
+#![allow(dead_code)]
+
 /* buffer with snoop header + packet header + packet data
 84 bytes
 */
@@ -11,3 +13,64 @@ pub const HEADER: &[u8] = &[
     0x08, 0x00, 0x06, 0x04, 0x00, 0x01, 0x1e, 0x65, 0x50, 0x7f, 0xb9, 0xca, 0x0a, 0x00, 0x33, 0x68,
     0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x0a, 0x00, 0x33, 0x01, 0x00, 0x00,
 ];
+
+/// temporary directory which is removed with its files when it is dropped
+pub struct TmpDir(std::path::PathBuf);
+
+impl std::ops::Deref for TmpDir {
+    type Target = std::path::Path;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl AsRef<std::path::Path> for TmpDir {
+    fn as_ref(&self) -> &std::path::Path {
+        &self.0
+    }
+}
+
+impl Drop for TmpDir {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.0);
+    }
+}
+
+/// create an empty temporary directory for a test, unique per test process
+pub fn tmp_dir(name: &str) -> TmpDir {
+    let dir = std::env::temp_dir().join(format!("snoop-{}-{name}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    TmpDir(dir)
+}
+
+/// capture with a copy of the packet in [`HEADER`] for every timestamp
+#[cfg(all(feature = "read", feature = "write"))]
+pub fn capture(
+    link_type: snoop::format::DataLinkType,
+    ts: &[u32],
+) -> snoop::read::Reader<std::io::Cursor<Vec<u8>>> {
+    capture_with(link_type, ts, |_, _| {})
+}
+
+/// capture like [`capture`], `f` changes the header of the packet with the index
+#[cfg(all(feature = "read", feature = "write"))]
+pub fn capture_with(
+    link_type: snoop::format::DataLinkType,
+    ts: &[u32],
+    f: impl Fn(usize, &mut snoop::format::PacketHeader),
+) -> snoop::read::Reader<std::io::Cursor<Vec<u8>>> {
+    let mut packet = snoop::read::Reader::new(HEADER)
+        .unwrap()
+        .next()
+        .unwrap()
+        .unwrap();
+    let mut w = snoop::write::Writer::new(Vec::new(), link_type).unwrap();
+    for (i, t) in ts.iter().enumerate() {
+        packet.header.timestamp_seconds = *t;
+        f(i, &mut packet.header);
+        w.write_packet(&packet).unwrap();
+    }
+    snoop::read::Reader::new(std::io::Cursor::new(w.into_inner())).unwrap()
+}
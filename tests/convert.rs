@@ -0,0 +1,100 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use crate::common::HEADER;
+    use snoop::convert::pcap::{pcap_to_snoop, snoop_to_pcap, PcapReader, PCAP_MAGIC_NANOS};
+    use snoop::convert::{from_linktype, to_linktype, ConvertStats, LINKTYPE_ETHERNET};
+    use snoop::error::Error;
+    use snoop::format::DataLinkType;
+    use snoop::read::Reader;
+    use std::io::BufReader;
+
+    fn pcap_be_nanos(linktype: u32, orig: u32, data: &[u8]) -> Vec<u8> {
+        let mut buf = Vec::new();
+        buf.extend_from_slice(&PCAP_MAGIC_NANOS.to_be_bytes());
+        buf.extend_from_slice(&[0x00, 0x02, 0x00, 0x04]);
+        buf.extend_from_slice(&[0u8; 8]);
+        buf.extend_from_slice(&65535u32.to_be_bytes());
+        buf.extend_from_slice(&linktype.to_be_bytes());
+        buf.extend_from_slice(&1556002892u32.to_be_bytes());
+        buf.extend_from_slice(&831815123u32.to_be_bytes());
+        buf.extend_from_slice(&(data.len() as u32).to_be_bytes());
+        buf.extend_from_slice(&orig.to_be_bytes());
+        buf.extend_from_slice(data);
+        buf
+    }
+
+    #[test]
+    fn convert_linktype() {
+        assert_eq!(to_linktype(DataLinkType::Ethernet), Some(LINKTYPE_ETHERNET));
+        assert_eq!(
+            from_linktype(LINKTYPE_ETHERNET),
+            Some(DataLinkType::Ethernet)
+        );
+        assert_eq!(to_linktype(DataLinkType::MetroNet), None);
+        assert_eq!(from_linktype(228), None);
+    }
+
+    #[test]
+    fn convert_round_trip() {
+        let mut pcap = Vec::new();
+        let mut reader = Reader::new(BufReader::new(HEADER)).unwrap();
+        assert_eq!(snoop_to_pcap(&mut reader, &mut pcap).unwrap(), 1);
+        assert_eq!(pcap.len(), 24 + 16 + 42);
+
+        let mut snoop = Vec::new();
+        assert_eq!(pcap_to_snoop(&pcap[..], &mut snoop).unwrap().packets, 1);
+        assert_eq!(HEADER, &snoop[..]);
+    }
+
+    #[test]
+    fn convert_pcap_big_endian_nanos() {
+        let pcap = pcap_be_nanos(LINKTYPE_ETHERNET, 60, &HEADER[40..82]);
+        let mut reader = PcapReader::new(&pcap[..]).unwrap();
+        assert!(reader.header().nanoseconds);
+        assert!(reader.header().big_endian);
+        let packet = reader.read().unwrap();
+        assert_eq!(packet.timestamp_nanoseconds, 831815123);
+        assert_eq!(packet.original_length, 60);
+
+        let mut snoop = Vec::new();
+        assert_eq!(pcap_to_snoop(&pcap[..], &mut snoop).unwrap().truncated, 0);
+        let packet = Reader::new(&snoop[..]).unwrap().read().unwrap();
+        assert_eq!(packet.header.timestamp_microseconds, 831815);
+        assert_eq!(packet.header.original_length, 60);
+        assert_eq!(packet.header.included_length, 42);
+        assert_eq!(packet.header.packet_record_length, 68);
+    }
+
+    #[test]
+    fn convert_pcap_truncate() {
+        let pcap = pcap_be_nanos(LINKTYPE_ETHERNET, 9000, &[0xAB; 9000]);
+        let mut snoop = Vec::new();
+        assert_eq!(
+            pcap_to_snoop(&pcap[..], &mut snoop).unwrap(),
+            ConvertStats {
+                packets: 1,
+                truncated: 1,
+                ..Default::default()
+            }
+        );
+        let packet = Reader::new(&snoop[..]).unwrap().read().unwrap();
+        assert_eq!(packet.header.original_length, 9000);
+        assert_eq!(packet.header.included_length, 4096);
+        assert_eq!(packet.data.len(), 4096);
+    }
+
+    #[test]
+    fn convert_pcap_invalid() {
+        let pcap = pcap_be_nanos(228, 42, &HEADER[40..82]);
+        assert!(matches!(
+            pcap_to_snoop(&pcap[..], Vec::new()),
+            Err(Error::UnsupportedLinkType(228))
+        ));
+        assert!(matches!(
+            PcapReader::new(HEADER),
+            Err(Error::InvalidFormat(_))
+        ));
+    }
+}
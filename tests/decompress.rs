@@ -0,0 +1,139 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use crate::common::HEADER;
+    use snoop::decompress::{Compression, Decompressor};
+    use snoop::error::Error;
+    use snoop::read::Reader;
+    use std::io::Read;
+    use std::sync::{Arc, Mutex};
+    use std::thread;
+    use std::time::Duration;
+
+    /// HEADER compressed with gzip -9 -n
+    const HEADER_GZ: &[u8] = &[
+        0x1F, 0x8B, 0x08, 0x00, 0x00, 0x00, 0x00, 0x00, 0x02, 0x03, 0x2B, 0xCE, 0xCB, 0xCF, 0x2F,
+        0x60, 0x00, 0x03, 0x26, 0x20, 0x66, 0x01, 0x62, 0x2D, 0x28, 0x76, 0x01, 0x09, 0xC6, 0xEC,
+        0xDB, 0xE1, 0xC3, 0xC0, 0xB3, 0xD1, 0xBD, 0x26, 0x4A, 0xC6, 0xD3, 0xE6, 0xA2, 0x5C, 0x6A,
+        0x40, 0xFD, 0xCE, 0x53, 0x1C, 0x6C, 0x0C, 0x8C, 0x1C, 0x0C, 0x6C, 0x2C, 0x0C, 0x8C, 0x10,
+        0x3E, 0x17, 0x83, 0x71, 0x06, 0xC4, 0x08, 0x20, 0x8B, 0x91, 0x81, 0x01, 0x00, 0xD6, 0x86,
+        0xDE, 0xA8, 0x54, 0x00, 0x00, 0x00,
+    ];
+
+    /// HEADER compressed with zstd -19
+    const HEADER_ZST: &[u8] = &[
+        0x28, 0xB5, 0x2F, 0xFD, 0x24, 0x54, 0x5D, 0x02, 0x00, 0x22, 0xC4, 0x0F, 0x1B, 0xB0, 0xB9,
+        0x1A, 0xE0, 0x80, 0x53, 0x1E, 0x06, 0xE7, 0x11, 0x4C, 0xA4, 0x42, 0xDD, 0x04, 0x1D, 0xAA,
+        0x1F, 0x45, 0x23, 0x8E, 0x77, 0xED, 0x6E, 0xEE, 0x9D, 0x02, 0x9F, 0xE6, 0xEE, 0xFF, 0x8D,
+        0xB9, 0x4B, 0x2F, 0x76, 0x4B, 0xB3, 0x06, 0x39, 0x8B, 0x50, 0xC4, 0x88, 0x02, 0x70, 0x50,
+        0x2C, 0xF3, 0x0E, 0x9A, 0x87, 0xF8, 0xAF, 0xF2, 0x5F, 0xBF, 0x1A, 0xB9, 0xF7, 0x48, 0x02,
+        0x03, 0x00, 0x9B, 0x39, 0x9E, 0x9B, 0xA7, 0x05, 0x45, 0x95, 0x5A, 0x08, 0xCA,
+    ];
+
+    /// HEADER compressed with xz -9
+    const HEADER_XZ: &[u8] = &[
+        0xFD, 0x37, 0x7A, 0x58, 0x5A, 0x00, 0x00, 0x04, 0xE6, 0xD6, 0xB4, 0x46, 0x04, 0xC0, 0x48,
+        0x54, 0x21, 0x01, 0x1C, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x05, 0x11,
+        0xAD, 0x78, 0xE0, 0x00, 0x53, 0x00, 0x40, 0x5D, 0x00, 0x39, 0x9B, 0x8A, 0x56, 0xF1, 0xB0,
+        0x9E, 0x49, 0xE8, 0xC6, 0xDA, 0xFA, 0x21, 0x77, 0xA0, 0xC3, 0x52, 0x9B, 0x9C, 0x78, 0x1C,
+        0x8C, 0x22, 0xD1, 0x45, 0x5B, 0x46, 0xD8, 0x4C, 0x8C, 0x1F, 0x8C, 0x26, 0x94, 0x2F, 0xA4,
+        0x0B, 0xEB, 0xF4, 0x18, 0x11, 0xFF, 0xE4, 0xB4, 0x5B, 0x47, 0x88, 0x84, 0x74, 0x1F, 0xA9,
+        0x6A, 0xE5, 0x72, 0x6F, 0x8E, 0x68, 0xD1, 0x1E, 0x67, 0x83, 0xFC, 0xA0, 0x00, 0x00, 0xE7,
+        0xE5, 0xE8, 0xF9, 0xE5, 0x5B, 0x31, 0xCA, 0x00, 0x01, 0x64, 0x54, 0x65, 0x8E, 0x11, 0x4D,
+        0x1F, 0xB6, 0xF3, 0x7D, 0x01, 0x00, 0x00, 0x00, 0x00, 0x04, 0x59, 0x5A,
+    ];
+
+    /// HEADER as two zstd frames, the snoop header and the packet
+    const HEADER_ZST_FRAMES: &[u8] = &[
+        0x28, 0xB5, 0x2F, 0xFD, 0x24, 0x10, 0x81, 0x00, 0x00, 0x73, 0x6E, 0x6F, 0x6F, 0x70, 0x00,
+        0x00, 0x00, 0x00, 0x00, 0x00, 0x02, 0x00, 0x00, 0x00, 0x04, 0xCD, 0x22, 0x31, 0xA9, 0x28,
+        0xB5, 0x2F, 0xFD, 0x24, 0x44, 0xFD, 0x01, 0x00, 0x34, 0x03, 0x00, 0x00, 0x00, 0x2A, 0x44,
+        0x00, 0x00, 0x00, 0x00, 0x5C, 0xBE, 0xB8, 0x4C, 0x00, 0x0C, 0xB1, 0x47, 0x7C, 0x5A, 0x1C,
+        0x49, 0x3C, 0xD1, 0x1E, 0x65, 0x50, 0x7F, 0xB9, 0xCA, 0x08, 0x06, 0x00, 0x01, 0x08, 0x00,
+        0x06, 0x04, 0x00, 0x01, 0x0A, 0x00, 0x33, 0x68, 0x00, 0x00, 0x0A, 0x00, 0x33, 0x01, 0x00,
+        0x00, 0x03, 0x00, 0x4F, 0x05, 0xB2, 0x99, 0xE3, 0x9D, 0x39, 0x22, 0xEE, 0x4D, 0x52, 0x09,
+    ];
+
+    /// reader over a buffer which is still growing, returns 0 if all data is consumed
+    struct Growing {
+        buf: Arc<Mutex<Vec<u8>>>,
+        pos: usize,
+    }
+
+    impl Read for Growing {
+        fn read(&mut self, out: &mut [u8]) -> std::io::Result<usize> {
+            let buf = self.buf.lock().unwrap();
+            let n = out.len().min(buf.len() - self.pos);
+            out[..n].copy_from_slice(&buf[self.pos..self.pos + n]);
+            self.pos += n;
+            Ok(n)
+        }
+    }
+
+    fn assert_packet(buf: &[u8], compression: Option<Compression>) {
+        let d = Decompressor::new(buf).unwrap();
+        assert_eq!(d.compression(), compression);
+        let packets: Vec<_> = Reader::new(d).unwrap().map(Result::unwrap).collect();
+        assert_eq!(packets.len(), 1);
+        assert_eq!(packets[0].data, &HEADER[40..82]);
+    }
+
+    #[test]
+    fn decompress_plain() {
+        assert_packet(HEADER, None);
+    }
+
+    #[test]
+    fn decompress_formats() {
+        assert_packet(HEADER_GZ, Some(Compression::Gzip));
+        assert_packet(HEADER_ZST, Some(Compression::Zstd));
+        assert_packet(HEADER_XZ, Some(Compression::Xz));
+        assert_packet(HEADER_ZST_FRAMES, Some(Compression::Zstd));
+    }
+
+    #[test]
+    fn decompress_open() {
+        let path = std::env::temp_dir().join(format!("snoop-{}.snoop.zst", std::process::id()));
+        std::fs::write(&path, HEADER_ZST).unwrap();
+        let mut reader = Reader::open(&path).unwrap();
+        assert_eq!(reader.read().unwrap().data, &HEADER[40..82]);
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn decompress_zstd_truncated() {
+        let mut buf = Vec::new();
+        let err = Decompressor::new(&HEADER_ZST[..60])
+            .unwrap()
+            .read_to_end(&mut buf)
+            .unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::UnexpectedEof);
+
+        // the snoop header frame is complete, the packet frame is not
+        let mut reader = Reader::new(Decompressor::new(&HEADER_ZST_FRAMES[..60]).unwrap()).unwrap();
+        assert!(
+            matches!(reader.read(), Err(Error::Io(e)) if e.kind() == std::io::ErrorKind::UnexpectedEof)
+        );
+    }
+
+    #[test]
+    fn decompress_zstd_follow() {
+        // the snoop header frame and a part of the packet frame are written
+        let buf = Arc::new(Mutex::new(HEADER_ZST_FRAMES[..60].to_vec()));
+        let growing = Growing {
+            buf: buf.clone(),
+            pos: 0,
+        };
+        let mut reader = Reader::new(Decompressor::follow(growing).unwrap()).unwrap();
+        let writer = thread::spawn(move || {
+            thread::sleep(Duration::from_millis(50));
+            buf.lock()
+                .unwrap()
+                .extend_from_slice(&HEADER_ZST_FRAMES[60..]);
+        });
+        let packet = reader.read_stream(Duration::from_millis(5)).unwrap();
+        assert_eq!(packet.data, &HEADER[40..82]);
+        writer.join().unwrap();
+    }
+}
@@ -0,0 +1,141 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use crate::common::HEADER;
+    use snoop::dedup::{dedup, DedupOptions, DedupStats, Deduplicator, Window};
+    use snoop::error::Error;
+    use snoop::format::{DataLinkType, PacketHeader};
+    use snoop::read::Reader;
+    use snoop::write::Writer;
+    use std::io::{BufReader, Cursor};
+    use std::time::Duration;
+
+    fn header(secs: u32) -> PacketHeader {
+        PacketHeader {
+            timestamp_seconds: secs,
+            ..Default::default()
+        }
+    }
+
+    // ethernet frame with an IPv4 header
+    fn ipv4(ttl: u8, checksum: u8, id: u8) -> Vec<u8> {
+        let mut frame = vec![0u8; 14 + 20];
+        frame[12] = 0x08;
+        frame[14] = 0x45;
+        frame[14 + 5] = id;
+        frame[14 + 8] = ttl;
+        frame[14 + 11] = checksum;
+        frame
+    }
+
+    #[test]
+    fn packet_window() {
+        let mut d = Deduplicator::new(
+            DataLinkType::Ethernet,
+            DedupOptions {
+                window: Window::Packets(2),
+                ..Default::default()
+            },
+        );
+        assert!(!d.is_duplicate(&header(0), b"a").unwrap());
+        assert!(!d.is_duplicate(&header(0), b"b").unwrap());
+        assert!(d.is_duplicate(&header(0), b"a").unwrap());
+        assert!(!d.is_duplicate(&header(0), b"c").unwrap());
+        assert!(!d.is_duplicate(&header(0), b"d").unwrap());
+        // b is out of the window
+        assert!(!d.is_duplicate(&header(0), b"b").unwrap());
+    }
+
+    #[test]
+    fn time_window() {
+        let mut d = Deduplicator::new(
+            DataLinkType::Ethernet,
+            DedupOptions {
+                window: Window::Time(Duration::from_secs(2)),
+                ..Default::default()
+            },
+        );
+        assert!(!d.is_duplicate(&header(10), b"a").unwrap());
+        assert!(d.is_duplicate(&header(12), b"a").unwrap());
+        assert!(!d.is_duplicate(&header(15), b"a").unwrap());
+    }
+
+    #[test]
+    fn ignore_mutable() {
+        let mut strict = Deduplicator::new(DataLinkType::Ethernet, DedupOptions::default());
+        assert!(!strict.is_duplicate(&header(0), &ipv4(64, 1, 1)).unwrap());
+        assert!(!strict.is_duplicate(&header(0), &ipv4(63, 2, 1)).unwrap());
+
+        let mut d = Deduplicator::new(
+            DataLinkType::Ethernet,
+            DedupOptions {
+                ignore_mutable: true,
+                ..Default::default()
+            },
+        );
+        assert!(!d.is_duplicate(&header(0), &ipv4(64, 1, 1)).unwrap());
+        assert!(d.is_duplicate(&header(0), &ipv4(63, 2, 1)).unwrap());
+        assert!(!d.is_duplicate(&header(0), &ipv4(63, 2, 2)).unwrap());
+    }
+
+    #[test]
+    fn ignore_mutable_vlan() {
+        // the same IPv4 frames behind a VLAN tag
+        let tagged = |ttl, checksum| {
+            let mut frame = ipv4(ttl, checksum, 1);
+            frame.splice(12..12, [0x81, 0x00, 0x00, 0x01]);
+            frame
+        };
+        let mut d = Deduplicator::new(
+            DataLinkType::Ethernet,
+            DedupOptions {
+                ignore_mutable: true,
+                ..Default::default()
+            },
+        );
+        assert!(!d.is_duplicate(&header(0), &tagged(64, 1)).unwrap());
+        assert!(d.is_duplicate(&header(0), &tagged(63, 2)).unwrap());
+    }
+
+    #[test]
+    fn invalid_timestamp() {
+        let mut d = Deduplicator::new(DataLinkType::Ethernet, DedupOptions::default());
+        let header = PacketHeader {
+            timestamp_microseconds: 1_000_000,
+            ..Default::default()
+        };
+        assert!(matches!(
+            d.is_duplicate(&header, b"a"),
+            Err(Error::InvalidMicroseconds(1_000_000))
+        ));
+    }
+
+    #[test]
+    fn dedup_capture() {
+        let packet = Reader::new(BufReader::new(HEADER))
+            .unwrap()
+            .next()
+            .unwrap()
+            .unwrap();
+        let mut w = Writer::new(Vec::new(), DataLinkType::Ethernet).unwrap();
+        for i in 0..6 {
+            let mut p = packet.clone();
+            p.header.timestamp_seconds += i;
+            p.data[0] = u8::try_from(i / 2).unwrap();
+            w.write_packet(&p).unwrap();
+        }
+        let mut r = Reader::new(Cursor::new(w.into_inner())).unwrap();
+        let mut w = Writer::new(Vec::new(), DataLinkType::Ethernet).unwrap();
+        let stats = dedup(&mut r, &mut w, DedupOptions::default()).unwrap();
+        assert_eq!(
+            stats,
+            DedupStats {
+                packets: 6,
+                removed: 3
+            }
+        );
+        let r = Reader::new(Cursor::new(w.into_inner())).unwrap();
+        assert_eq!(r.count(), 3);
+    }
+}
@@ -0,0 +1,106 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use crate::common::HEADER;
+    use snoop::capture::CaptureSource;
+    use snoop::convert::pcap::snoop_to_pcap;
+    use snoop::convert::pcapng::snoop_to_pcapng;
+    use snoop::detect::{from_reader, Format};
+    use snoop::error::Error;
+    use snoop::format::DataLinkType;
+    use snoop::read::Reader;
+    use std::fs;
+
+    /// HEADER compressed with gzip -9 -n
+    #[cfg(feature = "gzip")]
+    const HEADER_GZ: &[u8] = &[
+        0x1F, 0x8B, 0x08, 0x00, 0x00, 0x00, 0x00, 0x00, 0x02, 0x03, 0x2B, 0xCE, 0xCB, 0xCF, 0x2F,
+        0x60, 0x00, 0x03, 0x26, 0x20, 0x66, 0x01, 0x62, 0x2D, 0x28, 0x76, 0x01, 0x09, 0xC6, 0xEC,
+        0xDB, 0xE1, 0xC3, 0xC0, 0xB3, 0xD1, 0xBD, 0x26, 0x4A, 0xC6, 0xD3, 0xE6, 0xA2, 0x5C, 0x6A,
+        0x40, 0xFD, 0xCE, 0x53, 0x1C, 0x6C, 0x0C, 0x8C, 0x1C, 0x0C, 0x6C, 0x2C, 0x0C, 0x8C, 0x10,
+        0x3E, 0x17, 0x83, 0x71, 0x06, 0xC4, 0x08, 0x20, 0x8B, 0x91, 0x81, 0x01, 0x00, 0xD6, 0x86,
+        0xDE, 0xA8, 0x54, 0x00, 0x00, 0x00,
+    ];
+
+    /// HEADER compressed with zstd -19
+    #[cfg(feature = "zstd")]
+    const HEADER_ZST: &[u8] = &[
+        0x28, 0xB5, 0x2F, 0xFD, 0x24, 0x54, 0x5D, 0x02, 0x00, 0x22, 0xC4, 0x0F, 0x1B, 0xB0, 0xB9,
+        0x1A, 0xE0, 0x80, 0x53, 0x1E, 0x06, 0xE7, 0x11, 0x4C, 0xA4, 0x42, 0xDD, 0x04, 0x1D, 0xAA,
+        0x1F, 0x45, 0x23, 0x8E, 0x77, 0xED, 0x6E, 0xEE, 0x9D, 0x02, 0x9F, 0xE6, 0xEE, 0xFF, 0x8D,
+        0xB9, 0x4B, 0x2F, 0x76, 0x4B, 0xB3, 0x06, 0x39, 0x8B, 0x50, 0xC4, 0x88, 0x02, 0x70, 0x50,
+        0x2C, 0xF3, 0x0E, 0x9A, 0x87, 0xF8, 0xAF, 0xF2, 0x5F, 0xBF, 0x1A, 0xB9, 0xF7, 0x48, 0x02,
+        0x03, 0x00, 0x9B, 0x39, 0x9E, 0x9B, 0xA7, 0x05, 0x45, 0x95, 0x5A, 0x08, 0xCA,
+    ];
+
+    fn assert_source(mut source: Box<dyn CaptureSource>) {
+        assert_eq!(source.link_type(), DataLinkType::Ethernet);
+        let packets: Vec<_> = source.packets().map(Result::unwrap).collect();
+        assert_eq!(packets.len(), 1);
+        assert_eq!(packets[0].data, &HEADER[40..82]);
+    }
+
+    #[test]
+    fn detect_format() {
+        assert_eq!(Format::detect(HEADER), Some(Format::Snoop));
+        assert_eq!(
+            Format::detect(&[0xD4, 0xC3, 0xB2, 0xA1]),
+            Some(Format::Pcap)
+        );
+        assert_eq!(
+            Format::detect(&[0xA1, 0xB2, 0x3C, 0x4D]),
+            Some(Format::Pcap)
+        );
+        assert_eq!(
+            Format::detect(&[0x0A, 0x0D, 0x0D, 0x0A]),
+            Some(Format::Pcapng)
+        );
+        assert_eq!(Format::detect(&[0x1F, 0x8B, 0x08]), Some(Format::Gzip));
+        assert_eq!(
+            Format::detect(&[0x28, 0xB5, 0x2F, 0xFD]),
+            Some(Format::Zstd)
+        );
+        assert_eq!(Format::detect(&HEADER[..3]), None);
+    }
+
+    #[test]
+    fn detect_open() {
+        let path = std::env::temp_dir().join(format!("snoop-detect-{}.cap", std::process::id()));
+        fs::write(&path, HEADER).unwrap();
+        assert_source(snoop::open(&path).unwrap());
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn detect_pcap_formats() {
+        let mut pcap = Vec::new();
+        snoop_to_pcap(&mut Reader::new(HEADER).unwrap(), &mut pcap).unwrap();
+        assert_source(from_reader(std::io::Cursor::new(pcap)).unwrap());
+
+        let mut pcapng = Vec::new();
+        snoop_to_pcapng(&mut Reader::new(HEADER).unwrap(), &mut pcapng).unwrap();
+        assert_source(from_reader(std::io::Cursor::new(pcapng)).unwrap());
+    }
+
+    #[test]
+    #[cfg(feature = "gzip")]
+    fn detect_gzip() {
+        assert_source(from_reader(HEADER_GZ).unwrap());
+    }
+
+    #[test]
+    #[cfg(feature = "zstd")]
+    fn detect_zstd() {
+        assert_source(from_reader(HEADER_ZST).unwrap());
+    }
+
+    #[test]
+    fn detect_unknown() {
+        assert!(matches!(
+            from_reader(&HEADER[8..]),
+            Err(Error::InvalidFormat(_))
+        ));
+        assert!(matches!(from_reader(&[][..]), Err(Error::InvalidFormat(_))));
+    }
+}
@@ -0,0 +1,768 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use crate::common::HEADER;
+    use snoop::dissect::arp::{ARP_HARDWARE_ETHERNET, ARP_REPLY, ARP_REQUEST, RARP_REPLY};
+    use snoop::dissect::ethernet::{
+        LengthType, VlanTag, ETHERTYPE_ARP, ETHERTYPE_IPV4, ETHERTYPE_QINQ, ETHERTYPE_VLAN,
+    };
+    use snoop::dissect::hdlc::Slarp;
+    use snoop::dissect::inventory::inventory;
+    use snoop::dissect::ipv6::{IPPROTO_DSTOPTS, IPPROTO_FRAGMENT, IPPROTO_HOPOPTS};
+    use snoop::dissect::tcp::{TcpFlags, TcpOption};
+    use snoop::dissect::{
+        dissect, network, Checksum, Ipv4Packet, Ipv6Packet, LinkLayer, MacAddr, NetworkLayer,
+        TcpSegment, TransportLayer,
+    };
+    use snoop::error::Error;
+    use snoop::format::{DataLinkType, PacketHeader, SnoopPacket};
+    use snoop::read::Reader;
+    use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+    use std::time::Duration;
+
+    fn frame(tags: &[[u8; 4]], type_field: &[u8], payload: &[u8]) -> Vec<u8> {
+        let mut data = vec![0xFF; 6];
+        data.extend_from_slice(&[0x02, 0, 0, 0, 0, 1]);
+        for tag in tags {
+            data.extend_from_slice(tag);
+        }
+        data.extend_from_slice(type_field);
+        data.extend_from_slice(payload);
+        data
+    }
+
+    #[test]
+    fn ethernet_fixture() {
+        let mut reader = Reader::new(HEADER).unwrap();
+        let link_type = reader.header().link_type;
+        let packet = reader.read_ref().unwrap();
+        let LinkLayer::Ethernet(eth) = packet.dissect(link_type).unwrap() else {
+            panic!("no ethernet frame");
+        };
+        assert_eq!(
+            eth.destination(),
+            MacAddr([0x7c, 0x5a, 0x1c, 0x49, 0x3c, 0xd1])
+        );
+        assert_eq!(eth.source().to_string(), "1e:65:50:7f:b9:ca");
+        assert_eq!(eth.length_type(), LengthType::EtherType(ETHERTYPE_ARP));
+        assert_eq!(eth.ether_type(), Some(ETHERTYPE_ARP));
+        assert_eq!(eth.vlan_tags().count(), 0);
+        assert_eq!(eth.header().len(), 14);
+        assert_eq!(eth.payload().len(), 28);
+    }
+
+    #[test]
+    fn vlan_tags() {
+        let data = frame(
+            &[[0x88, 0xA8, 0x20, 0x64], [0x81, 0x00, 0x10, 0x0A]],
+            &[0x08, 0x00],
+            &[0x45, 0, 0, 20],
+        );
+        let LinkLayer::Ethernet(eth) = dissect(DataLinkType::Ethernet, &data).unwrap() else {
+            panic!("no ethernet frame");
+        };
+        assert!(eth.destination().is_broadcast());
+        assert!(eth.source().is_local());
+        let tags: Vec<VlanTag> = eth.vlan_tags().collect();
+        assert_eq!(
+            tags,
+            vec![
+                VlanTag {
+                    tpid: ETHERTYPE_QINQ,
+                    priority: 1,
+                    drop_eligible: false,
+                    id: 100
+                },
+                VlanTag {
+                    tpid: ETHERTYPE_VLAN,
+                    priority: 0,
+                    drop_eligible: true,
+                    id: 10
+                },
+            ]
+        );
+        assert_eq!(eth.ether_type(), Some(ETHERTYPE_IPV4));
+        assert_eq!(eth.header().len(), 22);
+        assert_eq!(eth.payload(), &[0x45, 0, 0, 20]);
+    }
+
+    #[test]
+    fn llc_snap() {
+        // 802.3 length 10 with SNAP header and ethertype IPv4, followed by padding
+        let data = frame(
+            &[],
+            &[0x00, 0x0A],
+            &[0xAA, 0xAA, 0x03, 0, 0, 0, 0x08, 0x00, 1, 2, 0, 0, 0, 0],
+        );
+        let LinkLayer::Ethernet(eth) = dissect(DataLinkType::IEEE8023, &data).unwrap() else {
+            panic!("no ethernet frame");
+        };
+        assert_eq!(eth.length_type(), LengthType::Length(10));
+        let llc = eth.llc().unwrap();
+        assert_eq!((llc.dsap(), llc.ssap(), llc.control()), (0xAA, 0xAA, 0x03));
+        assert_eq!(llc.snap().unwrap().protocol_id, ETHERTYPE_IPV4);
+        assert_eq!(eth.ether_type(), Some(ETHERTYPE_IPV4));
+        assert_eq!(eth.payload(), &[1, 2]);
+
+        // spanning tree bpdu without SNAP
+        let data = frame(&[], &[0x00, 0x05], &[0x42, 0x42, 0x03, 0, 0]);
+        let LinkLayer::Ethernet(eth) = dissect(DataLinkType::Ethernet, &data).unwrap() else {
+            panic!("no ethernet frame");
+        };
+        assert_eq!(eth.llc().unwrap().snap(), None);
+        assert_eq!(eth.ether_type(), None);
+        assert_eq!(eth.payload(), &[0, 0]);
+    }
+
+    #[test]
+    fn truncated() {
+        assert!(matches!(
+            dissect(DataLinkType::Ethernet, &[0; 10]),
+            Err(Error::Truncated("ethernet", 14, 10))
+        ));
+        let data = frame(&[[0x81, 0x00, 0, 1]], &[], &[]);
+        assert!(matches!(
+            dissect(DataLinkType::Ethernet, &data),
+            Err(Error::Truncated("vlan", 18, 16))
+        ));
+        let data = frame(&[], &[0x00, 0x08], &[0xAA, 0xAA, 0x03, 0]);
+        assert!(matches!(
+            dissect(DataLinkType::Ethernet, &data),
+            Err(Error::Truncated("llc", 8, 4))
+        ));
+        assert!(matches!(
+            dissect(DataLinkType::Other, &data),
+            Err(Error::UnsupportedLinkType(9))
+        ));
+    }
+
+    fn ipv4() -> Vec<u8> {
+        let mut data = vec![
+            0x46, 0xB8, 0x00, 0x24, 0x12, 0x34, 0x20, 0x03, 0x40, 0x11, 0x00, 0x00, 10, 0, 0, 1,
+            10, 0, 0, 2, // router alert option and end of list
+            0x94, 0x04, 0x00, 0x00,
+        ];
+        data.extend_from_slice(&[0xAB; 12]);
+        let sum = inet_checksum(&[&data[..24]]);
+        data[10..12].copy_from_slice(&sum.to_be_bytes());
+        data
+    }
+
+    fn inet_checksum(parts: &[&[u8]]) -> u16 {
+        let bytes: Vec<u8> = parts
+            .iter()
+            .flat_map(|p| {
+                let mut p = p.to_vec();
+                if p.len() % 2 == 1 {
+                    p.push(0);
+                }
+                p
+            })
+            .collect();
+        let mut sum = 0u32;
+        for word in bytes.chunks(2) {
+            sum += u32::from(u16::from_be_bytes([word[0], word[1]]));
+        }
+        while sum > 0xFFFF {
+            sum = (sum & 0xFFFF) + (sum >> 16);
+        }
+        !(sum as u16)
+    }
+
+    #[test]
+    fn ipv4_packet() {
+        let mut data = ipv4();
+        // ethernet padding after the total length
+        data.extend_from_slice(&[0; 6]);
+        let ip = Ipv4Packet::parse(&data).unwrap();
+        assert_eq!(ip.header_len(), 24);
+        assert_eq!((ip.dscp(), ip.ecn()), (46, 0));
+        assert_eq!(ip.total_length(), 36);
+        assert_eq!(ip.identification(), 0x1234);
+        assert!(!ip.dont_fragment());
+        assert!(ip.more_fragments());
+        assert_eq!(ip.fragment_offset(), 3);
+        assert!(ip.is_fragment());
+        assert_eq!((ip.ttl(), ip.protocol()), (64, 17));
+        assert!(ip.verify_checksum());
+        assert_eq!(ip.source(), Ipv4Addr::new(10, 0, 0, 1));
+        assert_eq!(ip.destination(), Ipv4Addr::new(10, 0, 0, 2));
+        let options: Vec<_> = ip.options().collect();
+        assert_eq!(options.len(), 1);
+        assert_eq!((options[0].kind, options[0].data), (0x94, &[0, 0][..]));
+        assert_eq!(ip.payload(), &[0xAB; 12]);
+        assert!(!ip.is_truncated());
+
+        data[12] = 11;
+        assert!(!Ipv4Packet::parse(&data).unwrap().verify_checksum());
+        let snapped = Ipv4Packet::parse(&data[..30]).unwrap();
+        assert!(snapped.is_truncated());
+        assert_eq!(snapped.payload().len(), 6);
+    }
+
+    #[test]
+    fn ipv4_invalid() {
+        let data = ipv4();
+        assert!(matches!(
+            Ipv4Packet::parse(&data[..22]),
+            Err(Error::Truncated("ipv4", 24, 22))
+        ));
+        let mut bad = data.clone();
+        bad[0] = 0x44;
+        assert!(matches!(
+            Ipv4Packet::parse(&bad),
+            Err(Error::InvalidPacket(_))
+        ));
+        let mut bad = data.clone();
+        bad[21] = 9;
+        assert!(matches!(
+            Ipv4Packet::parse(&bad),
+            Err(Error::InvalidPacket(_))
+        ));
+        let mut bad = data;
+        bad[0] = 0x65;
+        assert!(matches!(
+            Ipv4Packet::parse(&bad),
+            Err(Error::InvalidPacket(_))
+        ));
+    }
+
+    fn ipv6() -> Vec<u8> {
+        let mut data = vec![0x60, 0x12, 0x34, 0x56, 0x00, 0x20, IPPROTO_HOPOPTS, 0x40];
+        data.extend_from_slice(&Ipv6Addr::LOCALHOST.octets());
+        data.extend_from_slice(&"fe80::1".parse::<Ipv6Addr>().unwrap().octets());
+        // hop-by-hop with padding, fragment, destination options
+        data.extend_from_slice(&[IPPROTO_FRAGMENT, 0, 1, 4, 0, 0, 0, 0]);
+        data.extend_from_slice(&[IPPROTO_DSTOPTS, 0, 0x00, 0x51, 0, 0, 0, 7]);
+        data.extend_from_slice(&[17, 0, 1, 4, 0, 0, 0, 0]);
+        data.extend_from_slice(&[1, 2, 3, 4, 5, 6, 7, 8]);
+        data
+    }
+
+    #[test]
+    fn ipv6_packet() {
+        let data = ipv6();
+        let ip = Ipv6Packet::parse(&data).unwrap();
+        assert_eq!(ip.traffic_class(), 0x01);
+        assert_eq!(ip.flow_label(), 0x23456);
+        assert_eq!(ip.hop_limit(), 64);
+        assert_eq!(ip.source(), Ipv6Addr::LOCALHOST);
+        assert_eq!(ip.next_header(), IPPROTO_HOPOPTS);
+        assert_eq!(ip.protocol(), 17);
+        assert_eq!(ip.header_len(), 64);
+        let kinds: Vec<u8> = ip.extension_headers().map(|e| e.protocol).collect();
+        assert_eq!(
+            kinds,
+            vec![IPPROTO_HOPOPTS, IPPROTO_FRAGMENT, IPPROTO_DSTOPTS]
+        );
+        let fragment = ip.fragment().unwrap();
+        assert_eq!(fragment.offset, 10);
+        assert!(fragment.more_fragments);
+        assert_eq!(fragment.identification, 7);
+        assert_eq!(ip.payload(), &[1, 2, 3, 4, 5, 6, 7, 8]);
+        assert!(!ip.is_truncated());
+        assert!(Ipv6Packet::parse(&data[..70]).unwrap().is_truncated());
+        assert!(matches!(
+            Ipv6Packet::parse(&data[..60]),
+            Err(Error::Truncated("ipv6 extension", 8, 4))
+        ));
+    }
+
+    #[test]
+    fn network_layer() {
+        let mut data = frame(&[], &[0x86, 0xDD], &[]);
+        data.extend_from_slice(&ipv6());
+        let link = dissect(DataLinkType::Ethernet, &data).unwrap();
+        let Some(NetworkLayer::Ipv6(_)) = link.network().unwrap() else {
+            panic!("no ipv6 packet");
+        };
+        let data = ipv4();
+        let ip = network(ETHERTYPE_IPV4, &data).unwrap().unwrap();
+        assert_eq!(ip.source(), IpAddr::V4(Ipv4Addr::new(10, 0, 0, 1)));
+        assert_eq!(ip.protocol(), 17);
+        assert_eq!(network(ETHERTYPE_ARP, &[]).unwrap(), None);
+    }
+
+    fn ipv4_with(protocol: u8, payload: &[u8]) -> Vec<u8> {
+        let len = (20 + payload.len()) as u16;
+        let mut data = vec![0x45, 0, 0, 0, 0, 1, 0x40, 0, 64, protocol, 0, 0];
+        data[2..4].copy_from_slice(&len.to_be_bytes());
+        data.extend_from_slice(&[192, 168, 0, 1, 192, 168, 0, 2]);
+        let sum = inet_checksum(&[&data]);
+        data[10..12].copy_from_slice(&sum.to_be_bytes());
+        data.extend_from_slice(payload);
+        data
+    }
+
+    fn tcp_syn() -> Vec<u8> {
+        let mut tcp = vec![
+            0x30, 0x39, 0x00, 0x50, 0, 0, 0x10, 0, 0, 0, 0, 0, 0xA0, 0x02, 0xFF, 0xFF, 0, 0, 0, 0,
+            // mss 1460, sack permitted, timestamps, nop, window scale 7
+            2, 4, 0x05, 0xB4, 4, 2, 8, 10, 0, 0, 0, 1, 0, 0, 0, 0, 1, 3, 3, 7,
+        ];
+        tcp.extend_from_slice(b"hello");
+        let pseudo = [192, 168, 0, 1, 192, 168, 0, 2, 0, 6, 0, tcp.len() as u8];
+        let sum = inet_checksum(&[&pseudo, &tcp]);
+        tcp[16..18].copy_from_slice(&sum.to_be_bytes());
+        tcp
+    }
+
+    #[test]
+    fn tcp_segment() {
+        let data = ipv4_with(6, &tcp_syn());
+        let ip = network(ETHERTYPE_IPV4, &data).unwrap().unwrap();
+        let Some(transport @ TransportLayer::Tcp(tcp)) = ip.transport().unwrap() else {
+            panic!("no tcp segment");
+        };
+        assert_eq!((tcp.source_port(), tcp.destination_port()), (12345, 80));
+        assert_eq!(tcp.sequence(), 0x1000);
+        assert_eq!(tcp.header_len(), 40);
+        assert!(tcp.flags().contains(TcpFlags::SYN));
+        assert!(!tcp.flags().contains(TcpFlags::ACK));
+        assert_eq!(tcp.window(), 0xFFFF);
+        let options: Vec<TcpOption> = tcp.options().collect();
+        assert_eq!(
+            options,
+            vec![
+                TcpOption::Mss(1460),
+                TcpOption::SackPermitted,
+                TcpOption::Timestamps { value: 1, echo: 0 },
+                TcpOption::WindowScale(7),
+            ]
+        );
+        assert_eq!(tcp.payload(), b"hello");
+        assert_eq!(transport.verify_checksum(&ip), Checksum::Valid);
+
+        let mut bad = data.clone();
+        *bad.last_mut().unwrap() ^= 1;
+        let ip = network(ETHERTYPE_IPV4, &bad).unwrap().unwrap();
+        assert_eq!(
+            ip.transport().unwrap().unwrap().verify_checksum(&ip),
+            Checksum::Invalid
+        );
+
+        // cut off by the capture length, the checksum is not verifiable
+        let ip = network(ETHERTYPE_IPV4, &data[..data.len() - 2])
+            .unwrap()
+            .unwrap();
+        assert!(ip.is_truncated());
+        assert_eq!(
+            ip.transport().unwrap().unwrap().verify_checksum(&ip),
+            Checksum::Truncated
+        );
+    }
+
+    #[test]
+    fn tcp_sack() {
+        let mut tcp = vec![0; 20];
+        tcp[12] = 0x80;
+        tcp.extend_from_slice(&[1, 1, 5, 10, 0, 0, 0, 1, 0, 0, 0, 2]);
+        let segment = TcpSegment::parse(&tcp).unwrap();
+        let Some(TcpOption::Sack(blocks)) = segment.options().next() else {
+            panic!("no sack option");
+        };
+        assert_eq!(blocks.collect::<Vec<_>>(), vec![(1, 2)]);
+        tcp[23] = 14;
+        assert!(matches!(
+            TcpSegment::parse(&tcp),
+            Err(Error::InvalidPacket(_))
+        ));
+    }
+
+    #[test]
+    fn udp_over_ipv6() {
+        let mut data = vec![0x60, 0, 0, 0, 0, 12, 17, 0x40];
+        data.extend_from_slice(&Ipv6Addr::LOCALHOST.octets());
+        data.extend_from_slice(&Ipv6Addr::LOCALHOST.octets());
+        let mut udp = vec![0x00, 0x35, 0x13, 0x88, 0, 12, 0, 0, 1, 2, 3, 4];
+        let mut pseudo = data[8..40].to_vec();
+        pseudo.extend_from_slice(&[0, 0, 0, 12, 0, 0, 0, 17]);
+        let sum = inet_checksum(&[&pseudo, &udp]);
+        udp[6..8].copy_from_slice(&sum.to_be_bytes());
+        data.extend_from_slice(&udp);
+        let ip = network(0x86DD, &data).unwrap().unwrap();
+        let transport = ip.transport().unwrap().unwrap();
+        let TransportLayer::Udp(udp) = transport else {
+            panic!("no udp datagram");
+        };
+        assert_eq!(transport.source_port(), Some(53));
+        assert_eq!(udp.destination_port(), 5000);
+        assert_eq!(udp.length(), 12);
+        assert_eq!(udp.payload(), &[1, 2, 3, 4]);
+        assert_eq!(transport.verify_checksum(&ip), Checksum::Valid);
+    }
+
+    #[test]
+    fn udp_without_checksum() {
+        let data = ipv4_with(17, &[0, 1, 0, 2, 0, 9, 0, 0, 7]);
+        let ip = network(ETHERTYPE_IPV4, &data).unwrap().unwrap();
+        let transport = ip.transport().unwrap().unwrap();
+        assert_eq!(transport.payload(), &[7]);
+        assert_eq!(transport.verify_checksum(&ip), Checksum::Unset);
+    }
+
+    #[test]
+    fn udp_checksum_not_verifiable() {
+        let mut udp = vec![0, 1, 0, 2, 0, 12, 0, 0, 1, 2, 3, 4];
+        let pseudo = [192, 168, 0, 1, 192, 168, 0, 2, 0, 17, 0, 12];
+        let sum = inet_checksum(&[&pseudo, &udp]);
+        udp[6..8].copy_from_slice(&sum.to_be_bytes());
+        let data = ipv4_with(17, &udp);
+        let ip = network(ETHERTYPE_IPV4, &data).unwrap().unwrap();
+        assert_eq!(
+            ip.transport().unwrap().unwrap().verify_checksum(&ip),
+            Checksum::Valid
+        );
+
+        // 2 of 4 payload bytes captured, included length below the original length
+        let ip = network(ETHERTYPE_IPV4, &data[..data.len() - 2])
+            .unwrap()
+            .unwrap();
+        let transport = ip.transport().unwrap().unwrap();
+        assert_eq!(transport.payload(), &[1, 2]);
+        assert_eq!(transport.verify_checksum(&ip), Checksum::Truncated);
+
+        // first fragment with more fragments following
+        let mut fragment = data.clone();
+        fragment[6] = 0x20;
+        let ip = network(ETHERTYPE_IPV4, &fragment).unwrap().unwrap();
+        assert_eq!(
+            ip.transport().unwrap().unwrap().verify_checksum(&ip),
+            Checksum::Fragment
+        );
+    }
+
+    #[test]
+    fn icmp_echo() {
+        let mut icmp = vec![8, 0, 0, 0, 0x12, 0x34, 0, 1, b'p', b'i', b'n', b'g'];
+        let sum = inet_checksum(&[&icmp]);
+        icmp[2..4].copy_from_slice(&sum.to_be_bytes());
+        let data = ipv4_with(1, &icmp);
+        let ip = network(ETHERTYPE_IPV4, &data).unwrap().unwrap();
+        let transport = ip.transport().unwrap().unwrap();
+        let TransportLayer::Icmp(icmp) = transport else {
+            panic!("no icmp message");
+        };
+        assert!(!icmp.is_v6());
+        assert_eq!((icmp.icmp_type(), icmp.code()), (8, 0));
+        assert_eq!(icmp.echo(), Some((0x1234, 1)));
+        assert_eq!(icmp.payload(), b"ping");
+        assert_eq!(transport.source_port(), None);
+        assert_eq!(transport.verify_checksum(&ip), Checksum::Valid);
+        assert!(matches!(
+            network(ETHERTYPE_IPV4, &ipv4_with(1, &[8, 0, 0]))
+                .unwrap()
+                .unwrap()
+                .transport(),
+            Err(Error::Truncated("icmp", 8, 3))
+        ));
+    }
+
+    #[test]
+    fn later_fragment() {
+        let mut data = ipv4_with(6, &[0; 8]);
+        data[6] = 0x00;
+        data[7] = 0x10;
+        let ip = network(ETHERTYPE_IPV4, &data).unwrap().unwrap();
+        assert!(ip.is_fragment());
+        assert_eq!(ip.transport().unwrap(), None);
+    }
+
+    #[test]
+    fn arp_fixture() {
+        let mut reader = Reader::new(HEADER).unwrap();
+        let link_type = reader.header().link_type;
+        let packet = reader.read_ref().unwrap();
+        let link = packet.dissect(link_type).unwrap();
+        assert_eq!(link.network().unwrap(), None);
+        let arp = link.arp().unwrap().unwrap();
+        assert_eq!(arp.hardware_type(), ARP_HARDWARE_ETHERNET);
+        assert_eq!(arp.protocol_type(), ETHERTYPE_IPV4);
+        assert_eq!((arp.hardware_len(), arp.protocol_len()), (6, 4));
+        assert_eq!(arp.operation(), ARP_REQUEST);
+        assert!(!arp.is_rarp());
+        assert_eq!(
+            arp.sender_mac(),
+            Some(MacAddr([0x1e, 0x65, 0x50, 0x7f, 0xb9, 0xca]))
+        );
+        assert_eq!(arp.sender_ip(), Some(Ipv4Addr::new(10, 0, 51, 104)));
+        assert_eq!(arp.target_mac(), Some(MacAddr([0; 6])));
+        assert_eq!(arp.target_ip(), Some(Ipv4Addr::new(10, 0, 51, 1)));
+        assert!(!arp.is_gratuitous());
+        assert!(matches!(
+            snoop::dissect::ArpPacket::parse(&packet.data[14..30]),
+            Err(Error::Truncated("arp", 28, 16))
+        ));
+    }
+
+    fn arp_packet(
+        secs: u32,
+        op: u16,
+        sender: ([u8; 6], [u8; 4]),
+        target: ([u8; 6], [u8; 4]),
+    ) -> SnoopPacket {
+        let mut arp = vec![0, 1, 8, 0, 6, 4];
+        arp.extend_from_slice(&op.to_be_bytes());
+        arp.extend_from_slice(&sender.0);
+        arp.extend_from_slice(&sender.1);
+        arp.extend_from_slice(&target.0);
+        arp.extend_from_slice(&target.1);
+        let ether_type = if op > 2 { [0x80, 0x35] } else { [0x08, 0x06] };
+        let data = frame(&[], &ether_type, &arp);
+        SnoopPacket {
+            header: PacketHeader {
+                timestamp_seconds: secs,
+                ..Default::default()
+            },
+            data,
+        }
+    }
+
+    #[test]
+    fn arp_inventory() {
+        let a = [2, 0, 0, 0, 0, 0xA];
+        let b = [2, 0, 0, 0, 0, 0xB];
+        let c = [2, 0, 0, 0, 0, 0xC];
+        let packets = vec![
+            arp_packet(1, ARP_REQUEST, (a, [10, 0, 0, 1]), ([0; 6], [10, 0, 0, 2])),
+            arp_packet(2, ARP_REPLY, (b, [10, 0, 0, 2]), (a, [10, 0, 0, 1])),
+            // probe without sender address
+            arp_packet(3, ARP_REQUEST, (c, [0; 4]), ([0; 6], [10, 0, 0, 2])),
+            // spoofed gratuitous reply
+            arp_packet(4, ARP_REPLY, (c, [10, 0, 0, 2]), ([0xFF; 6], [10, 0, 0, 2])),
+            arp_packet(5, ARP_REQUEST, (a, [10, 0, 0, 1]), ([0; 6], [10, 0, 0, 3])),
+            arp_packet(6, RARP_REPLY, (b, [10, 0, 0, 2]), (c, [10, 0, 0, 9])),
+        ];
+        let inv = inventory(DataLinkType::Ethernet, packets.clone().into_iter().map(Ok)).unwrap();
+        assert_eq!(inv.bindings().count(), 4);
+
+        // invalid microseconds fall back to the seconds of the timestamp
+        let mut invalid = packets;
+        invalid[4].header.timestamp_microseconds = 1_000_000;
+        let inv_invalid = inventory(DataLinkType::Ethernet, invalid.into_iter().map(Ok)).unwrap();
+        assert_eq!(inv_invalid.bindings().count(), 4);
+        let first: Vec<_> = inv_invalid.macs(Ipv4Addr::new(10, 0, 0, 1)).collect();
+        assert_eq!(first[0].last_seen, Duration::from_secs(5));
+        assert_eq!(first[0].packets, 2);
+
+        let first: Vec<_> = inv.macs(Ipv4Addr::new(10, 0, 0, 1)).collect();
+        assert_eq!(first.len(), 1);
+        assert_eq!(first[0].mac, MacAddr(a));
+        assert_eq!(first[0].first_seen, Duration::from_secs(1));
+        assert_eq!(first[0].last_seen, Duration::from_secs(5));
+        assert_eq!(first[0].packets, 2);
+
+        let conflicts = inv.conflicts();
+        assert_eq!(conflicts.len(), 1);
+        assert_eq!(conflicts[0].ip, Ipv4Addr::new(10, 0, 0, 2));
+        let macs: Vec<MacAddr> = conflicts[0].bindings.iter().map(|b| b.mac).collect();
+        assert_eq!(macs, vec![MacAddr(b), MacAddr(c)]);
+        assert_eq!(conflicts[0].bindings[0].last_seen, Duration::from_secs(6));
+
+        let gratuitous: Vec<_> = inv.gratuitous().collect();
+        assert_eq!(gratuitous.len(), 1);
+        assert_eq!(gratuitous[0].mac, MacAddr(c));
+        assert_eq!(gratuitous[0].first_seen, Duration::from_secs(4));
+
+        // rarp reply assigns the target address
+        let ips: Vec<Ipv4Addr> = inv.ips(MacAddr(c)).map(|b| b.ip).collect();
+        assert_eq!(
+            ips,
+            vec![Ipv4Addr::new(10, 0, 0, 2), Ipv4Addr::new(10, 0, 0, 9)]
+        );
+    }
+
+    #[test]
+    fn inventory_fixture() {
+        let reader = Reader::new(HEADER).unwrap();
+        let link_type = reader.header().link_type;
+        let inv = inventory(link_type, reader).unwrap();
+        let bindings: Vec<_> = inv.bindings().collect();
+        assert_eq!(bindings.len(), 1);
+        assert_eq!(bindings[0].ip, Ipv4Addr::new(10, 0, 51, 104));
+        assert!(inv.conflicts().is_empty());
+    }
+
+    const SNAP_IPV4: [u8; 8] = [0xAA, 0xAA, 0x03, 0, 0, 0, 0x08, 0x00];
+
+    #[test]
+    fn token_ring() {
+        let mut data = vec![
+            0x10,
+            0x40,
+            0xC0,
+            0,
+            0,
+            0,
+            0,
+            1,
+            0x80 | 0x10,
+            0,
+            0x5A,
+            0,
+            0,
+            2,
+        ];
+        // routing information with two route designators
+        data.extend_from_slice(&[0x06, 0xB0, 0x00, 0x11, 0x00, 0x22]);
+        data.extend_from_slice(&SNAP_IPV4);
+        data.extend_from_slice(&ipv4());
+        let link = dissect(DataLinkType::TokenRing, &data).unwrap();
+        let LinkLayer::TokenRing(tr) = link else {
+            panic!("no token ring frame");
+        };
+        assert_eq!((tr.access_control(), tr.frame_control()), (0x10, 0x40));
+        assert!(tr.is_llc());
+        assert_eq!(tr.destination(), MacAddr([0xC0, 0, 0, 0, 0, 1]));
+        assert_eq!(tr.source(), MacAddr([0x10, 0, 0x5A, 0, 0, 2]));
+        let rif = tr.routing_information().unwrap();
+        assert_eq!(rif.len(), 2);
+        assert!(!rif.is_empty());
+        assert_eq!(rif.data().len(), 6);
+        assert_eq!(rif.broadcast(), 0);
+        assert!(rif.direction());
+        assert_eq!(rif.largest_frame(), 3);
+        assert_eq!(
+            rif.route_designators().collect::<Vec<_>>(),
+            vec![(1, 1), (2, 2)]
+        );
+        assert_eq!(tr.header().len(), 20);
+        assert_eq!(link.ether_type(), Some(ETHERTYPE_IPV4));
+        let Some(NetworkLayer::Ipv4(ip)) = link.network().unwrap() else {
+            panic!("no ipv4 packet");
+        };
+        assert_eq!(ip.source(), Ipv4Addr::new(10, 0, 0, 1));
+
+        // mac frame without llc
+        let mac = [
+            0x10, 0x00, 0xC0, 0, 0, 0, 0, 1, 0x10, 0, 0x5A, 0, 0, 2, 0, 4, 2, 0,
+        ];
+        let LinkLayer::TokenRing(tr) = dissect(DataLinkType::TokenRing, &mac).unwrap() else {
+            panic!("no token ring frame");
+        };
+        assert!(!tr.is_llc());
+        assert_eq!(tr.routing_information(), None);
+        assert_eq!(tr.payload(), &[0, 4, 2, 0]);
+
+        assert!(matches!(
+            dissect(DataLinkType::TokenRing, &data[..17]),
+            Err(Error::Truncated("token ring routing", 20, 17))
+        ));
+        let mut bad = data.clone();
+        bad[14] = 0x05;
+        assert!(matches!(
+            dissect(DataLinkType::TokenRing, &bad),
+            Err(Error::InvalidPacket(_))
+        ));
+    }
+
+    #[test]
+    fn fddi() {
+        let arp = &HEADER[16 + 24 + 14..16 + 24 + 42];
+        let mut data = vec![
+            0x50, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0, 0, 0xF8, 0, 0, 1,
+        ];
+        data.extend_from_slice(&[0xAA, 0xAA, 0x03, 0, 0, 0, 0x08, 0x06]);
+        data.extend_from_slice(arp);
+        let link = dissect(DataLinkType::Fddi, &data).unwrap();
+        let LinkLayer::Fddi(fddi) = link else {
+            panic!("no fddi frame");
+        };
+        assert_eq!(fddi.frame_control(), 0x50);
+        assert!(fddi.destination().is_broadcast());
+        assert_eq!(fddi.source(), MacAddr([0, 0, 0xF8, 0, 0, 1]));
+        assert_eq!(fddi.canonical_source(), MacAddr([0, 0, 0x1F, 0, 0, 0x80]));
+        assert!(fddi.canonical_destination().is_broadcast());
+        assert_eq!(fddi.llc().unwrap().dsap(), 0xAA);
+        assert_eq!(link.ether_type(), Some(ETHERTYPE_ARP));
+        let arp = link.arp().unwrap().unwrap();
+        assert_eq!(arp.sender_ip(), Some(Ipv4Addr::new(10, 0, 51, 104)));
+
+        // station management frame without llc
+        let smt = [0x41, 0, 0, 0, 0, 0, 1, 0, 0, 0, 0, 0, 2, 9];
+        let LinkLayer::Fddi(fddi) = dissect(DataLinkType::Fddi, &smt).unwrap() else {
+            panic!("no fddi frame");
+        };
+        assert!(!fddi.is_llc());
+        assert_eq!(fddi.payload(), &[9]);
+        assert!(matches!(
+            dissect(DataLinkType::Fddi, &data[..16]),
+            Err(Error::Truncated("llc", 8, 3))
+        ));
+    }
+
+    #[test]
+    fn hdlc() {
+        let mut data = vec![0x0F, 0x00, 0x08, 0x00];
+        data.extend_from_slice(&ipv4());
+        let link = dissect(DataLinkType::Hdlc, &data).unwrap();
+        let LinkLayer::Hdlc(hdlc) = link else {
+            panic!("no hdlc frame");
+        };
+        assert_eq!((hdlc.address(), hdlc.control()), (0x0F, 0));
+        assert!(!hdlc.is_broadcast());
+        assert_eq!(hdlc.protocol(), ETHERTYPE_IPV4);
+        assert_eq!(hdlc.header().len(), 4);
+        let transport = link.network().unwrap().unwrap().transport();
+        assert!(transport.is_ok());
+
+        data[0] = 0xFF;
+        assert!(matches!(
+            dissect(DataLinkType::Hdlc, &data),
+            Err(Error::InvalidPacket(_))
+        ));
+        assert!(matches!(
+            dissect(DataLinkType::Hdlc, &data[..3]),
+            Err(Error::Truncated("hdlc", 4, 3))
+        ));
+    }
+
+    #[test]
+    fn hdlc_slarp() {
+        let data = [
+            0x8F, 0x00, 0x80, 0x35, 0, 0, 0, 2, 0, 0, 0, 7, 0, 0, 0, 6, 0xFF, 0xFF, 0, 0, 0, 0,
+        ];
+        let link = dissect(DataLinkType::Hdlc, &data).unwrap();
+        assert_eq!(link.arp().unwrap(), None);
+        let LinkLayer::Hdlc(hdlc) = link else {
+            panic!("no hdlc frame");
+        };
+        assert!(hdlc.is_broadcast());
+        assert_eq!(
+            hdlc.slarp().unwrap(),
+            Some(Slarp::Keepalive {
+                sequence: 7,
+                peer_sequence: 6,
+                reliability: 0xFFFF
+            })
+        );
+        let inv = inventory(
+            DataLinkType::Hdlc,
+            [Ok(SnoopPacket {
+                header: PacketHeader::default(),
+                data: data.to_vec(),
+            })],
+        )
+        .unwrap();
+        assert_eq!(inv.bindings().count(), 0);
+
+        let reply = [
+            0x0F, 0, 0x80, 0x35, 0, 0, 0, 1, 10, 1, 1, 1, 255, 255, 255, 252, 0, 0, 0, 0, 0, 0,
+        ];
+        let LinkLayer::Hdlc(hdlc) = dissect(DataLinkType::Hdlc, &reply).unwrap() else {
+            panic!("no hdlc frame");
+        };
+        assert_eq!(
+            hdlc.slarp().unwrap(),
+            Some(Slarp::Reply {
+                address: Ipv4Addr::new(10, 1, 1, 1),
+                mask: Ipv4Addr::new(255, 255, 255, 252)
+            })
+        );
+        assert!(matches!(
+            Slarp::parse(&reply[4..12]),
+            Err(Error::Truncated("slarp", 18, 8))
+        ));
+    }
+}
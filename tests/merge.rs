@@ -0,0 +1,102 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use crate::common::{capture, capture_with};
+    use snoop::error::Error;
+    use snoop::format::{DataLinkType, SnoopPacket};
+    use snoop::merge::{merge, LinkTypePolicy, MergeOptions, Merger};
+    use std::io::Cursor;
+
+    fn times(m: Merger<Cursor<Vec<u8>>>) -> Vec<u32> {
+        m.map(|p| p.unwrap().header.timestamp_seconds).collect()
+    }
+
+    #[test]
+    fn merge_time_ordered() {
+        let m = merge(vec![
+            capture(DataLinkType::Ethernet, &[1, 4, 7]),
+            capture(DataLinkType::Ethernet, &[2, 5]),
+            capture(DataLinkType::Ethernet, &[0, 3, 6, 8]),
+        ])
+        .unwrap();
+        assert_eq!(times(m), vec![0, 1, 2, 3, 4, 5, 6, 7, 8]);
+    }
+
+    #[test]
+    fn merge_drops() {
+        let m = merge(vec![
+            capture_with(DataLinkType::Ethernet, &[1, 3], |i, h| {
+                h.cumulative_drops = u32::try_from(i).unwrap();
+            }),
+            capture_with(DataLinkType::Ethernet, &[2, 4], |i, h| {
+                h.cumulative_drops = 10 * u32::try_from(i).unwrap();
+            }),
+        ])
+        .unwrap();
+        let drops: Vec<u32> = m
+            .map(|p: Result<SnoopPacket, Error>| p.unwrap().header.cumulative_drops)
+            .collect();
+        assert_eq!(drops, vec![0, 0, 1, 11]);
+    }
+
+    #[test]
+    fn concatenate() {
+        let m = Merger::new(
+            vec![
+                capture(DataLinkType::Ethernet, &[5, 6]),
+                capture(DataLinkType::Ethernet, &[1, 2]),
+            ],
+            &MergeOptions {
+                concatenate: true,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        assert_eq!(times(m), vec![5, 6, 1, 2]);
+    }
+
+    #[test]
+    fn link_type_policy() {
+        let readers = || {
+            vec![
+                capture(DataLinkType::Ethernet, &[1]),
+                capture(DataLinkType::Fddi, &[2]),
+            ]
+        };
+        assert!(matches!(
+            merge(readers()),
+            Err(Error::LinkTypeMismatch(
+                DataLinkType::Ethernet,
+                DataLinkType::Fddi
+            ))
+        ));
+        let m = Merger::new(
+            readers(),
+            &MergeOptions {
+                link_type: LinkTypePolicy::First,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        assert_eq!(m.header().link_type, DataLinkType::Ethernet);
+        let m = Merger::new(
+            readers(),
+            &MergeOptions {
+                link_type: LinkTypePolicy::Use(DataLinkType::Fddi),
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        assert_eq!(m.header().link_type, DataLinkType::Fddi);
+        assert_eq!(times(m), vec![1, 2]);
+    }
+
+    #[test]
+    fn empty() {
+        assert!(matches!(
+            merge::<Cursor<Vec<u8>>>(vec![]),
+            Err(Error::InvalidFormat(_))
+        ));
+    }
+}
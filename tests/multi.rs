@@ -0,0 +1,59 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use crate::common::{capture, tmp_dir};
+    use snoop::capture::CaptureSource;
+    use snoop::error::Error;
+    use snoop::format::DataLinkType;
+    use snoop::multi::MultiReader;
+    use std::fs::{self, File};
+    use std::path::Path;
+
+    fn write(path: &Path, link_type: DataLinkType, ts: &[u32]) {
+        fs::write(path, capture(link_type, ts).into_inner().into_inner()).unwrap();
+    }
+
+    #[test]
+    fn directory() {
+        let dir = tmp_dir("multi-dir");
+        write(&dir.join("c.snoop"), DataLinkType::Ethernet, &[5]);
+        write(&dir.join("a.snoop"), DataLinkType::Ethernet, &[1, 2]);
+        write(&dir.join("b.snoop"), DataLinkType::Ethernet, &[]);
+        File::create(dir.join("b2.snoop")).unwrap();
+        write(&dir.join("b3.snoop"), DataLinkType::Ethernet, &[3, 4]);
+
+        let r = MultiReader::from_dir(&dir).unwrap();
+        assert_eq!(r.link_type(), DataLinkType::Ethernet);
+        assert_eq!(r.paths().len(), 5);
+        assert_eq!(r.path(4).unwrap(), dir.join("c.snoop"));
+        let packets: Vec<(usize, u32)> = r
+            .map(|p| {
+                let (file, p) = p.unwrap();
+                (file, p.header.timestamp_seconds)
+            })
+            .collect();
+        assert_eq!(packets, vec![(0, 1), (0, 2), (3, 3), (3, 4), (4, 5)]);
+    }
+
+    #[test]
+    fn incompatible() {
+        let dir = tmp_dir("multi-incompatible");
+        let (a, b, c) = (dir.join("a"), dir.join("b"), dir.join("c"));
+        write(&a, DataLinkType::Ethernet, &[1]);
+        write(&b, DataLinkType::Fddi, &[2]);
+        write(&c, DataLinkType::Ethernet, &[3]);
+        let mut r = MultiReader::new([&a, &b, &c]).unwrap();
+        assert_eq!(r.next().unwrap().unwrap().0, 0);
+        assert!(matches!(
+            r.next(),
+            Some(Err(Error::LinkTypeMismatch(
+                DataLinkType::Ethernet,
+                DataLinkType::Fddi
+            )))
+        ));
+        // continue with the next file
+        assert_eq!(r.next().unwrap().unwrap().0, 2);
+        assert!(r.next().is_none());
+    }
+}
@@ -0,0 +1,194 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use crate::common::{tmp_dir, HEADER};
+    use rayon::prelude::*;
+    use snoop::format::DataLinkType;
+    use snoop::parallel::{par_packets, ChunkOptions, ChunkedCapture, OrderedPackets};
+    use snoop::parse::Parser;
+    use snoop::read::Reader;
+    use snoop::write::Writer;
+    use std::fs::{self, File};
+    use std::io::BufReader;
+    use std::path::{Path, PathBuf};
+
+    fn files(dir: &Path, n: u32) -> Vec<PathBuf> {
+        let mut packet = Reader::new(BufReader::new(HEADER))
+            .unwrap()
+            .next()
+            .unwrap()
+            .unwrap();
+        (0..n)
+            .map(|f| {
+                let path = dir.join(format!("{f}.snoop"));
+                let mut w =
+                    Writer::new(File::create(&path).unwrap(), DataLinkType::Ethernet).unwrap();
+                for i in 0..f {
+                    packet.header.timestamp_seconds = f * 100 + i;
+                    w.write_packet(&packet).unwrap();
+                }
+                w.flush().unwrap();
+                path
+            })
+            .collect()
+    }
+
+    #[test]
+    fn unordered() {
+        let dir = tmp_dir("parallel-unordered");
+        let mut paths = files(&dir, 8);
+        paths.push(PathBuf::from("/nonexistent/snoop"));
+        let results: Vec<_> = par_packets(&paths).collect();
+        assert_eq!(results.iter().filter(|r| r.is_err()).count(), 1);
+        let mut ts: Vec<u32> = results
+            .into_iter()
+            .filter_map(Result::ok)
+            .map(|(_, p)| p.header.timestamp_seconds)
+            .collect();
+        ts.sort_unstable();
+        assert_eq!(ts.len(), 28);
+        assert_eq!(ts[0], 100);
+    }
+
+    #[test]
+    fn unordered_stop_at_error() {
+        let dir = tmp_dir("parallel-unordered-error");
+        let mut paths = files(&dir, 3);
+        // an invalid record followed by valid records
+        let mut data = HEADER[..16].to_vec();
+        let mut record = HEADER[16..].to_vec();
+        record[4..8].copy_from_slice(&u32::MAX.to_be_bytes());
+        data.extend_from_slice(&record);
+        for _ in 0..3 {
+            data.extend_from_slice(&HEADER[16..]);
+        }
+        let corrupt = dir.join("corrupt.snoop");
+        fs::write(&corrupt, data).unwrap();
+        paths.push(corrupt);
+        let results: Vec<_> = par_packets(&paths).collect();
+        assert_eq!(results.iter().filter(|r| r.is_err()).count(), 1);
+        let files: Vec<usize> = results
+            .into_iter()
+            .filter_map(Result::ok)
+            .map(|(file, _)| file)
+            .collect();
+        assert_eq!(files.len(), 3);
+        assert!(!files.contains(&3));
+    }
+
+    #[test]
+    fn ordered() {
+        let dir = tmp_dir("parallel-ordered");
+        let paths = files(&dir, 10);
+        for window in [1, 3, 16] {
+            let packets: Vec<(usize, u32)> = OrderedPackets::new(paths.clone(), window)
+                .map(|p| {
+                    let (file, p) = p.unwrap();
+                    (file, p.header.timestamp_seconds)
+                })
+                .collect();
+            let expected: Vec<(usize, u32)> = (0..10u32)
+                .flat_map(|f| (0..f).map(move |i| (f as usize, f * 100 + i)))
+                .collect();
+            assert_eq!(packets, expected);
+        }
+    }
+
+    // records with `pad(i)` pad bytes, ending at any alignment if the pads are not rounded
+    fn capture_padded(dir: &Path, pad: impl Fn(u32, u32) -> u32) -> (PathBuf, Vec<u8>) {
+        let path = dir.join("capture.snoop");
+        let mut packet = Reader::new(BufReader::new(HEADER))
+            .unwrap()
+            .next()
+            .unwrap()
+            .unwrap();
+        let mut w = Writer::new(File::create(&path).unwrap(), DataLinkType::Ethernet).unwrap();
+        for i in 0..2000u32 {
+            let len = (i * 37) % 1500 + 1;
+            packet.data = (0..len).map(|b| (b ^ i) as u8).collect();
+            packet.header.included_length = len;
+            packet.header.original_length = len;
+            packet.header.packet_record_length = 24 + len + pad(i, len);
+            packet.header.timestamp_seconds = i;
+            w.write_packet(&packet).unwrap();
+        }
+        w.flush().unwrap();
+        let data = fs::read(&path).unwrap();
+        (path, data)
+    }
+
+    fn capture(dir: &Path) -> (PathBuf, Vec<u8>) {
+        capture_padded(dir, |_, len| Parser::record_len(len) - 24 - len)
+    }
+
+    fn timestamps(
+        packets: impl Iterator<Item = Result<snoop::format::SnoopPacket, snoop::error::Error>>,
+    ) -> Vec<u32> {
+        packets
+            .map(|p| p.unwrap().header.timestamp_seconds)
+            .collect()
+    }
+
+    #[test]
+    fn chunked_slice() {
+        let dir = tmp_dir("parallel-slice");
+        let (_, data) = capture(&dir);
+        let options = ChunkOptions {
+            chunk_size: 64 * 1024,
+            ..Default::default()
+        };
+        let capture = ChunkedCapture::from_slice(&data, options).unwrap();
+        assert!(capture.chunks().len() > 10);
+        for pair in capture.chunks().windows(2) {
+            assert_eq!(pair[0].end, pair[1].start);
+        }
+        assert_eq!(capture.chunks().last().unwrap().end, data.len() as u64);
+        let expected: Vec<u32> = (0..2000).collect();
+        assert_eq!(timestamps(capture.packets()), expected);
+
+        let counts: Vec<usize> = capture.par_readers().map(|r| r.unwrap().count()).collect();
+        assert_eq!(counts.iter().sum::<usize>(), 2000);
+    }
+
+    #[test]
+    fn chunked_file() {
+        let dir = tmp_dir("parallel-file");
+        let (path, data) = capture(&dir);
+        let options = ChunkOptions {
+            chunk_size: 10_000,
+            chain: 4,
+        };
+        let file = ChunkedCapture::open(&path, options).unwrap();
+        let slice = ChunkedCapture::from_slice(&data, options).unwrap();
+        assert_eq!(file.chunks(), slice.chunks());
+        assert_eq!(file.header(), slice.header());
+        let expected: Vec<u32> = (0..2000).collect();
+        assert_eq!(timestamps(file.packets()), expected);
+    }
+
+    #[test]
+    fn chunked_unaligned() {
+        let dir = tmp_dir("parallel-unaligned");
+        let (_, data) = capture_padded(&dir, |i, _| i % 5);
+        assert_eq!(Reader::new(&data[..]).unwrap().count(), 2000);
+        let options = ChunkOptions {
+            chunk_size: 10_000,
+            ..Default::default()
+        };
+        let capture = ChunkedCapture::from_slice(&data, options).unwrap();
+        assert!(capture.chunks().len() > 10);
+        assert!(capture.chunks().iter().any(|c| c.start % 4 != 0));
+        let expected: Vec<u32> = (0..2000).collect();
+        assert_eq!(timestamps(capture.packets()), expected);
+    }
+
+    #[test]
+    fn chunked_small() {
+        let capture = ChunkedCapture::from_slice(HEADER, ChunkOptions::default()).unwrap();
+        assert_eq!(capture.chunks().len(), 1);
+        let sequential = Reader::new(HEADER).unwrap().count();
+        assert_eq!(capture.packets().count(), sequential);
+        assert!(ChunkedCapture::from_slice(&HEADER[..8], ChunkOptions::default()).is_err());
+    }
+}
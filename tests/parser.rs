@@ -132,4 +132,35 @@ mod tests {
             Err(Error::InvalidRecordLength)
         ));
     }
+
+    #[test]
+    fn parser_packet_header_truncated() {
+        // 42 of 1500 bytes captured, the record length is based on the included length
+        let mut h: [u8; 24] = [0; 24];
+        h.copy_from_slice(&HEADER[16..40]);
+        h[0..4].copy_from_slice(&1500u32.to_be_bytes());
+        let mut p = PacketHeader {
+            ..Default::default()
+        };
+        Parser::parse_packet_header(&h, &mut p).unwrap();
+        assert_eq!(p.original_length, 1500);
+        assert_eq!(p.included_length, 42);
+        assert_eq!(Parser::pad(&p), 2);
+        assert_eq!(Parser::data_len(&p), 44);
+
+        // record length too short for the included data
+        h[8..12].copy_from_slice(&65u32.to_be_bytes());
+        assert!(matches!(
+            Parser::parse_packet_header(&h, &mut p),
+            Err(Error::InvalidRecordLength)
+        ));
+    }
+
+    #[test]
+    fn parser_record_len() {
+        assert_eq!(Parser::record_len(0), 24);
+        assert_eq!(Parser::record_len(1), 28);
+        assert_eq!(Parser::record_len(4), 28);
+        assert_eq!(Parser::record_len(42), 68);
+    }
 }
@@ -0,0 +1,223 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use crate::common::HEADER;
+    use snoop::convert::pcapng::{
+        pcapng_to_snoop, snoop_to_pcapng, PcapngReader, ENHANCED_PACKET_BLOCK,
+        INTERFACE_DESCRIPTION_BLOCK, SECTION_HEADER_BLOCK, SIMPLE_PACKET_BLOCK,
+    };
+    use snoop::convert::{ConvertStats, LINKTYPE_ETHERNET, LINKTYPE_IEEE802_5};
+    use snoop::error::Error;
+    use snoop::format::{DataLinkType, SnoopPacket};
+    use snoop::read::Reader;
+    use snoop::write::Writer;
+
+    fn block(buf: &mut Vec<u8>, block_type: u32, body: &[u8]) {
+        let total = (body.len() + 12) as u32;
+        buf.extend_from_slice(&block_type.to_le_bytes());
+        buf.extend_from_slice(&total.to_le_bytes());
+        buf.extend_from_slice(body);
+        buf.extend_from_slice(&total.to_le_bytes());
+    }
+
+    fn section(buf: &mut Vec<u8>) {
+        block(
+            buf,
+            SECTION_HEADER_BLOCK,
+            &[
+                0x4D, 0x3C, 0x2B, 0x1A, 1, 0, 0, 0, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF,
+            ],
+        );
+    }
+
+    fn interface(buf: &mut Vec<u8>, linktype: u16, tsresol: u8) {
+        interface_snaplen(buf, linktype, 0, tsresol);
+    }
+
+    fn interface_snaplen(buf: &mut Vec<u8>, linktype: u16, snaplen: u32, tsresol: u8) {
+        let mut body = Vec::new();
+        body.extend_from_slice(&linktype.to_le_bytes());
+        body.extend_from_slice(&[0, 0]);
+        body.extend_from_slice(&snaplen.to_le_bytes());
+        body.extend_from_slice(&[9, 0, 1, 0, tsresol, 0, 0, 0, 0, 0, 0, 0]);
+        block(buf, INTERFACE_DESCRIPTION_BLOCK, &body);
+    }
+
+    fn packet(buf: &mut Vec<u8>, interface: u32, ts: u64, data: &[u8], drops: Option<u64>) {
+        let mut body = Vec::new();
+        body.extend_from_slice(&interface.to_le_bytes());
+        body.extend_from_slice(&((ts >> 32) as u32).to_le_bytes());
+        body.extend_from_slice(&(ts as u32).to_le_bytes());
+        body.extend_from_slice(&(data.len() as u32).to_le_bytes());
+        body.extend_from_slice(&(data.len() as u32).to_le_bytes());
+        body.extend_from_slice(data);
+        body.resize((body.len() + 3) & !3, 0);
+        if let Some(d) = drops {
+            body.extend_from_slice(&[4, 0, 8, 0]);
+            body.extend_from_slice(&d.to_le_bytes());
+            body.extend_from_slice(&[0, 0, 0, 0]);
+        }
+        block(buf, ENHANCED_PACKET_BLOCK, &body);
+    }
+
+    fn read_all(buf: &[u8]) -> Vec<SnoopPacket> {
+        Reader::new(buf).unwrap().map(Result::unwrap).collect()
+    }
+
+    #[test]
+    fn pcapng_round_trip() {
+        let mut pcapng = Vec::new();
+        let mut reader = Reader::new(HEADER).unwrap();
+        assert_eq!(snoop_to_pcapng(&mut reader, &mut pcapng).unwrap(), 1);
+
+        let mut r = PcapngReader::new(&pcapng[..]).unwrap();
+        let packet = r.read().unwrap();
+        assert_eq!(r.interfaces()[0].linktype, LINKTYPE_ETHERNET);
+        assert_eq!(r.interfaces()[0].tsresol, 6);
+        assert_eq!(packet.timestamp_seconds, 1556002892);
+        assert_eq!(packet.timestamp_nanoseconds, 831815000);
+        assert_eq!(packet.drops, None);
+
+        let (out, stats) = pcapng_to_snoop(&pcapng[..], |_| Ok(Vec::new())).unwrap();
+        assert_eq!(stats.packets, 1);
+        assert_eq!(out.len(), 1);
+        assert_eq!(HEADER, &out[0].1[..]);
+    }
+
+    #[test]
+    fn pcapng_drop_count() {
+        let mut snoop = Vec::new();
+        {
+            let mut writer = Writer::new(&mut snoop, DataLinkType::Ethernet).unwrap();
+            let mut packet = Reader::new(HEADER).unwrap().read().unwrap();
+            for drops in [0, 5, 5, 7] {
+                packet.header.cumulative_drops = drops;
+                writer.write_packet(&packet).unwrap();
+            }
+        }
+        let mut pcapng = Vec::new();
+        snoop_to_pcapng(&mut Reader::new(&snoop[..]).unwrap(), &mut pcapng).unwrap();
+        let drops: Vec<_> = PcapngReader::new(&pcapng[..])
+            .unwrap()
+            .map(|p| p.unwrap().drops)
+            .collect();
+        assert_eq!(drops, vec![None, Some(5), None, Some(2)]);
+
+        let (out, _) = pcapng_to_snoop(&pcapng[..], |_| Ok(Vec::new())).unwrap();
+        let drops: Vec<_> = read_all(&out[0].1)
+            .iter()
+            .map(|p| p.header.cumulative_drops)
+            .collect();
+        assert_eq!(drops, vec![0, 5, 5, 7]);
+    }
+
+    #[test]
+    fn pcapng_split_interfaces() {
+        let data = &HEADER[40..82];
+        let mut pcapng = Vec::new();
+        section(&mut pcapng);
+        interface(&mut pcapng, LINKTYPE_ETHERNET as u16, 9);
+        interface(&mut pcapng, LINKTYPE_IEEE802_5 as u16, 6);
+        packet(&mut pcapng, 0, 1_556_002_892_831_815_123, data, None);
+        packet(&mut pcapng, 1, 1_556_002_893_000_001, data, None);
+        packet(&mut pcapng, 0, 1_556_002_894_000_000_000, data, Some(3));
+
+        let mut created = Vec::new();
+        let (out, _) = pcapng_to_snoop(&pcapng[..], |link_type| {
+            created.push(link_type);
+            Ok(Vec::new())
+        })
+        .unwrap();
+        assert_eq!(
+            created,
+            vec![DataLinkType::Ethernet, DataLinkType::TokenRing]
+        );
+        let (ethernet, token_ring) = (&out[0].1, &out[1].1);
+
+        let r = Reader::new(&ethernet[..]).unwrap();
+        assert_eq!(r.header().link_type, DataLinkType::Ethernet);
+        let packets = read_all(ethernet);
+        assert_eq!(packets.len(), 2);
+        assert_eq!(packets[0].header.timestamp_microseconds, 831815);
+        assert_eq!(packets[1].header.timestamp_seconds, 1556002894);
+        assert_eq!(packets[1].header.cumulative_drops, 3);
+
+        let r = Reader::new(&token_ring[..]).unwrap();
+        assert_eq!(r.header().link_type, DataLinkType::TokenRing);
+        let packets = read_all(token_ring);
+        assert_eq!(packets[0].header.timestamp_seconds, 1556002893);
+        assert_eq!(packets[0].header.timestamp_microseconds, 1);
+    }
+
+    #[test]
+    fn pcapng_skip_unsupported_interface() {
+        let data = &HEADER[40..82];
+        let mut pcapng = Vec::new();
+        section(&mut pcapng);
+        interface(&mut pcapng, 147, 6);
+        interface(&mut pcapng, LINKTYPE_ETHERNET as u16, 6);
+        packet(&mut pcapng, 0, 1_556_002_892_000_000, data, None);
+        packet(&mut pcapng, 1, 1_556_002_892_831_815, data, None);
+        packet(&mut pcapng, 0, 1_556_002_893_000_000, data, None);
+
+        let (out, stats) = pcapng_to_snoop(&pcapng[..], |_| Ok(Vec::new())).unwrap();
+        assert_eq!(
+            stats,
+            ConvertStats {
+                packets: 1,
+                skipped: 2,
+                ..Default::default()
+            }
+        );
+        assert_eq!(out.len(), 1);
+        assert_eq!(HEADER, &out[0].1[..]);
+    }
+
+    #[test]
+    fn pcapng_simple_packet_no_snaplen() {
+        let data = &HEADER[40..82];
+        let mut pcapng = Vec::new();
+        section(&mut pcapng);
+        interface_snaplen(&mut pcapng, LINKTYPE_ETHERNET as u16, 0, 6);
+        let mut body = (data.len() as u32).to_le_bytes().to_vec();
+        body.extend_from_slice(data);
+        body.resize((body.len() + 3) & !3, 0);
+        block(&mut pcapng, SIMPLE_PACKET_BLOCK, &body);
+
+        let packet = PcapngReader::new(&pcapng[..]).unwrap().read().unwrap();
+        assert_eq!(packet.data, data);
+
+        let mut pcapng = Vec::new();
+        section(&mut pcapng);
+        interface_snaplen(&mut pcapng, LINKTYPE_ETHERNET as u16, 14, 6);
+        block(&mut pcapng, SIMPLE_PACKET_BLOCK, &body);
+        let packet = PcapngReader::new(&pcapng[..]).unwrap().read().unwrap();
+        assert_eq!(packet.data, &data[..14]);
+    }
+
+    #[test]
+    fn pcapng_invalid_tsresol() {
+        for tsresol in [20, 0x80 | 64] {
+            let mut pcapng = Vec::new();
+            section(&mut pcapng);
+            interface(&mut pcapng, LINKTYPE_ETHERNET as u16, tsresol);
+            assert!(matches!(
+                PcapngReader::new(&pcapng[..]),
+                Err(Error::InvalidFormat(_))
+            ));
+        }
+        let mut pcapng = Vec::new();
+        section(&mut pcapng);
+        interface(&mut pcapng, LINKTYPE_ETHERNET as u16, 19);
+        assert!(PcapngReader::new(&pcapng[..]).is_ok());
+    }
+
+    #[test]
+    fn pcapng_invalid() {
+        assert!(matches!(
+            PcapngReader::new(HEADER),
+            Err(Error::InvalidFormat(_))
+        ));
+    }
+}
@@ -34,4 +34,17 @@ mod tests {
         let packet = &i.unwrap();
         assert_eq!(&HEADER[40..(HEADER.len() - 2)], &packet.data[..]);
     }
+
+    #[test]
+    fn reader_truncated_record() {
+        // 42 of 1500 bytes captured
+        let mut buf = HEADER.to_vec();
+        buf[16..20].copy_from_slice(&1500u32.to_be_bytes());
+        let mut r = Reader::new(BufReader::new(&buf[..])).unwrap();
+        let packet = r.read().unwrap();
+        assert_eq!(packet.header.original_length, 1500);
+        assert_eq!(packet.header.included_length, 42);
+        assert_eq!(&HEADER[40..(HEADER.len() - 2)], &packet.data[..]);
+        assert!(matches!(r.read(), Err(Error::Eof)));
+    }
 }
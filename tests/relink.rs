@@ -0,0 +1,88 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use crate::common::HEADER;
+    use snoop::format::{DataLinkType, PacketHeader};
+    use snoop::read::Reader;
+    use snoop::relink::{encapsulate, relink, set_link_type, Encapsulation, RelinkStats};
+    use snoop::write::Writer;
+    use std::io::{BufReader, Cursor};
+
+    fn header(len: u32) -> PacketHeader {
+        PacketHeader {
+            original_length: len,
+            included_length: len,
+            packet_record_length: 24 + len,
+            ..Default::default()
+        }
+    }
+
+    fn ethernet(ethertype: [u8; 2], payload: &[u8]) -> Vec<u8> {
+        let mut frame = vec![0xAA; 12];
+        frame.extend_from_slice(&ethertype);
+        frame.extend_from_slice(payload);
+        frame
+    }
+
+    #[test]
+    fn override_link_type() {
+        let mut f = Cursor::new(HEADER.to_vec());
+        set_link_type(&mut f, DataLinkType::Fddi).unwrap();
+        let r = Reader::new(Cursor::new(f.into_inner())).unwrap();
+        assert_eq!(r.header().link_type, DataLinkType::Fddi);
+        assert_eq!(r.count(), 1);
+        assert!(set_link_type(&mut Cursor::new(vec![0u8; 16]), DataLinkType::Fddi).is_err());
+    }
+
+    #[test]
+    fn add_ethernet() {
+        let ip = [0x45, 0, 0, 20, 1, 2, 3, 4];
+        let enc = Encapsulation::AddEthernet {
+            destination: [1; 6],
+            source: [2; 6],
+        };
+        let (ph, data) = encapsulate(&header(8), &ip, enc).unwrap();
+        assert_eq!(ph.original_length, 22);
+        assert_eq!(ph.included_length, 22);
+        assert_eq!(ph.packet_record_length, 48);
+        assert_eq!(&data[..6], &[1; 6]);
+        assert_eq!(&data[12..14], &[0x08, 0x00]);
+        assert_eq!(&data[14..], &ip);
+        let (_, data) = encapsulate(&header(2), &[0x60, 0], enc).unwrap();
+        assert_eq!(&data[12..14], &[0x86, 0xDD]);
+        assert!(encapsulate(&header(2), &[0x00, 0], enc).is_none());
+    }
+
+    #[test]
+    fn strip_ethernet() {
+        let frame = ethernet([0x81, 0x00], &[0, 1, 0x08, 0x00, 0x45, 0, 0, 20]);
+        let (ph, data) = encapsulate(&header(60), &frame, Encapsulation::StripEthernet).unwrap();
+        assert_eq!(data, &[0x45, 0, 0, 20]);
+        assert_eq!(ph.original_length, 42);
+        assert_eq!(ph.included_length, 4);
+        assert_eq!(ph.packet_record_length, 28);
+    }
+
+    #[test]
+    fn relink_capture() {
+        // the packet in the header is an arp frame which has no ip payload
+        let mut r = Reader::new(BufReader::new(HEADER)).unwrap();
+        let mut w = Writer::new(Vec::new(), DataLinkType::Other).unwrap();
+        let stats = relink(&mut r, &mut w, Encapsulation::StripEthernet).unwrap();
+        assert_eq!(
+            stats,
+            RelinkStats {
+                packets: 0,
+                skipped: 1
+            }
+        );
+
+        let mut r = Reader::new(BufReader::new(HEADER)).unwrap();
+        let mut w = Writer::new(Vec::new(), DataLinkType::IEEE8023).unwrap();
+        relink(&mut r, &mut w, Encapsulation::Keep).unwrap();
+        let r = Reader::new(Cursor::new(w.into_inner())).unwrap();
+        assert_eq!(r.header().link_type, DataLinkType::IEEE8023);
+        assert_eq!(r.count(), 1);
+    }
+}
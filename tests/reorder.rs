@@ -0,0 +1,101 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use crate::common::{tmp_dir, HEADER};
+    use snoop::format::DataLinkType;
+    use snoop::read::Reader;
+    use snoop::reorder::{reorder, ReorderOptions, MIN_MAX_MEMORY};
+    use snoop::write::Writer;
+    use std::fs;
+    use std::io::{BufReader, Cursor};
+
+    const TIMES: [u32; 10] = [5, 3, 9, 1, 3, 7, 0, 8, 2, 6];
+
+    fn capture() -> Reader<Cursor<Vec<u8>>> {
+        let mut packet = Reader::new(BufReader::new(HEADER))
+            .unwrap()
+            .next()
+            .unwrap()
+            .unwrap();
+        let mut w = Writer::new(Vec::new(), DataLinkType::Ethernet).unwrap();
+        for (i, t) in TIMES.iter().enumerate() {
+            packet.header.timestamp_seconds = *t;
+            packet.header.cumulative_drops = u32::try_from(i).unwrap();
+            w.write_packet(&packet).unwrap();
+        }
+        Reader::new(Cursor::new(w.into_inner())).unwrap()
+    }
+
+    fn run(options: &ReorderOptions) -> Vec<(u32, u32)> {
+        let mut w = Writer::new(Vec::new(), DataLinkType::Ethernet).unwrap();
+        assert_eq!(reorder(&mut capture(), &mut w, options).unwrap(), 10);
+        Reader::new(Cursor::new(w.into_inner()))
+            .unwrap()
+            .map(|p| {
+                let p = p.unwrap();
+                (p.header.timestamp_seconds, p.header.cumulative_drops)
+            })
+            .collect()
+    }
+
+    fn expected() -> Vec<(u32, u32)> {
+        let mut v: Vec<(u32, u32)> = TIMES.iter().zip(0..).map(|(t, i)| (*t, i)).collect();
+        v.sort_by_key(|p| p.0);
+        v
+    }
+
+    #[test]
+    fn in_memory() {
+        assert_eq!(run(&ReorderOptions::default()), expected());
+        // a tiny limit is raised to the minimum
+        let options = ReorderOptions {
+            max_memory: 1,
+            ..Default::default()
+        };
+        assert_eq!(run(&options), expected());
+    }
+
+    #[test]
+    fn external() {
+        let dir = tmp_dir("reorder");
+        let mut packet = Reader::new(BufReader::new(HEADER))
+            .unwrap()
+            .next()
+            .unwrap()
+            .unwrap();
+        packet.data.resize(1000, 0);
+        packet.header.included_length = 1000;
+        packet.header.original_length = 1000;
+        packet.header.packet_record_length = 1024;
+        let mut w = Writer::new(Vec::new(), DataLinkType::Ethernet).unwrap();
+        let mut expected = Vec::new();
+        for i in 0..1200u32 {
+            packet.header.timestamp_seconds = i * 7919 % 500;
+            packet.header.cumulative_drops = i;
+            expected.push((packet.header.timestamp_seconds, i));
+            w.write_packet(&packet).unwrap();
+        }
+        expected.sort_by_key(|p| p.0);
+
+        // 19 runs of 64 KiB or 64 packets, merged 4 at a time in several passes
+        let options = ReorderOptions {
+            max_memory: MIN_MAX_MEMORY,
+            fan_in: 4,
+            temp_dir: Some(dir.to_path_buf()),
+        };
+        let mut r = Reader::new(Cursor::new(w.into_inner())).unwrap();
+        let mut w = Writer::new(Vec::new(), DataLinkType::Ethernet).unwrap();
+        assert_eq!(reorder(&mut r, &mut w, &options).unwrap(), 1200);
+        let sorted: Vec<(u32, u32)> = Reader::new(Cursor::new(w.into_inner()))
+            .unwrap()
+            .map(|p| {
+                let p = p.unwrap();
+                (p.header.timestamp_seconds, p.header.cumulative_drops)
+            })
+            .collect();
+        assert_eq!(sorted, expected);
+        // the runs are removed
+        assert_eq!(fs::read_dir(&dir).unwrap().count(), 0);
+    }
+}
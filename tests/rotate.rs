@@ -0,0 +1,174 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use crate::common::{tmp_dir, HEADER};
+    use snoop::error::Error;
+    use snoop::format::DataLinkType;
+    use snoop::read::Reader;
+    use snoop::rotate::{RotatingWriter, Rotation};
+    use std::fs::{self, File};
+    use std::io::BufReader;
+    use std::path::PathBuf;
+    use std::time::Duration;
+
+    fn write_packets(w: &mut RotatingWriter, n: u32, step: u32) {
+        let mut packet = Reader::new(BufReader::new(HEADER))
+            .unwrap()
+            .next()
+            .unwrap()
+            .unwrap();
+        for _ in 0..n {
+            w.write_packet(&packet).unwrap();
+            packet.header.timestamp_seconds += step;
+        }
+        w.flush().unwrap();
+    }
+
+    fn count(path: &std::path::Path) -> usize {
+        Reader::new(BufReader::new(File::open(path).unwrap()))
+            .unwrap()
+            .count()
+    }
+
+    #[test]
+    fn rotate_packets() {
+        let dir = tmp_dir("rotate-packets");
+        let template = dir.join("cap-%n.snoop").to_str().unwrap().to_string();
+        let mut w = RotatingWriter::new(
+            template,
+            DataLinkType::Ethernet,
+            Rotation {
+                max_packets: Some(2),
+                ..Default::default()
+            },
+        );
+        write_packets(&mut w, 5, 0);
+        let files: Vec<_> = w.files().map(PathBuf::from).collect();
+        assert_eq!(files.len(), 3);
+        assert_eq!(files[2], dir.join("cap-2.snoop"));
+        assert_eq!(count(&files[0]), 2);
+        assert_eq!(count(&files[2]), 1);
+    }
+
+    #[test]
+    fn rotate_bytes_ring() {
+        let dir = tmp_dir("rotate-bytes");
+        let template = dir.join("cap.snoop").to_str().unwrap().to_string();
+        let mut w = RotatingWriter::new(
+            template,
+            DataLinkType::Ethernet,
+            Rotation {
+                max_bytes: Some(16 + 68 * 3),
+                max_files: Some(2),
+                ..Default::default()
+            },
+        );
+        write_packets(&mut w, 10, 0);
+        let files: Vec<_> = w.files().map(PathBuf::from).collect();
+        assert_eq!(
+            files,
+            vec![dir.join("cap.snoop.2"), dir.join("cap.snoop.3")]
+        );
+        assert_eq!(fs::read_dir(&dir).unwrap().count(), 2);
+        assert_eq!(count(&files[0]), 3);
+        assert_eq!(count(&files[1]), 1);
+    }
+
+    #[test]
+    fn rotate_time_template() {
+        let dir = tmp_dir("rotate-time");
+        let template = dir
+            .join("%Y%m%d-%H%M%S.snoop")
+            .to_str()
+            .unwrap()
+            .to_string();
+        let mut w = RotatingWriter::new(
+            template,
+            DataLinkType::Ethernet,
+            Rotation {
+                max_duration: Some(Duration::from_secs(60)),
+                ..Default::default()
+            },
+        );
+        write_packets(&mut w, 4, 30);
+        let files: Vec<_> = w.files().map(PathBuf::from).collect();
+        assert_eq!(
+            files,
+            vec![
+                dir.join("20190423-070132.snoop"),
+                dir.join("20190423-070232.snoop")
+            ]
+        );
+        assert_eq!(count(&files[0]), 2);
+    }
+
+    #[test]
+    fn rotate_time_align() {
+        let dir = tmp_dir("rotate-align");
+        let template = dir.join("%H%M%S.snoop").to_str().unwrap().to_string();
+        let mut w = RotatingWriter::new(
+            template,
+            DataLinkType::Ethernet,
+            Rotation {
+                max_duration: Some(Duration::from_secs(60)),
+                align: true,
+                ..Default::default()
+            },
+        );
+        write_packets(&mut w, 4, 30);
+        let files: Vec<_> = w.files().map(PathBuf::from).collect();
+        assert_eq!(
+            files,
+            vec![
+                dir.join("070100.snoop"),
+                dir.join("070200.snoop"),
+                dir.join("070300.snoop")
+            ]
+        );
+        assert_eq!(count(&files[1]), 2);
+    }
+
+    #[test]
+    fn rotate_name_collision() {
+        let dir = tmp_dir("rotate-collision");
+        let template = dir.join("%S.snoop").to_str().unwrap().to_string();
+        let mut w = RotatingWriter::new(
+            template,
+            DataLinkType::Ethernet,
+            Rotation {
+                max_duration: Some(Duration::from_secs(30)),
+                ..Default::default()
+            },
+        );
+        write_packets(&mut w, 3, 30);
+        let files: Vec<_> = w.files().map(PathBuf::from).collect();
+        assert_eq!(
+            files,
+            vec![
+                dir.join("32.snoop"),
+                dir.join("02.snoop"),
+                dir.join("32.snoop.2")
+            ]
+        );
+        assert!(files.iter().all(|f| count(f) == 1));
+    }
+
+    #[test]
+    fn rotate_invalid_timestamp() {
+        let dir = tmp_dir("rotate-invalid");
+        let template = dir.join("cap-%n.snoop").to_str().unwrap().to_string();
+        let mut w = RotatingWriter::new(template, DataLinkType::Ethernet, Rotation::default());
+        let mut packet = Reader::new(BufReader::new(HEADER))
+            .unwrap()
+            .next()
+            .unwrap()
+            .unwrap();
+        packet.header.timestamp_microseconds = 1_000_000;
+        assert!(matches!(
+            w.write_packet(&packet),
+            Err(Error::InvalidMicroseconds(1_000_000))
+        ));
+        assert_eq!(w.files().count(), 0);
+    }
+}
@@ -0,0 +1,92 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use crate::common::HEADER;
+    use snoop::format::{DataLinkType, SnoopPacket};
+    use snoop::read::Reader;
+    use snoop::sample::{reservoir, sample, SampleStats, Sampler, Sampling};
+    use snoop::write::Writer;
+    use std::io::{BufReader, Cursor};
+
+    fn packets(n: u32) -> Vec<Result<SnoopPacket, snoop::error::Error>> {
+        let packet = Reader::new(BufReader::new(HEADER))
+            .unwrap()
+            .next()
+            .unwrap()
+            .unwrap();
+        (0..n)
+            .map(|i| {
+                let mut p = packet.clone();
+                p.header.timestamp_seconds = i;
+                Ok(p)
+            })
+            .collect()
+    }
+
+    fn seen(sample: impl Iterator<Item = SnoopPacket>) -> Vec<(u32, u32)> {
+        sample
+            .map(|p| (p.header.timestamp_seconds, p.header.cumulative_drops))
+            .collect()
+    }
+
+    #[test]
+    fn every() {
+        let s = Sampler::every(packets(10).into_iter(), 4).map(Result::unwrap);
+        assert_eq!(seen(s), vec![(0, 0), (4, 3), (8, 6)]);
+    }
+
+    #[test]
+    fn bernoulli() {
+        let run = |seed| {
+            seen(Sampler::bernoulli(packets(1000).into_iter(), 0.1, seed).map(Result::unwrap))
+        };
+        let a = run(7);
+        assert_eq!(a, run(7));
+        assert_ne!(a, run(8));
+        assert!(a.len() > 50 && a.len() < 150);
+        // drops count the skipped packets before each kept packet
+        for (i, (t, drops)) in a.iter().enumerate() {
+            assert_eq!(*drops as usize, *t as usize - i);
+        }
+        assert!(Sampler::bernoulli(packets(10).into_iter(), 0.0, 1)
+            .next()
+            .is_none());
+        assert_eq!(
+            Sampler::bernoulli(packets(10).into_iter(), 1.0, 1).count(),
+            10
+        );
+    }
+
+    #[test]
+    fn reservoir_sample() {
+        let s = reservoir(packets(100).into_iter(), 5, 42).unwrap();
+        assert_eq!(s.len(), 5);
+        let s = seen(s.into_iter());
+        assert!(s.windows(2).all(|w| w[0].0 < w[1].0));
+        for (i, (t, drops)) in s.iter().enumerate() {
+            assert_eq!(*drops as usize, *t as usize - i);
+        }
+        assert_eq!(reservoir(packets(3).into_iter(), 5, 42).unwrap().len(), 3);
+    }
+
+    #[test]
+    fn sample_capture() {
+        let mut w = Writer::new(Vec::new(), DataLinkType::Ethernet).unwrap();
+        for p in packets(10) {
+            w.write_packet(&p.unwrap()).unwrap();
+        }
+        let mut r = Reader::new(Cursor::new(w.into_inner())).unwrap();
+        let mut w = Writer::new(Vec::new(), DataLinkType::Ethernet).unwrap();
+        let stats = sample(&mut r, &mut w, Sampling::Reservoir { size: 4, seed: 1 }).unwrap();
+        assert_eq!(
+            stats,
+            SampleStats {
+                packets: 10,
+                written: 4
+            }
+        );
+        let r = Reader::new(Cursor::new(w.into_inner())).unwrap();
+        assert_eq!(r.count(), 4);
+    }
+}
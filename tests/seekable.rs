@@ -0,0 +1,150 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use crate::common::HEADER;
+    use snoop::decompress::Decompressor;
+    use snoop::format::{DataLinkType, SnoopPacket};
+    use snoop::read::Reader;
+    use snoop::seekable::{SeekableReader, SeekableWriter};
+    use std::io::{BufReader, Cursor, Read, Seek, SeekFrom};
+
+    fn packet() -> SnoopPacket {
+        Reader::new(BufReader::new(HEADER))
+            .unwrap()
+            .next()
+            .unwrap()
+            .unwrap()
+    }
+
+    fn write(n: u32, frame_records: usize) -> Vec<u8> {
+        let mut w = SeekableWriter::new(Vec::new(), DataLinkType::Ethernet, frame_records).unwrap();
+        let mut p = packet();
+        for i in 0..n {
+            p.header.timestamp_seconds = i;
+            w.write_packet(&p).unwrap();
+        }
+        w.finish().unwrap()
+    }
+
+    #[test]
+    fn frames() {
+        let buf = write(10, 4);
+        let r = SeekableReader::new(Cursor::new(buf)).unwrap();
+        // header frame + 4 + 4 + 2 records
+        assert_eq!(r.frames().len(), 4);
+        assert_eq!(r.frames()[0].decompressed_size, 16);
+        assert_eq!(r.frames()[1].decompressed_size, 4 * 68);
+        assert_eq!(r.frames()[3].decompressed_size, 2 * 68);
+        assert_eq!(r.len(), 16 + 10 * 68);
+        assert_eq!(r.header().link_type, DataLinkType::Ethernet);
+    }
+
+    #[test]
+    fn stream_compatible() {
+        let buf = write(10, 3);
+        let r = Reader::new(Decompressor::new(Cursor::new(buf)).unwrap()).unwrap();
+        let ts: Vec<u32> = r.map(|p| p.unwrap().header.timestamp_seconds).collect();
+        assert_eq!(ts, (0..10).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn seek_packet() {
+        let buf = write(10, 3);
+        for i in [0, 2, 3, 7, 9] {
+            let r = SeekableReader::new(Cursor::new(buf.clone())).unwrap();
+            let ts: Vec<u32> = r
+                .reader_at(i)
+                .unwrap()
+                .map(|p| p.unwrap().header.timestamp_seconds)
+                .collect();
+            assert_eq!(ts, (i as u32..10).collect::<Vec<_>>());
+        }
+        let mut r = SeekableReader::new(Cursor::new(buf)).unwrap();
+        assert!(matches!(r.seek_packet(10), Err(snoop::error::Error::Eof)));
+    }
+
+    #[test]
+    fn seek_packet_last_frame() {
+        let mut buf = write(10, 3);
+        let r = SeekableReader::new(Cursor::new(buf.clone())).unwrap();
+        // header frame + 3 + 3 + 3 + 1 records
+        let frames = r.frames().to_vec();
+        assert_eq!(frames.len(), 5);
+        // overwrite all data frames except the last, seeking into it must not decode them
+        let start = frames[0].compressed_size as usize;
+        let end: usize = frames[..4].iter().map(|f| f.compressed_size as usize).sum();
+        buf[start..end].fill(0xFF);
+        let ts: Vec<u32> = SeekableReader::new(Cursor::new(buf))
+            .unwrap()
+            .reader_at(9)
+            .unwrap()
+            .map(|p| p.unwrap().header.timestamp_seconds)
+            .collect();
+        assert_eq!(ts, vec![9]);
+    }
+
+    #[test]
+    fn read_seek() {
+        let buf = write(5, 2);
+        let mut plain = Vec::new();
+        Decompressor::new(Cursor::new(buf.clone()))
+            .unwrap()
+            .read_to_end(&mut plain)
+            .unwrap();
+        let mut r = SeekableReader::new(Cursor::new(buf)).unwrap();
+        let mut all = Vec::new();
+        r.seek(SeekFrom::Start(0)).unwrap();
+        r.read_to_end(&mut all).unwrap();
+        assert_eq!(all, plain);
+        let mut part = [0u8; 100];
+        r.seek(SeekFrom::Start(50)).unwrap();
+        r.read_exact(&mut part).unwrap();
+        assert_eq!(&part[..], &plain[50..150]);
+        r.seek(SeekFrom::End(-10)).unwrap();
+        let mut tail = Vec::new();
+        r.read_to_end(&mut tail).unwrap();
+        assert_eq!(tail, &plain[plain.len() - 10..]);
+    }
+
+    #[test]
+    fn corrupt_record_index() {
+        let mut buf = write(10, 3);
+        // header frame and 4 data frames, the record index and the table with 6 entries
+        let table = buf.len() - (8 + 6 * 8 + 9);
+        let index = table - (8 + 5 * 4);
+        // the first data frame claims 5 instead of 3 records
+        buf[index + 12..index + 16].copy_from_slice(&5u32.to_le_bytes());
+        let mut r = SeekableReader::new(Cursor::new(buf)).unwrap();
+        for i in [3, 4] {
+            assert!(matches!(
+                r.seek_packet(i),
+                Err(snoop::error::Error::UnexpectedEof(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn corrupt_frame_sizes() {
+        let buf = write(10, 3);
+        let entry = buf.len() - (6 * 8 + 9) + 8;
+        for (at, size) in [(entry + 4, u32::MAX), (entry, 0xFFFF_0000)] {
+            let mut buf = buf.clone();
+            buf[at..at + 4].copy_from_slice(&size.to_le_bytes());
+            assert!(matches!(
+                SeekableReader::new(Cursor::new(buf)),
+                Err(snoop::error::Error::InvalidFormat(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn missing_table() {
+        let mut buf = write(5, 2);
+        buf.truncate(buf.len() - 1);
+        assert!(matches!(
+            SeekableReader::new(Cursor::new(buf)),
+            Err(snoop::error::Error::InvalidFormat(_))
+        ));
+    }
+}
@@ -0,0 +1,110 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use crate::common::HEADER;
+    use snoop::error::Error;
+    use snoop::format::DataLinkType;
+    use snoop::read::Reader;
+    use snoop::slice::{slice, slice_indexed, Range, SeekIndex};
+    use snoop::write::Writer;
+    use std::io::{BufReader, Cursor};
+    use std::time::Duration;
+
+    // packets with timestamps 10..20 and one more drop per packet
+    fn capture() -> Vec<u8> {
+        let mut packet = Reader::new(BufReader::new(HEADER))
+            .unwrap()
+            .next()
+            .unwrap()
+            .unwrap();
+        let mut w = Writer::new(Vec::new(), DataLinkType::Ethernet).unwrap();
+        for i in 0..10 {
+            packet.header.timestamp_seconds = 10 + i;
+            packet.header.timestamp_microseconds = 500;
+            packet.header.cumulative_drops = i;
+            w.write_packet(&packet).unwrap();
+        }
+        w.into_inner()
+    }
+
+    fn packets(buf: Vec<u8>) -> Vec<(u32, u32)> {
+        Reader::new(Cursor::new(buf))
+            .unwrap()
+            .map(|p| {
+                let p = p.unwrap();
+                (p.header.timestamp_seconds, p.header.cumulative_drops)
+            })
+            .collect()
+    }
+
+    fn run(range: Range, index: Option<&SeekIndex>) -> Vec<(u32, u32)> {
+        let mut r = Reader::new(Cursor::new(capture())).unwrap();
+        let mut w = Writer::new(Vec::new(), DataLinkType::Ethernet).unwrap();
+        let n = match index {
+            Some(index) => slice_indexed(&mut r, index, &mut w, range).unwrap(),
+            None => slice(&mut r, &mut w, range).unwrap(),
+        };
+        let out = packets(w.into_inner());
+        assert_eq!(n, out.len() as u64);
+        out
+    }
+
+    #[test]
+    fn index_range() {
+        assert_eq!(
+            run(Range::Index(3, 6), None),
+            vec![(13, 0), (14, 1), (15, 2)]
+        );
+        assert_eq!(run(Range::Index(8, 100), None), vec![(18, 0), (19, 1)]);
+        assert!(run(Range::Index(10, 20), None).is_empty());
+    }
+
+    #[test]
+    fn time_range() {
+        let range = Range::Time(Duration::from_secs(12), Duration::new(14, 500_000));
+        assert_eq!(run(range, None), vec![(12, 0), (13, 1)]);
+    }
+
+    #[test]
+    fn seek_index() {
+        let index = SeekIndex::build(&mut Cursor::new(capture()), 4).unwrap();
+        let offsets: Vec<(u64, u64)> = index
+            .entries()
+            .iter()
+            .map(|e| (e.index, e.offset))
+            .collect();
+        assert_eq!(offsets, vec![(0, 16), (4, 16 + 4 * 68), (8, 16 + 8 * 68)]);
+        assert_eq!(index.lookup_index(6).unwrap().index, 4);
+        assert_eq!(index.lookup_time(Duration::from_secs(18)).unwrap().index, 4);
+
+        for range in [
+            Range::Index(0, 2),
+            Range::Index(5, 9),
+            Range::Index(8, 10),
+            Range::Time(Duration::from_secs(14), Duration::from_secs(17)),
+            Range::Time(Duration::new(18, 500_000), Duration::from_secs(30)),
+        ] {
+            assert_eq!(run(range, Some(&index)), run(range, None));
+        }
+    }
+
+    #[test]
+    fn invalid_timestamp() {
+        let mut buf = capture();
+        // microseconds of the third packet
+        let at = 16 + 2 * 68 + 20;
+        buf[at..at + 4].copy_from_slice(&1_000_000u32.to_be_bytes());
+        assert!(matches!(
+            SeekIndex::build(&mut Cursor::new(buf.clone()), 1),
+            Err(Error::InvalidMicroseconds(1_000_000))
+        ));
+        let range = Range::Time(Duration::from_secs(15), Duration::from_secs(20));
+        let mut r = Reader::new(Cursor::new(buf)).unwrap();
+        let mut w = Writer::new(Vec::new(), DataLinkType::Ethernet).unwrap();
+        assert!(matches!(
+            slice(&mut r, &mut w, range),
+            Err(Error::InvalidMicroseconds(1_000_000))
+        ));
+    }
+}
@@ -0,0 +1,46 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use crate::common::HEADER;
+    use snoop::format::DataLinkType;
+    use snoop::read::Reader;
+    use snoop::snaplen::{snaplen, truncate_packet};
+    use snoop::write::Writer;
+    use std::io::{BufReader, Cursor};
+
+    #[test]
+    fn truncate() {
+        let mut packet = Reader::new(BufReader::new(HEADER))
+            .unwrap()
+            .next()
+            .unwrap()
+            .unwrap();
+        truncate_packet(&mut packet, 13);
+        assert_eq!(packet.data.len(), 13);
+        assert_eq!(packet.header.included_length, 13);
+        assert_eq!(packet.header.original_length, 42);
+        assert_eq!(packet.header.packet_record_length, 40);
+        // larger snaplen keeps the packet
+        truncate_packet(&mut packet, 96);
+        assert_eq!(packet.data.len(), 13);
+    }
+
+    #[test]
+    fn rewrite() {
+        let mut r = Reader::new(BufReader::new(HEADER)).unwrap();
+        let mut w = Writer::new(Vec::new(), DataLinkType::Ethernet).unwrap();
+        assert_eq!(snaplen(&mut r, &mut w, 14).unwrap(), 1);
+        let buf = w.into_inner();
+        assert_eq!(buf.len(), 16 + 24 + 16);
+        let p = Reader::new(Cursor::new(buf))
+            .unwrap()
+            .next()
+            .unwrap()
+            .unwrap();
+        assert_eq!(p.data, &HEADER[40..54]);
+        assert_eq!(p.header.original_length, 42);
+        assert_eq!(p.header.included_length, 14);
+        assert_eq!(p.header.packet_record_length, 40);
+    }
+}
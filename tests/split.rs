@@ -0,0 +1,75 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use crate::common::{capture, tmp_dir};
+    use snoop::format::DataLinkType;
+    use snoop::read::Reader;
+    use snoop::split::{split_bytes, split_interval, split_packets};
+    use std::fs::{self, File};
+    use std::io::BufReader;
+    use std::path::Path;
+    use std::time::Duration;
+
+    fn times(path: &Path) -> Vec<u32> {
+        let r = Reader::new(BufReader::new(File::open(path).unwrap())).unwrap();
+        assert_eq!(r.header().link_type, DataLinkType::Ethernet);
+        r.map(|p| p.unwrap().header.timestamp_seconds).collect()
+    }
+
+    fn template(dir: &Path, t: &str) -> String {
+        dir.join(t).to_str().unwrap().to_string()
+    }
+
+    #[test]
+    fn by_packets() {
+        let dir = tmp_dir("split-packets");
+        let files = split_packets(
+            &mut capture(DataLinkType::Ethernet, &[1, 2, 3, 4, 5]),
+            template(&dir, "p-%n"),
+            2,
+        )
+        .unwrap();
+        assert_eq!(files.len(), 3);
+        assert_eq!(times(&files[0]), vec![1, 2]);
+        assert_eq!(times(&files[2]), vec![5]);
+    }
+
+    #[test]
+    fn by_interval_aligned() {
+        let dir = tmp_dir("split-interval");
+        let files = split_interval(
+            &mut capture(DataLinkType::Ethernet, &[3605, 3650, 3660, 3725, 3900]),
+            template(&dir, "i-%H%M%S"),
+            Duration::from_secs(60),
+        )
+        .unwrap();
+        assert_eq!(
+            files,
+            vec![
+                dir.join("i-010000"),
+                dir.join("i-010100"),
+                dir.join("i-010200"),
+                dir.join("i-010500"),
+            ]
+        );
+        assert_eq!(times(&files[0]), vec![3605, 3650]);
+        assert_eq!(times(&files[1]), vec![3660]);
+        assert_eq!(times(&files[3]), vec![3900]);
+    }
+
+    #[test]
+    fn by_bytes() {
+        let dir = tmp_dir("split-bytes");
+        // header 16 bytes + 2 records of 68 bytes
+        let files = split_bytes(
+            &mut capture(DataLinkType::Ethernet, &[1, 2, 3, 4, 5]),
+            template(&dir, "b-%n"),
+            152,
+        )
+        .unwrap();
+        assert_eq!(files.len(), 3);
+        assert_eq!(fs::metadata(&files[0]).unwrap().len(), 152);
+        assert_eq!(times(&files[1]), vec![3, 4]);
+    }
+}
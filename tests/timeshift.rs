@@ -0,0 +1,82 @@
+mod common;
+
+#[cfg(test)]
+mod tests {
+    use crate::common::HEADER;
+    use snoop::error::Error;
+    use snoop::format::{DataLinkType, PacketHeader};
+    use snoop::read::Reader;
+    use snoop::timeshift::{timeshift, ReferencePoint, Shift, TimeShift};
+    use snoop::write::Writer;
+    use std::io::{BufReader, Cursor};
+    use std::time::Duration;
+
+    fn header(secs: u32, micros: u32) -> PacketHeader {
+        PacketHeader {
+            timestamp_seconds: secs,
+            timestamp_microseconds: micros,
+            ..Default::default()
+        }
+    }
+
+    fn apply(ts: &mut TimeShift, secs: u32, micros: u32) -> Result<(u32, u32), Error> {
+        let mut ph = header(secs, micros);
+        ts.apply(&mut ph)?;
+        Ok((ph.timestamp_seconds, ph.timestamp_microseconds))
+    }
+
+    #[test]
+    fn offset_carry() {
+        let mut ts = TimeShift::new(Shift::Offset(600_000));
+        assert_eq!(apply(&mut ts, 10, 500_000).unwrap(), (11, 100_000));
+        let mut ts = TimeShift::new(Shift::Offset(-600_000));
+        assert_eq!(apply(&mut ts, 10, 500_000).unwrap(), (9, 900_000));
+    }
+
+    #[test]
+    fn out_of_range() {
+        let mut ts = TimeShift::new(Shift::Offset(-2_000_000));
+        assert!(matches!(apply(&mut ts, 1, 0), Err(Error::TimeEpoch)));
+        let mut ts = TimeShift::new(Shift::Offset(1_000_000));
+        assert!(matches!(apply(&mut ts, u32::MAX, 0), Err(Error::TimeEpoch)));
+    }
+
+    #[test]
+    fn start_at() {
+        let mut ts = TimeShift::new(Shift::StartAt(Duration::new(1000, 0)));
+        assert_eq!(apply(&mut ts, 50, 250_000).unwrap(), (1000, 0));
+        assert_eq!(apply(&mut ts, 51, 0).unwrap(), (1000, 750_000));
+    }
+
+    #[test]
+    fn drift() {
+        // the clock is 10 s behind at 100 and 20 s behind at 200
+        let mut ts = TimeShift::new(Shift::Drift(
+            ReferencePoint {
+                captured: Duration::from_secs(100),
+                actual: Duration::from_secs(110),
+            },
+            ReferencePoint {
+                captured: Duration::from_secs(200),
+                actual: Duration::from_secs(220),
+            },
+        ));
+        assert_eq!(apply(&mut ts, 100, 0).unwrap(), (110, 0));
+        assert_eq!(apply(&mut ts, 150, 0).unwrap(), (165, 0));
+        assert_eq!(apply(&mut ts, 300, 0).unwrap(), (330, 0));
+    }
+
+    #[test]
+    fn shift_capture() {
+        let mut r = Reader::new(BufReader::new(HEADER)).unwrap();
+        let mut w = Writer::new(Vec::new(), DataLinkType::Ethernet).unwrap();
+        assert_eq!(timeshift(&mut r, &mut w, Shift::Offset(-1)).unwrap(), 1);
+        let p = Reader::new(Cursor::new(w.into_inner()))
+            .unwrap()
+            .next()
+            .unwrap()
+            .unwrap();
+        assert_eq!(p.header.timestamp_seconds, 0x5CBE_B84C);
+        assert_eq!(p.header.timestamp_microseconds, 0x000C_B146);
+    }
+}
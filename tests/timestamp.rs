@@ -0,0 +1,104 @@
+#[cfg(test)]
+mod tests {
+    use snoop::error::Error;
+    use snoop::format::PacketHeader;
+    use snoop::timestamp::TimeFormat;
+    use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+    fn header(secs: u32, micros: u32) -> PacketHeader {
+        PacketHeader {
+            timestamp_seconds: secs,
+            timestamp_microseconds: micros,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn duration() {
+        let ph = header(1_556_003_916, 831_815);
+        assert_eq!(
+            ph.timestamp().unwrap(),
+            Duration::new(1_556_003_916, 831_815_000)
+        );
+        assert_eq!(Duration::try_from(&ph).unwrap(), ph.timestamp().unwrap());
+        let mut set = PacketHeader::default();
+        set.set_timestamp(Duration::new(1_556_003_916, 831_815_999))
+            .unwrap();
+        assert_eq!(set, ph);
+        assert!(matches!(
+            set.set_timestamp(Duration::from_secs(u64::from(u32::MAX) + 1)),
+            Err(Error::TimeEpoch)
+        ));
+    }
+
+    #[test]
+    fn invalid_micros() {
+        let ph = header(1, 1_000_000);
+        assert!(matches!(
+            ph.timestamp(),
+            Err(Error::InvalidMicroseconds(1_000_000))
+        ));
+        assert!(ph.system_time().is_err());
+        assert!(ph.format_timestamp(TimeFormat::Epoch).is_err());
+    }
+
+    #[test]
+    fn system_time() {
+        let ph = header(100, 5);
+        let t = SystemTime::try_from(&ph).unwrap();
+        assert_eq!(t, UNIX_EPOCH + Duration::from_micros(100_000_005));
+        let mut set = PacketHeader::default();
+        set.set_system_time(t).unwrap();
+        assert_eq!(set, ph);
+        assert!(matches!(
+            set.set_system_time(UNIX_EPOCH - Duration::from_secs(1)),
+            Err(Error::Time(_))
+        ));
+    }
+
+    #[test]
+    fn format() {
+        let ph = header(1_556_003_916, 831_815);
+        assert_eq!(
+            ph.format_timestamp(TimeFormat::Rfc3339).unwrap(),
+            "2019-04-23T07:18:36.831815Z"
+        );
+        assert_eq!(
+            ph.format_timestamp(TimeFormat::Epoch).unwrap(),
+            "1556003916.831815"
+        );
+        let first = Duration::new(1_556_003_915, 331_815_000);
+        assert_eq!(
+            ph.format_timestamp(TimeFormat::Relative(first)).unwrap(),
+            "1.500000"
+        );
+        let later = Duration::new(1_556_003_917, 0);
+        assert_eq!(
+            ph.format_timestamp(TimeFormat::Relative(later)).unwrap(),
+            "-0.168185"
+        );
+    }
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn chrono() {
+        let ph = header(1_556_003_916, 831_815);
+        let dt = ph.datetime().unwrap();
+        assert_eq!(dt.to_rfc3339(), "2019-04-23T07:18:36.831815+00:00");
+        let mut set = PacketHeader::default();
+        set.set_datetime(&dt).unwrap();
+        assert_eq!(set, ph);
+    }
+
+    #[cfg(feature = "time")]
+    #[test]
+    fn time() {
+        let ph = header(1_556_003_916, 831_815);
+        let dt = ph.offset_datetime().unwrap();
+        assert_eq!(dt.unix_timestamp(), 1_556_003_916);
+        assert_eq!(dt.microsecond(), 831_815);
+        let mut set = PacketHeader::default();
+        set.set_offset_datetime(dt).unwrap();
+        assert_eq!(set, ph);
+    }
+}
@@ -82,4 +82,25 @@ mod tests {
         assert_eq!(packet.header.cumulative_drops, 0);
         assert_eq!(&packet.data, &HEADER[40..]);
     }
+
+    #[test]
+    fn writer_truncated_record() {
+        let mut expected = HEADER.to_vec();
+        expected[16..20].copy_from_slice(&1500u32.to_be_bytes());
+        let mut packet = Reader::new(BufReader::new(HEADER)).unwrap().read().unwrap();
+        packet.header.original_length = 1500;
+
+        let mut buf = Vec::new();
+        let mut writer = Writer::new(&mut buf, DataLinkType::Ethernet).unwrap();
+        writer.write_packet(&packet).unwrap();
+        assert_eq!(expected, buf);
+
+        // record length too short for the included data
+        packet.header.packet_record_length = 24 + 41;
+        let mut writer = Writer::new(Vec::new(), DataLinkType::Ethernet).unwrap();
+        assert!(matches!(
+            writer.write_packet(&packet),
+            Err(Error::InvalidRecordLength)
+        ));
+    }
 }